@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE exercise_translation;
+
+ALTER TABLE users DROP COLUMN locale;
+
+ALTER TABLE exercise DROP COLUMN editorial;
+ALTER TABLE exercise DROP COLUMN hint;
@@ -0,0 +1,27 @@
+-- Add up migration script here
+ALTER TABLE exercise ADD COLUMN hint text;
+ALTER TABLE exercise ADD COLUMN editorial text;
+
+ALTER TABLE users ADD COLUMN locale text;
+
+-- texts of the exercise itself are in the default locale,
+-- translations override them field by field
+CREATE TABLE exercise_translation (
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+    locale text NOT NULL,
+
+    name text,
+    question text,
+    hint text,
+    editorial text,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (exercise_id, locale)
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON exercise_translation
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE exercise_schema DROP COLUMN dialect;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE exercise_schema
+ADD COLUMN dialect text NOT NULL DEFAULT 'postgres'
+CHECK (dialect IN ('postgres', 'sqlite'));
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX user_solution_exercise_fingerprint_idx;
+
+ALTER TABLE user_solution
+DROP COLUMN query_fingerprint;
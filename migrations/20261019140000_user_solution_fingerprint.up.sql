@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE user_solution
+ADD COLUMN query_fingerprint text;
+
+CREATE INDEX user_solution_exercise_fingerprint_idx ON user_solution (exercise_id, query_fingerprint);
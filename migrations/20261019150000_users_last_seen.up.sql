@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE users
+ADD COLUMN last_seen_at timestamp;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE exercise DROP COLUMN author_id;
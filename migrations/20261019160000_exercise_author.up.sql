@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE exercise
+ADD COLUMN author_id text REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,28 @@
+-- Add up migration script here
+CREATE TABLE cohort (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v7(),
+    name text NOT NULL,
+    teacher_id text NOT NULL REFERENCES users(id),
+
+    -- students join by following a link with this code
+    invite_code text NOT NULL UNIQUE DEFAULT replace(gen_random_uuid()::text, '-', ''),
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON cohort
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE TABLE cohort_member (
+    cohort_id uuid NOT NULL REFERENCES cohort(id) ON DELETE CASCADE,
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (cohort_id, user_id)
+);
+
+CREATE INDEX cohort_member_user_idx ON cohort_member (user_id);
@@ -0,0 +1,29 @@
+-- Add up migration script here
+CREATE TABLE assignment (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v7(),
+    cohort_id uuid NOT NULL REFERENCES cohort(id) ON DELETE CASCADE,
+    name text NOT NULL,
+
+    opens_at timestamp NOT NULL,
+    -- solutions submitted after this are late
+    due_at timestamp NOT NULL,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now(),
+
+    CHECK (opens_at < due_at)
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON assignment
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX assignment_cohort_idx ON assignment (cohort_id);
+
+CREATE TABLE assignment_exercise (
+    assignment_id uuid NOT NULL REFERENCES assignment(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (assignment_id, exercise_id)
+);
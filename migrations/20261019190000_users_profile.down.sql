@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE users
+DROP COLUMN email,
+DROP COLUMN display_name,
+DROP COLUMN display_name_custom;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE users
+ADD COLUMN email text,
+ADD COLUMN display_name text,
+-- once the user picks a name, logins stop overwriting it with the one from claims
+ADD COLUMN display_name_custom boolean NOT NULL DEFAULT false;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP MATERIALIZED VIEW leaderboard_stats;
+
+ALTER TABLE users DROP COLUMN leaderboard_opt_out;
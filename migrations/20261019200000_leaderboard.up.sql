@@ -0,0 +1,44 @@
+-- Add up migration script here
+ALTER TABLE users
+ADD COLUMN leaderboard_opt_out boolean NOT NULL DEFAULT false;
+
+-- Per user and track (exercise schema) totals, refreshed periodically by the app.
+--
+-- A solved exercise scores 10 points, minus one for every incorrect attempt before
+-- the first correct one, but at least 1 point.
+CREATE MATERIALIZED VIEW leaderboard_stats AS
+SELECT
+    attempts.user_id,
+    exercise.schema_id,
+    COUNT(*) FILTER (WHERE attempts.solved) AS solved,
+    COUNT(*) FILTER (WHERE attempts.first_try) AS first_try,
+    COALESCE(
+        SUM(GREATEST(10 - attempts.failed_before_solve, 1)) FILTER (WHERE attempts.solved),
+        0
+    ) AS score
+FROM (
+    SELECT
+        user_id,
+        exercise_id,
+        BOOL_OR(status = 'correct') AS solved,
+        (ARRAY_AGG(status ORDER BY created_at))[1] = 'correct' AS first_try,
+        COUNT(*) FILTER (WHERE created_at < first_solved_at) AS failed_before_solve
+    FROM (
+        SELECT
+            user_id,
+            exercise_id,
+            status,
+            created_at,
+            MIN(created_at) FILTER (WHERE status = 'correct')
+                OVER (PARTITION BY user_id, exercise_id) AS first_solved_at
+        FROM user_solution
+    ) AS solutions
+    GROUP BY user_id, exercise_id
+) AS attempts
+JOIN exercise ON
+    exercise.id = attempts.exercise_id
+    AND exercise.published_at IS NOT NULL
+GROUP BY attempts.user_id, exercise.schema_id;
+
+-- required by REFRESH MATERIALIZED VIEW CONCURRENTLY
+CREATE UNIQUE INDEX leaderboard_stats_user_schema_idx ON leaderboard_stats (user_id, schema_id);
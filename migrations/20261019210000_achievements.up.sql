@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- first time the user opened the hint of an exercise
+CREATE TABLE hint_view (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (user_id, exercise_id)
+);
+
+-- achievements are defined in code, this only records who earned them and when
+CREATE TABLE user_achievement (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    achievement text NOT NULL,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    -- set once the user has been shown the award
+    notified_at timestamp,
+
+    PRIMARY KEY (user_id, achievement)
+);
@@ -0,0 +1,10 @@
+-- Add down migration script here
+ALTER TABLE user_solution
+DROP CONSTRAINT user_solution_user_id_fkey,
+ADD CONSTRAINT user_solution_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users(id);
+
+ALTER TABLE cohort
+DROP CONSTRAINT cohort_teacher_id_fkey,
+ADD CONSTRAINT cohort_teacher_id_fkey
+    FOREIGN KEY (teacher_id) REFERENCES users(id);
@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- deleting a user (on their request) takes all of their data with it
+ALTER TABLE user_solution
+DROP CONSTRAINT user_solution_user_id_fkey,
+ADD CONSTRAINT user_solution_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE cohort
+DROP CONSTRAINT cohort_teacher_id_fkey,
+ADD CONSTRAINT cohort_teacher_id_fkey
+    FOREIGN KEY (teacher_id) REFERENCES users(id) ON DELETE CASCADE;
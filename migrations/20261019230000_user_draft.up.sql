@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- in-progress query autosaved from the editor, never graded
+CREATE TABLE user_draft (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+    query text NOT NULL,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (user_id, exercise_id)
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON user_draft
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE solution_vote;
+
+ALTER TABLE users
+DROP COLUMN share_solution_name;
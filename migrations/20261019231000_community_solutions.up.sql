@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- authors stay anonymous in community solutions unless they opt in
+ALTER TABLE users
+ADD COLUMN share_solution_name boolean NOT NULL DEFAULT false;
+
+-- upvotes are given to a cluster of correct queries sharing the fingerprint
+CREATE TABLE solution_vote (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+    fingerprint text NOT NULL,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (user_id, exercise_id, fingerprint)
+);
+
+CREATE INDEX solution_vote_exercise_fingerprint_idx ON solution_vote (exercise_id, fingerprint);
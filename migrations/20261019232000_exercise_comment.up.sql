@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE exercise_comment (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v7(),
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body text NOT NULL,
+
+    -- hidden by a moderator, only moderators still see it
+    hidden boolean NOT NULL DEFAULT false,
+    pinned boolean NOT NULL DEFAULT false,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON exercise_comment
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX exercise_comment_exercise_idx ON exercise_comment (exercise_id, created_at);
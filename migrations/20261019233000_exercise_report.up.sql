@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- problems with exercises reported by students
+CREATE TABLE exercise_report (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v7(),
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- what was in the editor when reporting
+    query text NOT NULL,
+    message text NOT NULL,
+    status text NOT NULL DEFAULT 'open'
+    CHECK (status IN ('open', 'resolved', 'wont_fix')),
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON exercise_report
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX exercise_report_status_idx ON exercise_report (status, created_at);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE exercise_note;
+DROP TABLE exercise_bookmark;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+CREATE TABLE exercise_bookmark (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (user_id, exercise_id)
+);
+
+-- private markdown notes of the user on the exercise
+CREATE TABLE exercise_note (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+    body text NOT NULL,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (user_id, exercise_id)
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON exercise_note
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
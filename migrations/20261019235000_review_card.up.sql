@@ -0,0 +1,31 @@
+-- Add up migration script here
+-- spaced repetition schedule of a solved exercise, see `review.rs`
+CREATE TABLE review_card (
+    user_id text NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    exercise_id uuid NOT NULL REFERENCES exercise(id) ON DELETE CASCADE,
+
+    ease double precision NOT NULL DEFAULT 2.5,
+    interval_days integer NOT NULL DEFAULT 1,
+    repetitions integer NOT NULL DEFAULT 1,
+    due_at timestamp NOT NULL DEFAULT now() + interval '1 day',
+    reviewed_at timestamp,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (user_id, exercise_id)
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON review_card
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+CREATE INDEX review_card_user_due_idx ON review_card (user_id, due_at);
+
+-- exercises solved so far are due a day after they were first solved
+INSERT INTO review_card (user_id, exercise_id, due_at, created_at)
+SELECT user_id, exercise_id, MIN(created_at) + interval '1 day', MIN(created_at)
+FROM user_solution
+WHERE status = 'correct'
+GROUP BY user_id, exercise_id;
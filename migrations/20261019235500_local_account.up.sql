@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- password logins of the local auth provider, the username is the user ID
+CREATE TABLE local_account (
+    user_id text PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- argon2 hash in the PHC string format
+    password_hash text NOT NULL,
+
+    created_at timestamp NOT NULL DEFAULT now(),
+    updated_at timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TRIGGER set_timestamp
+BEFORE UPDATE ON local_account
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
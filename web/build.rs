@@ -24,10 +24,22 @@ fn main() {
         "static/js/controllers/editor-controller.js",
         "editor_controller_HASH",
     );
+    process_file(
+        "static/js/controllers/hint-controller.js",
+        "hint_controller_HASH",
+    );
+    process_file(
+        "static/js/controllers/markdown-preview-controller.js",
+        "markdown_preview_controller_HASH",
+    );
     process_file(
         "static/js/controllers/mermaid-schema-vis-controller.js",
         "mermaid_schema_vis_controller_HASH",
     );
+    process_file(
+        "static/js/controllers/report-controller.js",
+        "report_controller_HASH",
+    );
     process_file(
         "static/js/controllers/schema-hidden-controller.js",
         "schema_hidden_controller_HASH",
@@ -36,6 +48,10 @@ fn main() {
         "static/js/controllers/solution-submit-controller.js",
         "solution_submit_controller_HASH",
     );
+    process_file(
+        "static/js/controllers/spoiler-controller.js",
+        "spoiler_controller_HASH",
+    );
     process_file(
         "static/js/controllers/sql-highlight-controller.js",
         "sql_highlight_controller_HASH",
@@ -0,0 +1,221 @@
+//! Badges users earn by solving exercises.
+//!
+//! Definitions live here, the DB only records who earned what and when (by [`Achievement::key`]).
+
+use std::ops::ControlFlow;
+
+use sqlparser::{
+    ast::{Expr, Visit, Visitor},
+    parser::Parser,
+};
+use time::{Date, Duration};
+
+use crate::models::exercise::SqlDialect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    FirstSolve,
+    TenSolves,
+    FirstTry,
+    WeekStreak,
+    WindowFunctions,
+    NoHints,
+}
+
+impl Achievement {
+    /// In the order they're shown on the profile page.
+    pub const ALL: &[Achievement] = &[
+        Achievement::FirstSolve,
+        Achievement::FirstTry,
+        Achievement::NoHints,
+        Achievement::TenSolves,
+        Achievement::WeekStreak,
+        Achievement::WindowFunctions,
+    ];
+
+    /// Stored in the DB, so it must never change.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Achievement::FirstSolve => "first_solve",
+            Achievement::TenSolves => "ten_solves",
+            Achievement::FirstTry => "first_try",
+            Achievement::WeekStreak => "week_streak",
+            Achievement::WindowFunctions => "window_functions",
+            Achievement::NoHints => "no_hints",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|achievement| achievement.key() == key)
+            .copied()
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::FirstSolve => "First Blood",
+            Achievement::TenSolves => "Getting Serious",
+            Achievement::FirstTry => "Sharpshooter",
+            Achievement::WeekStreak => "On a Roll",
+            Achievement::WindowFunctions => "Through the Window",
+            Achievement::NoHints => "Self-Reliant",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstSolve => "Solve your first exercise",
+            Achievement::TenSolves => "Solve 10 exercises",
+            Achievement::FirstTry => "Solve an exercise on the first try",
+            Achievement::WeekStreak => "Submit solutions 7 days in a row",
+            Achievement::WindowFunctions => "Solve all exercises about window functions",
+            Achievement::NoHints => "Solve an exercise with a hint without opening it",
+        }
+    }
+
+    /// Lucide icon name.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Achievement::FirstSolve => "flag",
+            Achievement::TenSolves => "medal",
+            Achievement::FirstTry => "target",
+            Achievement::WeekStreak => "flame",
+            Achievement::WindowFunctions => "app-window",
+            Achievement::NoHints => "brain",
+        }
+    }
+}
+
+/// Everything achievements are evaluated on, collected right after a submission.
+#[derive(Debug, Default)]
+pub struct AchievementFacts {
+    /// Exercises solved so far
+    pub solved: i64,
+    /// The submitted exercise was solved on the first attempt
+    pub first_try: bool,
+    /// The submitted exercise has a hint and was solved without opening it
+    pub solved_without_hint: bool,
+    /// Days in a row with submissions, up to today
+    pub streak: u32,
+    /// Every published exercise using window functions is solved (and there's at least one)
+    pub window_functions_solved: bool,
+}
+
+impl AchievementFacts {
+    pub fn earned(&self) -> Vec<Achievement> {
+        Achievement::ALL
+            .iter()
+            .copied()
+            .filter(|achievement| match achievement {
+                Achievement::FirstSolve => self.solved >= 1,
+                Achievement::TenSolves => self.solved >= 10,
+                Achievement::FirstTry => self.first_try,
+                Achievement::WeekStreak => self.streak >= 7,
+                Achievement::WindowFunctions => self.window_functions_solved,
+                Achievement::NoHints => self.solved_without_hint,
+            })
+            .collect()
+    }
+}
+
+/// Counts consecutive days with activity, ending today (or yesterday, so the streak
+/// isn't lost before the user had a chance to keep it up today).
+///
+/// `days` must be sorted.
+pub fn current_streak(days: &[Date], today: Date) -> u32 {
+    let mut expected = match days.last() {
+        Some(&last) if last == today || last == today - Duration::days(1) => last,
+        _ => return 0,
+    };
+
+    let mut streak = 0;
+
+    for &day in days.iter().rev() {
+        if day != expected {
+            break;
+        }
+
+        streak += 1;
+        expected = day - Duration::days(1);
+    }
+
+    streak
+}
+
+struct WindowFunctionFinder;
+
+impl Visitor for WindowFunctionFinder {
+    type Break = ();
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Function(function) if function.over.is_some() => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Checks if the query calls a function with an `OVER` clause.
+pub fn uses_window_function(query: &str, dialect: SqlDialect) -> bool {
+    Parser::parse_sql(dialect.parser_dialect(), query)
+        .map(|statements| Visit::visit(&statements, &mut WindowFunctionFinder).is_break())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn keys_roundtrip() {
+        for achievement in Achievement::ALL {
+            assert_eq!(Achievement::from_key(achievement.key()), Some(*achievement));
+        }
+        assert_eq!(Achievement::from_key("removed"), None);
+    }
+
+    #[test]
+    fn counts_streak() {
+        let today = date!(2025 - 01 - 12);
+        let days = [
+            date!(2025 - 01 - 05),
+            date!(2025 - 01 - 09),
+            date!(2025 - 01 - 10),
+            date!(2025 - 01 - 11),
+        ];
+
+        assert_eq!(current_streak(&days, today), 3);
+        assert_eq!(current_streak(&days, date!(2025 - 01 - 11)), 3);
+        assert_eq!(current_streak(&days, date!(2025 - 01 - 13)), 0);
+        assert_eq!(current_streak(&[], today), 0);
+    }
+
+    #[test]
+    fn evaluates_facts() {
+        let facts = AchievementFacts {
+            solved: 10,
+            streak: 6,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            facts.earned(),
+            [Achievement::FirstSolve, Achievement::TenSolves]
+        );
+    }
+
+    #[test]
+    fn finds_window_functions() {
+        assert!(uses_window_function(
+            "SELECT name, RANK() OVER (ORDER BY score DESC) FROM users",
+            SqlDialect::Postgres
+        ));
+        assert!(!uses_window_function(
+            "SELECT name, COUNT(*) FROM users GROUP BY name",
+            SqlDialect::Sqlite
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+//! Authentication backends, picked with the `AUTH_PROVIDER` setting.
+//!
+//! Every provider keeps a token in a session cookie, turns it back into [`UserClaims`]
+//! for the extractors, and brings its own login flow under `/auth/`. The rest of the app
+//! only deals with the claims and never knows which provider signed the user in.
+
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    extract::State,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+
+use crate::{
+    config::Config, extractors::AuthExtractorError, models::user::UserClaims, state::AppState,
+};
+
+mod corbado;
+mod local;
+mod oidc;
+mod rate_limit;
+mod session;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProviderKind {
+    /// Passkeys through Corbado, which issues the session tokens itself
+    #[default]
+    Corbado,
+    /// Any OpenID Connect issuer, using the authorization code flow with PKCE
+    Oidc,
+    /// Usernames and passwords stored in the app, hashed with argon2
+    Local,
+}
+
+pub trait AuthProvider: Send + Sync {
+    /// Name of the cookie the session token is kept in.
+    fn session_cookie(&self) -> &'static str;
+
+    /// Checks the signature and the issuer of the session token, returning its claims.
+    ///
+    /// Expiration is checked by the extractor, the same way for every provider.
+    fn validate(&self, token: &str) -> Result<UserClaims, AuthExtractorError>;
+
+    /// Login flow, nested under `/auth/`, `/auth/login/` is where unauthenticated users land.
+    fn routes(&self) -> Router<AppState>;
+
+    /// Adds the layers the provider needs on every route.
+    fn layer(&self, app: Router<AppState>) -> Router<AppState> {
+        app
+    }
+
+    /// Starts the background work of the provider, i.e. refreshing the signing keys.
+    fn spawn_background_tasks(&self) {}
+}
+
+/// Signs the user out by dropping the session cookie, whichever provider issued it.
+#[tracing::instrument(skip_all)]
+pub async fn logout(State(state): State<AppState>, cookies: CookieJar) -> impl IntoResponse {
+    let cookies = cookies.remove(Cookie::build(state.auth().session_cookie()).path("/"));
+
+    (cookies, Redirect::to("/auth/login/"))
+}
+
+/// Sets up the provider picked in the config, failing if its settings are missing.
+pub async fn from_config(config: &Config) -> Result<Arc<dyn AuthProvider>> {
+    let provider: Arc<dyn AuthProvider> = match config.auth_provider() {
+        AuthProviderKind::Corbado => Arc::new(
+            corbado::CorbadoProvider::new(config).wrap_err("Failed to set up Corbado auth")?,
+        ),
+        AuthProviderKind::Oidc => Arc::new(
+            oidc::OidcProvider::discover(config)
+                .await
+                .wrap_err("Failed to set up OpenID Connect auth")?,
+        ),
+        AuthProviderKind::Local => Arc::new(local::LocalProvider::new(config)),
+    };
+
+    Ok(provider)
+}
@@ -0,0 +1,182 @@
+//! Passkey logins through Corbado: its widget signs users in and keeps the session cookie
+//! fresh, the app only checks the tokens against the keys Corbado publishes.
+
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    body::Body,
+    debug_handler,
+    extract::State,
+    http::Request,
+    middleware::{self, Next},
+    response::{IntoResponse, Redirect},
+    routing,
+};
+use axum_extra::extract::cookie::{self, Cookie, CookieJar};
+use axum_jwt_auth::{JwtDecoder, RemoteJwksDecoder, RemoteJwksDecoderBuilder};
+use eyre::{OptionExt, WrapErr, eyre};
+use jsonwebtoken::{Algorithm, Validation};
+use maud::{Markup, html};
+use time::Duration;
+use tracing::error;
+
+use super::AuthProvider;
+use crate::{
+    config::Config,
+    db::user,
+    error::Result,
+    extractors::AuthExtractorError,
+    models::user::UserClaims,
+    partials::{AuthState, app_layout, page},
+    state::AppState,
+    static_files::corbado_login,
+};
+
+const SESSION_COOKIE: &str = "cbo_session_token";
+
+/// Read by `corbado-init.js`, which only loads Corbado when it's set.
+const PROJECT_ID_COOKIE: &str = "corbado_project_id";
+
+pub struct CorbadoProvider {
+    host: String,
+    project_id: String,
+    jwks_decoder: Arc<RemoteJwksDecoder>,
+}
+
+impl CorbadoProvider {
+    pub fn new(config: &Config) -> eyre::Result<Self> {
+        let host = config
+            .corbado_host()
+            .clone()
+            .ok_or_eyre("CORBADO_HOST is not set")?;
+        let project_id = config
+            .corbado_project_id()
+            .clone()
+            .ok_or_eyre("CORBADO_PROJECT_ID is not set")?;
+
+        let jwks_url = url::Url::parse(&host)
+            .wrap_err("Failed to parse corbado host")?
+            .join("/.well-known/jwks")
+            .wrap_err("Failed to join corbado host and jwks path")?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.algorithms = vec![Algorithm::RS256, Algorithm::RS384, Algorithm::RS512];
+
+        let jwks_decoder = RemoteJwksDecoderBuilder::default()
+            .jwks_url(jwks_url.to_string())
+            .validation(validation)
+            .config(Default::default())
+            .keys_cache(Default::default())
+            .client(Default::default())
+            .build()
+            .map_err(|error| eyre!("Failed to build JWKS decoder: {error}"))?;
+
+        Ok(Self {
+            host,
+            project_id,
+            jwks_decoder: Arc::new(jwks_decoder),
+        })
+    }
+}
+
+impl AuthProvider for CorbadoProvider {
+    fn session_cookie(&self) -> &'static str {
+        SESSION_COOKIE
+    }
+
+    fn validate(&self, token: &str) -> Result<UserClaims, AuthExtractorError> {
+        let claims: UserClaims = self
+            .jwks_decoder
+            .decode(token)
+            .map_err(AuthExtractorError::JWTValidationError)
+            .map(|token_data| token_data.claims)?;
+
+        if claims.iss() != &self.host {
+            error!("Invalid issuer: {}", claims.iss());
+
+            return Err(AuthExtractorError::InvalidIssuer);
+        }
+
+        Ok(claims)
+    }
+
+    fn routes(&self) -> Router<AppState> {
+        Router::new()
+            .route("/login/", routing::get(login))
+            .route("/callback/", routing::get(after_login))
+    }
+
+    fn layer(&self, app: Router<AppState>) -> Router<AppState> {
+        app.layer(middleware::from_fn_with_state(
+            self.project_id.clone(),
+            add_project_id,
+        ))
+    }
+
+    fn spawn_background_tasks(&self) {
+        let jwks_decoder = self.jwks_decoder.clone();
+
+        tokio::spawn(async move { jwks_decoder.refresh_keys_periodically().await });
+    }
+}
+
+/// Middleware to add the Corbado project ID to cookies, if it's not already set.
+async fn add_project_id(
+    State(project_id): State<String>,
+    cookies: CookieJar,
+    request: Request<Body>,
+    next: Next,
+) -> impl IntoResponse {
+    let response = next.run(request).await;
+
+    if let Some(cookie) = cookies.get(PROJECT_ID_COOKIE)
+        && cookie.value() == project_id
+    {
+        return response.into_response();
+    }
+
+    let cookies = {
+        let mut cookie = Cookie::new(PROJECT_ID_COOKIE, project_id);
+        cookie.set_same_site(cookie::SameSite::Lax);
+        cookie.set_path("/");
+        cookie.set_max_age(Duration::days(365));
+
+        cookies.add(cookie)
+    };
+
+    (cookies, response).into_response()
+}
+
+#[debug_handler]
+#[tracing::instrument]
+async fn login() -> Markup {
+    let inner = app_layout(
+        html! {
+            script src={"/static/" (corbado_login.name)} {}
+            div #corbado-auth {}
+        },
+        "Login",
+        AuthState::Unauthenticated,
+    );
+
+    page("Login", inner)
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all, fields(sub = %claims.sub()))]
+async fn after_login(
+    State(state): State<AppState>,
+    claims: UserClaims,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    // creates the user on the first login, refreshes email and name on the later ones
+    user::save_user(&mut conn, &claims).await?;
+
+    Ok(Redirect::to("/"))
+}
@@ -0,0 +1,427 @@
+//! Usernames and passwords kept in the app itself, for deployments without an identity
+//! provider. Passwords are hashed with argon2, the username doubles as the user ID.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+};
+use axum::{
+    Extension, Router, debug_handler,
+    extract::{ConnectInfo, Form, State},
+    http::{self, HeaderMap},
+    response::{IntoResponse, Redirect},
+    routing,
+};
+use axum_extra::extract::cookie::CookieJar;
+use eyre::{OptionExt, WrapErr, eyre};
+use lazy_static::lazy_static;
+use maud::{Markup, html};
+use rand::rngs::OsRng;
+use serde::Deserialize;
+
+use super::{
+    AuthProvider,
+    rate_limit::{RateLimiter, client_address},
+    session::{SESSION_COOKIE, SESSION_LIFETIME, SessionKeys, session_cookie},
+};
+use crate::{
+    config::Config,
+    db::{local_account, user},
+    error::Result,
+    extractors::AuthExtractorError,
+    models::user::UserClaims,
+    partials::{AuthState, app_layout, page},
+    state::AppState,
+};
+
+/// Issuer of the session tokens of local accounts.
+const LOCAL_ISSUER: &str = "local";
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+/// Hashing is deliberately slow, so there is a limit on how much there is to hash.
+const MAX_PASSWORD_LENGTH: usize = 256;
+
+lazy_static! {
+    /// Checked when there's no such account, so the response takes as long as for a real one.
+    static ref DUMMY_PASSWORD_HASH: String =
+        hash_password("not anyone's password").expect("Failed to hash dummy password");
+}
+
+const TOO_MANY_ATTEMPTS: &str = "Too many attempts, please try again in a few minutes.";
+
+/// Failed sign ins by client address and by username on that address, and accounts created
+/// by client address.
+struct AttemptLimits {
+    login_by_address: RateLimiter,
+    login_by_username: RateLimiter,
+    register_by_address: RateLimiter,
+}
+
+#[derive(Clone)]
+pub struct LocalProvider {
+    keys: SessionKeys,
+    limits: Arc<AttemptLimits>,
+    proxy_header: Option<String>,
+}
+
+impl LocalProvider {
+    pub fn new(config: &Config) -> Self {
+        // hashed upfront, so the first unknown username doesn't take twice as long
+        lazy_static::initialize(&DUMMY_PASSWORD_HASH);
+
+        Self {
+            keys: SessionKeys::new(config.secret_key()),
+            limits: Arc::new(AttemptLimits {
+                login_by_address: RateLimiter::new(20, Duration::from_secs(5 * 60)),
+                login_by_username: RateLimiter::new(10, Duration::from_secs(15 * 60)),
+                register_by_address: RateLimiter::new(5, Duration::from_secs(60 * 60)),
+            }),
+            proxy_header: config.trusted_proxy_header().clone(),
+        }
+    }
+
+    fn sign_in(&self, claims: &UserClaims, cookies: CookieJar) -> eyre::Result<impl IntoResponse> {
+        let token = self
+            .keys
+            .encode(claims)
+            .wrap_err("Failed to issue session token")?;
+
+        Ok((cookies.add(session_cookie(token)), Redirect::to("/")))
+    }
+}
+
+impl AuthProvider for LocalProvider {
+    fn session_cookie(&self) -> &'static str {
+        SESSION_COOKIE
+    }
+
+    fn validate(&self, token: &str) -> Result<UserClaims, AuthExtractorError> {
+        self.keys.validate(token, LOCAL_ISSUER)
+    }
+
+    fn routes(&self) -> Router<AppState> {
+        Router::new()
+            .route("/login/", routing::get(login).post(login_post))
+            .route("/register/", routing::get(register).post(register_post))
+            .layer(Extension(self.clone()))
+    }
+}
+
+fn hash_password(password: &str) -> eyre::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|error| eyre!("Failed to hash password: {error}"))
+}
+
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// Usernames end up in URLs and on the leaderboard, so they are kept simple.
+fn is_valid_username(username: &str) -> bool {
+    (3..=32).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+fn login_page(error: Option<&str>, username: &str) -> Markup {
+    let inner = html! {
+        form class="form form--narrow" method="post" action="/auth/login/" {
+            h1 class="form__title" { "Sign In" }
+
+            @if let Some(error) = error {
+                p class="form__error" { (error) }
+            }
+
+            div class="form__group" {
+                label class="form__label" for="username" { "Username" }
+                input #username type="text" class="form__input" name="username" value=(username) required autofocus;
+            }
+            div class="form__group" {
+                label class="form__label" for="password" { "Password" }
+                input #password type="password" class="form__input" name="password" required;
+            }
+
+            div class="form__actions" {
+                a href="/auth/register/" class="button button--secondary" { "Create Account" }
+                button type="submit" class="button button--primary" { "Sign In" }
+            }
+        }
+    };
+
+    page(
+        "Login",
+        app_layout(inner, "Login", AuthState::Unauthenticated),
+    )
+}
+
+fn register_page(error: Option<&str>, username: &str, email: &str) -> Markup {
+    let inner = html! {
+        form class="form form--narrow" method="post" action="/auth/register/" {
+            h1 class="form__title" { "Create Account" }
+
+            @if let Some(error) = error {
+                p class="form__error" { (error) }
+            }
+
+            div class="form__group" {
+                label class="form__label" for="username" { "Username" }
+                input
+                    #username
+                    type="text"
+                    class="form__input"
+                    name="username"
+                    value=(username)
+                    required
+                    minlength="3"
+                    maxlength="32"
+                    pattern="[a-z0-9_\\-]+"
+                    autofocus;
+                p class="form__hint" { "Lowercase letters, digits, dashes and underscores." }
+            }
+            div class="form__group" {
+                label class="form__label" for="email" { "Email" }
+                input #email type="email" class="form__input" name="email" value=(email) required;
+            }
+            div class="form__group" {
+                label class="form__label" for="password" { "Password" }
+                input
+                    #password
+                    type="password"
+                    class="form__input"
+                    name="password"
+                    required
+                    minlength=(MIN_PASSWORD_LENGTH)
+                    maxlength=(MAX_PASSWORD_LENGTH);
+            }
+
+            div class="form__actions" {
+                a href="/auth/login/" class="button button--secondary" { "Sign In Instead" }
+                button type="submit" class="button button--primary" { "Create Account" }
+            }
+        }
+    };
+
+    page(
+        "Create Account",
+        app_layout(inner, "Create Account", AuthState::Unauthenticated),
+    )
+}
+
+#[debug_handler]
+#[tracing::instrument]
+async fn login() -> Markup {
+    login_page(None, "")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginForm {
+    username: String,
+    password: String,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all, fields(username = %form.username))]
+async fn login_post(
+    State(state): State<AppState>,
+    Extension(provider): Extension<LocalProvider>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    cookies: CookieJar,
+    Form(form): Form<LoginForm>,
+) -> Result<impl IntoResponse> {
+    let username = form.username.trim().to_lowercase();
+
+    // only failures are counted, and per address, so nobody can lock others out
+    let address = client_address(&headers, peer, provider.proxy_header.as_deref());
+    let address_and_username = format!("{address} {username}");
+    let limits = &provider.limits;
+
+    if !limits.login_by_address.is_allowed(&address)
+        || !limits.login_by_username.is_allowed(&address_and_username)
+    {
+        return Ok((
+            http::StatusCode::TOO_MANY_REQUESTS,
+            login_page(Some(TOO_MANY_ATTEMPTS), &username),
+        )
+            .into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let password_hash = local_account::get_password_hash(&mut conn, &username)
+        .await
+        .wrap_err("Failed to query local account")?;
+
+    let verified = if form.password.chars().count() <= MAX_PASSWORD_LENGTH {
+        tokio::task::spawn_blocking(move || match password_hash {
+            Some(password_hash) => verify_password(&form.password, &password_hash),
+            None => {
+                verify_password(&form.password, &DUMMY_PASSWORD_HASH);
+                false
+            }
+        })
+        .await
+        .wrap_err("Failed to verify password")?
+    } else {
+        false
+    };
+
+    if !verified {
+        limits.login_by_address.record(&address);
+        limits.login_by_username.record(&address_and_username);
+
+        return Ok((
+            http::StatusCode::UNAUTHORIZED,
+            login_page(Some("Wrong username or password."), &username),
+        )
+            .into_response());
+    }
+
+    let user = user::get_user_by_id(&mut conn, &username)
+        .await
+        .wrap_err("Failed to query user")?
+        .ok_or_eyre("User of the local account not found")?;
+
+    let claims = UserClaims::new(
+        LOCAL_ISSUER.to_string(),
+        user.id().clone(),
+        user.email().clone().unwrap_or_default(),
+        user.display_name().clone(),
+        SESSION_LIFETIME,
+    );
+
+    Ok(provider.sign_in(&claims, cookies)?.into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument]
+async fn register() -> Markup {
+    register_page(None, "", "")
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterForm {
+    username: String,
+    email: String,
+    password: String,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all, fields(username = %form.username))]
+async fn register_post(
+    State(state): State<AppState>,
+    Extension(provider): Extension<LocalProvider>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    cookies: CookieJar,
+    Form(form): Form<RegisterForm>,
+) -> Result<impl IntoResponse> {
+    let username = form.username.trim().to_lowercase();
+    let email = form.email.trim();
+    let address = client_address(&headers, peer, provider.proxy_header.as_deref());
+
+    if !provider.limits.register_by_address.check(&address) {
+        return Ok((
+            http::StatusCode::TOO_MANY_REQUESTS,
+            register_page(Some(TOO_MANY_ATTEMPTS), &username, email),
+        )
+            .into_response());
+    }
+
+    let error = if !is_valid_username(&username) {
+        Some("Usernames are 3 to 32 lowercase letters, digits, dashes or underscores.")
+    } else if !email.contains('@') {
+        Some("That doesn't look like an email address.")
+    } else if !(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH).contains(&form.password.chars().count())
+    {
+        Some("Passwords are 8 to 256 characters long.")
+    } else {
+        None
+    };
+
+    if let Some(error) = error {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            register_page(Some(error), &username, email),
+        )
+            .into_response());
+    }
+
+    let mut txn = state
+        .db()
+        .begin()
+        .await
+        .wrap_err("Failed to start transaction")?;
+
+    if user::get_user_by_id(&mut txn, &username)
+        .await
+        .wrap_err("Failed to query user")?
+        .is_some()
+    {
+        return Ok((
+            http::StatusCode::CONFLICT,
+            register_page(Some("That username is taken."), &username, email),
+        )
+            .into_response());
+    }
+
+    let password_hash = tokio::task::spawn_blocking(move || hash_password(&form.password))
+        .await
+        .wrap_err("Failed to hash password")??;
+
+    let claims = UserClaims::new(
+        LOCAL_ISSUER.to_string(),
+        username,
+        email.to_string(),
+        None,
+        SESSION_LIFETIME,
+    );
+
+    user::save_user(&mut txn, &claims).await?;
+    local_account::create_local_account(&mut txn, claims.sub(), &password_hash)
+        .await
+        .wrap_err("Failed to save password")?;
+
+    txn.commit()
+        .await
+        .wrap_err("Failed to commit transaction")?;
+
+    Ok(provider.sign_in(&claims, cookies)?.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_passwords() {
+        let password_hash = hash_password("correct horse").expect("Failed to hash password");
+
+        assert!(verify_password("correct horse", &password_hash));
+        assert!(!verify_password("battery staple", &password_hash));
+        assert!(!verify_password("correct horse", "not a hash"));
+    }
+
+    #[test]
+    fn validates_usernames() {
+        assert!(is_valid_username("alice_01"));
+        assert!(!is_valid_username("al"));
+        assert!(!is_valid_username("Alice"));
+        assert!(!is_valid_username("alice smith"));
+    }
+}
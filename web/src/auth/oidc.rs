@@ -0,0 +1,416 @@
+//! Logins through any OpenID Connect issuer, using the authorization code flow with PKCE.
+//!
+//! The issuer's ID token is only checked once, on the callback: after that users get
+//! a session token signed by the app, so their sessions don't end with the ID token.
+
+use std::{ops::Deref, sync::Arc};
+
+use axum::{
+    Extension, Router, debug_handler,
+    extract::{Query, State},
+    http,
+    response::{IntoResponse, Redirect},
+    routing,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use axum_jwt_auth::{JwtDecoder, RemoteJwksDecoder, RemoteJwksDecoderBuilder};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use eyre::{OptionExt, WrapErr, eyre};
+use jsonwebtoken::{Algorithm, Validation};
+use maud::{Markup, html};
+use rand::{RngCore, rngs::OsRng};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use tracing::warn;
+
+use super::{
+    AuthProvider,
+    session::{SESSION_COOKIE, SESSION_LIFETIME, SessionKeys, session_cookie},
+};
+use crate::{
+    config::Config,
+    db::user,
+    error::Result,
+    extractors::AuthExtractorError,
+    models::user::UserClaims,
+    partials::{AuthState, app_layout, page},
+    state::AppState,
+};
+
+/// Keeps the PKCE verifier and the state of a login until the issuer sends the user back.
+const LOGIN_STATE_COOKIE: &str = "grimoire_oidc_login";
+const LOGIN_STATE_LIFETIME: time::Duration = time::Duration::minutes(10);
+
+/// The subset of the issuer's discovery document the login flow needs.
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Clone)]
+pub struct OidcProvider(Arc<OidcProviderInner>);
+
+pub struct OidcProviderInner {
+    issuer: String,
+    client_id: String,
+    client_secret: Option<SecretString>,
+    redirect_url: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_decoder: Arc<RemoteJwksDecoder>,
+    keys: SessionKeys,
+    client: reqwest::Client,
+}
+
+impl Deref for OidcProvider {
+    type Target = OidcProviderInner;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl OidcProvider {
+    /// Fetches the configuration of the issuer from its discovery document.
+    pub async fn discover(config: &Config) -> eyre::Result<Self> {
+        let issuer = config
+            .oidc_issuer()
+            .clone()
+            .ok_or_eyre("OIDC_ISSUER is not set")?;
+        let client_id = config
+            .oidc_client_id()
+            .clone()
+            .ok_or_eyre("OIDC_CLIENT_ID is not set")?;
+        let redirect_url = config
+            .oidc_redirect_url()
+            .clone()
+            .ok_or_eyre("OIDC_REDIRECT_URL is not set")?;
+
+        let client = reqwest::Client::new();
+
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = client
+            .get(&discovery_url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .wrap_err("Failed to fetch OpenID Connect discovery document")?
+            .json()
+            .await
+            .wrap_err("Failed to parse OpenID Connect discovery document")?;
+
+        if metadata.issuer != issuer {
+            return Err(eyre!(
+                "Discovery document is for issuer {}, not {issuer}",
+                metadata.issuer
+            ));
+        }
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.algorithms = vec![Algorithm::RS256, Algorithm::RS384, Algorithm::RS512];
+        validation.set_issuer(&[&issuer]);
+        validation.set_audience(&[&client_id]);
+
+        let jwks_decoder = RemoteJwksDecoderBuilder::default()
+            .jwks_url(metadata.jwks_uri)
+            .validation(validation)
+            .config(Default::default())
+            .keys_cache(Default::default())
+            .client(Default::default())
+            .build()
+            .map_err(|error| eyre!("Failed to build JWKS decoder: {error}"))?;
+
+        Ok(Self(Arc::new(OidcProviderInner {
+            issuer,
+            client_id,
+            client_secret: config.oidc_client_secret().clone(),
+            redirect_url,
+            authorization_endpoint: metadata.authorization_endpoint,
+            token_endpoint: metadata.token_endpoint,
+            jwks_decoder: Arc::new(jwks_decoder),
+            keys: SessionKeys::new(config.secret_key()),
+            client,
+        })))
+    }
+
+    /// Exchanges the authorization code for an ID token, returning its verified claims.
+    async fn exchange_code(&self, code: &str, login: &LoginState) -> eyre::Result<IdTokenClaims> {
+        let request = self.client.post(&self.token_endpoint).form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_url),
+            ("client_id", &self.client_id),
+            ("code_verifier", &login.verifier),
+        ]);
+
+        let request = match &self.client_secret {
+            Some(secret) => request.basic_auth(&self.client_id, Some(secret.expose_secret())),
+            None => request,
+        };
+
+        let response = request.send().await.wrap_err("Failed to request tokens")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            return Err(eyre!("Token endpoint responded with {status}: {body}"));
+        }
+
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .wrap_err("Failed to parse token response")?;
+
+        let claims: IdTokenClaims = self
+            .jwks_decoder
+            .decode(&tokens.id_token)
+            .map_err(|error| eyre!("ID token is invalid: {error:?}"))?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(login.nonce.as_str()) {
+            return Err(eyre!("ID token nonce doesn't match"));
+        }
+
+        Ok(claims)
+    }
+}
+
+impl AuthProvider for OidcProvider {
+    fn session_cookie(&self) -> &'static str {
+        SESSION_COOKIE
+    }
+
+    fn validate(&self, token: &str) -> Result<UserClaims, AuthExtractorError> {
+        self.keys.validate(token, &self.issuer)
+    }
+
+    fn routes(&self) -> Router<AppState> {
+        Router::new()
+            .route("/login/", routing::get(login))
+            .route("/authorize/", routing::get(authorize))
+            .route("/callback/", routing::get(callback))
+            .layer(Extension(self.clone()))
+    }
+
+    fn spawn_background_tasks(&self) {
+        let jwks_decoder = self.jwks_decoder.clone();
+
+        tokio::spawn(async move { jwks_decoder.refresh_keys_periodically().await });
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    nonce: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LoginState {
+    state: String,
+    nonce: String,
+    verifier: String,
+    #[serde(with = "time::serde::timestamp")]
+    exp: OffsetDateTime,
+}
+
+/// 256 random bits, URL-safe.
+fn random_token() -> String {
+    let mut bytes = [0; 32];
+    OsRng.fill_bytes(&mut bytes);
+
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// PKCE code challenge of the verifier, using the `S256` method.
+fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn login_page(error: Option<&str>) -> Markup {
+    let inner = html! {
+        div class="form form--narrow" {
+            h1 class="form__title" { "Sign In" }
+
+            @if let Some(error) = error {
+                p class="form__error" { (error) }
+            }
+
+            // the issuer's pages can't be fetched by Turbo
+            a href="/auth/authorize/" class="button button--primary" data-turbo="false" {
+                "Continue to Sign In"
+            }
+        }
+    };
+
+    page(
+        "Login",
+        app_layout(inner, "Login", AuthState::Unauthenticated),
+    )
+}
+
+#[debug_handler]
+#[tracing::instrument]
+async fn login() -> Markup {
+    login_page(None)
+}
+
+/// Sends the user to the issuer, remembering what to check once they're back.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+async fn authorize(
+    Extension(provider): Extension<OidcProvider>,
+    cookies: CookieJar,
+) -> Result<impl IntoResponse> {
+    let login = LoginState {
+        state: random_token(),
+        nonce: random_token(),
+        verifier: random_token(),
+        exp: OffsetDateTime::now_utc() + LOGIN_STATE_LIFETIME,
+    };
+
+    let authorization_url = url::Url::parse_with_params(
+        &provider.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", &provider.client_id),
+            ("redirect_uri", &provider.redirect_url),
+            ("scope", "openid email profile"),
+            ("state", &login.state),
+            ("nonce", &login.nonce),
+            ("code_challenge", &code_challenge(&login.verifier)),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .wrap_err("Failed to build authorization URL")?;
+
+    let cookie = Cookie::build((LOGIN_STATE_COOKIE, provider.keys.encode(&login)?))
+        .path("/auth/")
+        .http_only(true)
+        .secure(true)
+        // the issuer sends the user back with a cross-site navigation
+        .same_site(SameSite::Lax)
+        .max_age(LOGIN_STATE_LIFETIME)
+        .build();
+
+    Ok((
+        cookies.add(cookie),
+        Redirect::to(authorization_url.as_str()),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    /// Set by the issuer instead of the code when the login failed.
+    error: Option<String>,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+async fn callback(
+    State(state): State<AppState>,
+    Extension(provider): Extension<OidcProvider>,
+    Query(query): Query<CallbackQuery>,
+    cookies: CookieJar,
+) -> Result<impl IntoResponse> {
+    let login: Option<LoginState> = cookies
+        .get(LOGIN_STATE_COOKIE)
+        .and_then(|cookie| provider.keys.decode(cookie.value()).ok());
+    let cookies = cookies.remove(Cookie::build(LOGIN_STATE_COOKIE).path("/auth/"));
+
+    let failed = |message: &str| {
+        (
+            cookies.clone(),
+            (http::StatusCode::BAD_REQUEST, login_page(Some(message))),
+        )
+            .into_response()
+    };
+
+    if let Some(error) = &query.error {
+        warn!(%error, "Issuer reported a failed login");
+
+        return Ok(failed("Signing in didn't work out, please try again."));
+    }
+
+    let (Some(login), Some(code)) = (login, &query.code) else {
+        return Ok(failed("The sign in took too long, please try again."));
+    };
+
+    if query.state.as_deref() != Some(login.state.as_str()) {
+        return Ok(failed("The sign in took too long, please try again."));
+    }
+
+    let id_token = match provider.exchange_code(code, &login).await {
+        Ok(id_token) => id_token,
+        Err(error) => {
+            warn!(?error, "Failed to complete login");
+
+            return Ok(failed("Signing in didn't work out, please try again."));
+        }
+    };
+
+    let Some(email) = id_token.email else {
+        return Ok(failed(
+            "Your account doesn't share an email address, which is needed to sign in.",
+        ));
+    };
+
+    let claims = UserClaims::new(
+        provider.issuer.clone(),
+        id_token.sub,
+        email,
+        id_token.name,
+        SESSION_LIFETIME,
+    );
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    // creates the user on the first login, refreshes email and name on the later ones
+    user::save_user(&mut conn, &claims).await?;
+
+    let token = provider
+        .keys
+        .encode(&claims)
+        .wrap_err("Failed to issue session token")?;
+
+    Ok((cookies.add(session_cookie(token)), Redirect::to("/")).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_code_challenge() {
+        // example from RFC 7636, appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+}
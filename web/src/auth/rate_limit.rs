@@ -0,0 +1,127 @@
+//! Throttling of login attempts, kept in memory: password hashing is deliberately slow,
+//! so it shouldn't be available to anyone as often as they like.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use axum::http::HeaderMap;
+
+/// Past this many tracked keys, expired ones are dropped.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Allows up to `max_attempts` per key in every fixed window.
+pub struct RateLimiter {
+    max_attempts: u32,
+    window: Duration,
+    /// Start of the current window and the attempts made in it, by key.
+    attempts: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        Self {
+            max_attempts,
+            window,
+            attempts: Mutex::default(),
+        }
+    }
+
+    /// Records an attempt, returning whether it's allowed.
+    pub fn check(&self, key: &str) -> bool {
+        self.record_at(key, Instant::now()) <= self.max_attempts
+    }
+
+    /// Whether another attempt is allowed, without counting one.
+    pub fn is_allowed(&self, key: &str) -> bool {
+        self.is_allowed_at(key, Instant::now())
+    }
+
+    /// Counts an attempt, for limits that only apply to failed ones.
+    pub fn record(&self, key: &str) {
+        self.record_at(key, Instant::now());
+    }
+
+    fn is_allowed_at(&self, key: &str, now: Instant) -> bool {
+        let attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+
+        attempts.get(key).is_none_or(|(started_at, count)| {
+            now.duration_since(*started_at) >= self.window || *count < self.max_attempts
+        })
+    }
+
+    /// Returns the attempts made in the current window, this one included.
+    fn record_at(&self, key: &str, now: Instant) -> u32 {
+        let mut attempts = self.attempts.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if attempts.len() > PRUNE_THRESHOLD {
+            attempts.retain(|_, (started_at, _)| now.duration_since(*started_at) < self.window);
+        }
+
+        let (started_at, count) = attempts.entry(key.to_owned()).or_insert((now, 0));
+
+        if now.duration_since(*started_at) >= self.window {
+            *started_at = now;
+            *count = 0;
+        }
+
+        *count += 1;
+        *count
+    }
+}
+
+/// Address of the client, from `proxy_header` when the app runs behind a proxy, so that
+/// clients don't all share the proxy's address. The last address in the header is the one
+/// the proxy added, those before it are whatever the client sent.
+pub fn client_address(headers: &HeaderMap, peer: SocketAddr, proxy_header: Option<&str>) -> String {
+    proxy_header
+        .and_then(|header| headers.get_all(header).iter().next_back())
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map(|address| address.trim().to_owned())
+        .filter(|address| !address.is_empty())
+        .unwrap_or_else(|| peer.ip().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_attempts_per_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+
+        for (key, allowed) in [
+            ("alice", true),
+            ("alice", true),
+            ("alice", false),
+            ("bob", true),
+        ] {
+            assert_eq!(limiter.is_allowed_at(key, now), allowed);
+            limiter.record_at(key, now);
+        }
+
+        assert!(limiter.is_allowed_at("alice", now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn reads_client_address_from_proxy_header() {
+        let peer: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.2.3.4, 5.6.7.8".parse().unwrap());
+
+        assert_eq!(
+            client_address(&headers, peer, Some("X-Forwarded-For")),
+            "5.6.7.8"
+        );
+        assert_eq!(client_address(&headers, peer, None), "10.0.0.1");
+        assert_eq!(
+            client_address(&HeaderMap::new(), peer, Some("X-Forwarded-For")),
+            "10.0.0.1"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Session tokens the app issues itself, for providers which don't have their own:
+//! JWTs signed with the secret key, kept in an HTTP-only cookie.
+
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use eyre::{Result, WrapErr};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{extractors::AuthExtractorError, models::user::UserClaims};
+
+pub const SESSION_COOKIE: &str = "grimoire_session";
+
+/// How long users stay signed in.
+pub const SESSION_LIFETIME: time::Duration = time::Duration::days(30);
+
+#[derive(Clone)]
+pub struct SessionKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl SessionKeys {
+    pub fn new(secret_key: &SecretString) -> Self {
+        let secret = secret_key.expose_secret().as_bytes();
+
+        Self {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+        }
+    }
+
+    /// Signs the claims, they need an `exp` claim to be decoded back.
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String> {
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), claims, &self.encoding)
+            .wrap_err("Failed to sign token")
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> jsonwebtoken::errors::Result<T> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_nbf = true;
+
+        jsonwebtoken::decode(token, &self.decoding, &validation).map(|data| data.claims)
+    }
+
+    /// Decodes a session token, which has to be issued by `issuer`.
+    pub fn validate(&self, token: &str, issuer: &str) -> Result<UserClaims, AuthExtractorError> {
+        let claims: UserClaims = self
+            .decode(token)
+            .map_err(AuthExtractorError::InvalidSessionToken)?;
+
+        if claims.iss() != issuer {
+            return Err(AuthExtractorError::InvalidIssuer);
+        }
+
+        Ok(claims)
+    }
+}
+
+/// Cookie with the session token, for a freshly signed in user.
+pub fn session_cookie(token: String) -> Cookie<'static> {
+    Cookie::build((SESSION_COOKIE, token))
+        .path("/")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(SESSION_LIFETIME)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> SessionKeys {
+        SessionKeys::new(&SecretString::from("test secret key"))
+    }
+
+    fn claims(iss: &str) -> UserClaims {
+        UserClaims::new(
+            iss.to_string(),
+            "alice".to_string(),
+            "alice@example.com".to_string(),
+            None,
+            SESSION_LIFETIME,
+        )
+    }
+
+    #[test]
+    fn validates_own_sessions() {
+        let keys = keys();
+        let token = keys.encode(&claims("local")).expect("Failed to sign token");
+
+        let claims = keys.validate(&token, "local").expect("Session is invalid");
+        assert_eq!(claims.sub(), "alice");
+
+        assert!(matches!(
+            keys.validate(&token, "https://issuer.example.com"),
+            Err(AuthExtractorError::InvalidIssuer)
+        ));
+    }
+
+    #[test]
+    fn rejects_foreign_signatures() {
+        let token = SessionKeys::new(&SecretString::from("another key"))
+            .encode(&claims("local"))
+            .expect("Failed to sign token");
+
+        assert!(matches!(
+            keys().validate(&token, "local"),
+            Err(AuthExtractorError::InvalidSessionToken(_))
+        ));
+    }
+}
@@ -6,15 +6,48 @@ use serde::Deserialize;
 
 use sql_grimoire_observability::Config as ObservabilityConfig;
 
+use crate::auth::AuthProviderKind;
+
+fn en() -> String {
+    "en".to_string()
+}
+
+fn five_minutes() -> u64 {
+    5 * 60
+}
+
 #[derive(Debug, Deserialize, Getters)]
 pub struct ConfigInner {
     #[serde(flatten)]
     observability: ObservabilityConfig,
     database_url: SecretString,
     bind_to: String,
-    corbado_host: String,
-    corbado_project_id: String,
+    /// Where users sign in, see [`AuthProviderKind`].
+    #[serde(default)]
+    auth_provider: AuthProviderKind,
+    corbado_host: Option<String>,
+    corbado_project_id: Option<String>,
+    /// Issuer of the OpenID Connect provider, the rest of its configuration is discovered.
+    oidc_issuer: Option<String>,
+    oidc_client_id: Option<String>,
+    /// Not needed for public clients, PKCE protects the code exchange.
+    oidc_client_secret: Option<SecretString>,
+    /// Absolute URL of `/auth/callback/`, registered with the OpenID Connect provider.
+    oidc_redirect_url: Option<String>,
     secret_key: SecretString,
+    /// Header a reverse proxy puts the client address in, e.g. `X-Forwarded-For`.
+    /// Only set it behind a proxy that sets the header, clients could forge it otherwise.
+    trusted_proxy_header: Option<String>,
+    /// Locale of the texts stored on exercises themselves,
+    /// shown when there is no translation for the user's locale.
+    #[serde(default = "en")]
+    default_locale: String,
+    /// Comma-separated list of locales exercises can be translated to.
+    #[serde(default)]
+    locales: Vec<String>,
+    /// How often leaderboards are recomputed, in seconds, at least one.
+    #[serde(default = "five_minutes")]
+    leaderboard_refresh_secs: u64,
 }
 
 #[derive(Clone)]
@@ -31,6 +64,24 @@ impl Deref for Config {
 impl Config {
     pub fn from_env() -> eyre::Result<Self> {
         let config = envy::from_env::<ConfigInner>()?;
+
+        if config.leaderboard_refresh_secs == 0 {
+            return Err(eyre::eyre!("LEADERBOARD_REFRESH_SECS must be at least 1"));
+        }
+
         Ok(Self(Arc::new(config)))
     }
+
+    /// All locales content can be shown in, the default one goes first.
+    pub fn supported_locales(&self) -> Vec<&str> {
+        let mut locales = vec![self.default_locale.as_str()];
+
+        for locale in &self.locales {
+            if !locales.contains(&locale.as_str()) {
+                locales.push(locale);
+            }
+        }
+
+        locales
+    }
 }
@@ -1,3 +1,14 @@
+pub mod achievement;
+pub mod analytics;
+pub mod assignment;
+pub mod bookmark;
+pub mod cohort;
+pub mod comment;
 pub mod exercise;
 pub mod exercise_solution;
+pub mod leaderboard;
+pub mod local_account;
+pub mod progress;
+pub mod report;
+pub mod review;
 pub mod user;
@@ -0,0 +1,204 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+use sql_grimoire_id::Id;
+
+use crate::{
+    achievements::{Achievement, AchievementFacts, current_streak, uses_window_function},
+    db::progress,
+    models::{
+        Queryable,
+        achievement::UserAchievement,
+        exercise::{ExerciseId, SqlDialect},
+    },
+};
+
+type UserAchievementInner = <UserAchievement as Queryable>::Inner;
+
+/// How far back activity is looked at when counting streaks.
+const STREAK_LOOKBACK_DAYS: i64 = 31;
+
+/// Records that the user opened the hint of the exercise, only the first time counts.
+#[tracing::instrument(skip(conn))]
+pub async fn record_hint_view(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO hint_view (user_id, exercise_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING",
+        user_id,
+        exercise_id.get(),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to record hint view")?;
+
+    Ok(())
+}
+
+/// Collects what achievements are evaluated on, right after a submission to the exercise.
+#[tracing::instrument(skip(conn))]
+pub async fn get_achievement_facts(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<AchievementFacts> {
+    let submission = sqlx::query!(
+        r#"SELECT
+            (
+                SELECT COUNT(DISTINCT exercise_id)
+                FROM user_solution
+                WHERE user_id = $1 AND status = 'correct'
+            ) AS "solved!",
+            COALESCE((
+                SELECT (ARRAY_AGG(status ORDER BY created_at))[1] = 'correct'
+                FROM user_solution
+                WHERE user_id = $1 AND exercise_id = $2
+            ), false) AS "first_try!",
+            EXISTS (
+                SELECT 1
+                FROM exercise
+                WHERE
+                    id = $2
+                    AND COALESCE(hint, '') <> ''
+                    AND EXISTS (
+                        SELECT 1
+                        FROM user_solution
+                        WHERE user_id = $1 AND exercise_id = $2 AND status = 'correct'
+                    )
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM hint_view
+                        WHERE user_id = $1 AND exercise_id = $2
+                    )
+            ) AS "solved_without_hint!"
+        "#,
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .wrap_err("Failed to get submission facts")?;
+
+    let today = OffsetDateTime::now_utc().date();
+    let activity = progress::get_activity(
+        conn,
+        user_id,
+        PrimitiveDateTime::new(today - Duration::days(STREAK_LOOKBACK_DAYS), Time::MIDNIGHT),
+    )
+    .await
+    .wrap_err("Failed to get activity")?;
+    let days: Vec<_> = activity.iter().map(|day| *day.day()).collect();
+
+    let exercises = sqlx::query!(
+        r#"SELECT
+            exercise.expected_query,
+            exercise_schema.dialect,
+            EXISTS (
+                SELECT 1
+                FROM user_solution
+                WHERE
+                    user_id = $1
+                    AND exercise_id = exercise.id
+                    AND status = 'correct'
+            ) AS "solved!"
+        FROM exercise
+        JOIN exercise_schema ON exercise_schema.id = exercise.schema_id
+        WHERE exercise.published_at IS NOT NULL
+        "#,
+        user_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get published exercises")?;
+
+    let window_exercises: Vec<_> = exercises
+        .iter()
+        .filter(|exercise| {
+            uses_window_function(
+                &exercise.expected_query,
+                SqlDialect::from(exercise.dialect.clone()),
+            )
+        })
+        .collect();
+
+    Ok(AchievementFacts {
+        solved: submission.solved,
+        first_try: submission.first_try,
+        solved_without_hint: submission.solved_without_hint,
+        streak: current_streak(&days, today),
+        window_functions_solved: !window_exercises.is_empty()
+            && window_exercises.iter().all(|exercise| exercise.solved),
+    })
+}
+
+/// Awards the achievements, the ones the user already has are left as they are.
+#[tracing::instrument(skip(conn))]
+pub async fn award_achievements(
+    conn: &mut PgConnection,
+    user_id: &str,
+    achievements: &[Achievement],
+) -> Result<()> {
+    let keys: Vec<String> = achievements
+        .iter()
+        .map(|achievement| achievement.key().to_owned())
+        .collect();
+
+    sqlx::query!(
+        "INSERT INTO user_achievement (user_id, achievement)
+        SELECT $1, UNNEST($2::text[])
+        ON CONFLICT DO NOTHING",
+        user_id,
+        &keys,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to award achievements")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_user_achievements(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Vec<UserAchievement>> {
+    let achievements = sqlx::query_as!(
+        UserAchievementInner,
+        "SELECT achievement AS key, created_at AS earned_at
+        FROM user_achievement
+        WHERE user_id = $1
+        ORDER BY created_at",
+        user_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get user achievements")?;
+
+    Ok(achievements.into_iter().map(Queryable::parse).collect())
+}
+
+/// Returns achievements the user hasn't been notified about yet, marking them as notified.
+#[tracing::instrument(skip(conn))]
+pub async fn take_new_achievements(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Vec<UserAchievement>> {
+    let achievements = sqlx::query_as!(
+        UserAchievementInner,
+        "UPDATE user_achievement
+        SET notified_at = now()
+        WHERE user_id = $1 AND notified_at IS NULL
+        RETURNING achievement AS key, created_at AS earned_at",
+        user_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to take new achievements")?;
+
+    Ok(achievements.into_iter().map(Queryable::parse).collect())
+}
@@ -0,0 +1,155 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+
+use sql_grimoire_id::Id;
+
+use crate::models::{
+    Queryable,
+    analytics::{CommonQuery, CommonResult, ExerciseStats},
+    exercise::ExerciseId,
+};
+
+type ExerciseStatsInner = <ExerciseStats as Queryable>::Inner;
+type CommonResultInner = <CommonResult as Queryable>::Inner;
+type CommonQueryInner = <CommonQuery as Queryable>::Inner;
+
+/// Aggregates attempts at the exercise, or at every exercise if `exercise_id` is `None`.
+#[tracing::instrument(skip(conn))]
+pub async fn get_exercise_stats(
+    conn: &mut PgConnection,
+    exercise_id: Option<ExerciseId>,
+) -> Result<Vec<ExerciseStats>> {
+    let stats = sqlx::query_as!(
+        ExerciseStatsInner,
+        r#"WITH attempts AS (
+            SELECT
+                exercise_id,
+                user_id,
+                created_at,
+                MIN(created_at) FILTER (WHERE status = 'correct')
+                    OVER (PARTITION BY exercise_id, user_id) AS solved_at
+            FROM user_solution
+        ), user_attempts AS (
+            SELECT
+                exercise_id,
+                user_id,
+                COUNT(*) AS attempts,
+                COUNT(*) FILTER (WHERE created_at <= solved_at) AS attempts_to_solve,
+                MIN(created_at) AS first_attempt_at,
+                MIN(solved_at) AS solved_at
+            FROM attempts
+            GROUP BY exercise_id, user_id
+        )
+        SELECT
+            exercise.id AS exercise_id,
+            exercise.name,
+            COALESCE(SUM(user_attempts.attempts), 0)::bigint AS "attempts!",
+            COUNT(user_attempts.user_id) AS "users!",
+            COUNT(user_attempts.solved_at) AS "solvers!",
+            PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY user_attempts.attempts_to_solve)
+                FILTER (WHERE user_attempts.solved_at IS NOT NULL)
+                AS median_attempts_to_solve,
+            PERCENTILE_CONT(0.5) WITHIN GROUP (
+                ORDER BY EXTRACT(EPOCH FROM user_attempts.solved_at - user_attempts.first_attempt_at)::float8
+            )
+                FILTER (WHERE user_attempts.solved_at IS NOT NULL)
+                AS median_seconds_to_solve
+        FROM exercise
+        LEFT OUTER JOIN user_attempts ON exercise.id = user_attempts.exercise_id
+        WHERE
+            $1::uuid IS NULL
+            OR exercise.id = $1
+        GROUP BY exercise.id, exercise.name
+        ORDER BY exercise.name
+        "#,
+        exercise_id.map(|id| id.get()),
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get exercise stats")?;
+
+    Ok(stats.into_iter().map(Queryable::parse).collect())
+}
+
+/// Counts users who attempted any exercise.
+#[tracing::instrument(skip(conn))]
+pub async fn count_active_users(conn: &mut PgConnection) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(DISTINCT user_id) AS "count!"
+        FROM user_solution"#,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count active users")?;
+
+    Ok(count)
+}
+
+/// Lists the incorrect results submitted most often for the exercise.
+#[tracing::instrument(skip(conn))]
+pub async fn get_common_incorrect_results(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    limit: i64,
+) -> Result<Vec<CommonResult>> {
+    let results = sqlx::query_as!(
+        CommonResultInner,
+        r#"SELECT
+            result,
+            COUNT(*) AS "count!",
+            COUNT(DISTINCT user_id) AS "users!"
+        FROM user_solution
+        WHERE
+            exercise_id = $1
+            AND status = 'incorrect'
+        GROUP BY result
+        ORDER BY 2 DESC, 3 DESC
+        LIMIT $2
+        "#,
+        exercise_id.get(),
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get common incorrect results")?;
+
+    Ok(results.into_iter().map(Queryable::parse).collect())
+}
+
+/// Groups incorrect queries submitted for the exercise by their fingerprints,
+/// largest clusters first.
+///
+/// Queries which couldn't be parsed are only compared ignoring whitespace.
+#[tracing::instrument(skip(conn))]
+pub async fn get_common_incorrect_queries(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    limit: i64,
+) -> Result<Vec<CommonQuery>> {
+    let queries = sqlx::query_as!(
+        CommonQueryInner,
+        r#"SELECT
+            COALESCE(
+                NULLIF(query_fingerprint, ''),
+                REGEXP_REPLACE(BTRIM(query), '\s+', ' ', 'g')
+            ) AS "fingerprint!",
+            (ARRAY_AGG(query ORDER BY created_at DESC))[1] AS "example!",
+            COUNT(*) AS "count!",
+            COUNT(DISTINCT user_id) AS "users!"
+        FROM user_solution
+        WHERE
+            exercise_id = $1
+            AND status = 'incorrect'
+        GROUP BY 1
+        ORDER BY 3 DESC, 4 DESC
+        LIMIT $2
+        "#,
+        exercise_id.get(),
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get common incorrect queries")?;
+
+    Ok(queries.into_iter().map(Queryable::parse).collect())
+}
@@ -0,0 +1,223 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+use time::PrimitiveDateTime;
+use uuid::Uuid;
+
+use sql_grimoire_id::Id;
+
+use crate::models::{
+    Queryable,
+    assignment::{
+        Assignment, AssignmentExercise, AssignmentId, AssignmentListItem, AssignmentResult,
+        StudentAssignment,
+    },
+    cohort::CohortId,
+    exercise::ExerciseId,
+};
+
+type AssignmentInner = <Assignment as Queryable>::Inner;
+type AssignmentListItemInner = <AssignmentListItem as Queryable>::Inner;
+type AssignmentExerciseInner = <AssignmentExercise as Queryable>::Inner;
+type StudentAssignmentInner = <StudentAssignment as Queryable>::Inner;
+type AssignmentResultInner = <AssignmentResult as Queryable>::Inner;
+
+#[tracing::instrument(skip(conn))]
+pub async fn create_assignment(
+    conn: &mut PgConnection,
+    cohort_id: CohortId,
+    name: &str,
+    opens_at: PrimitiveDateTime,
+    due_at: PrimitiveDateTime,
+) -> Result<Assignment> {
+    let assignment = sqlx::query_as!(
+        AssignmentInner,
+        "INSERT INTO assignment (cohort_id, name, opens_at, due_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, cohort_id, name, opens_at, due_at",
+        cohort_id.get(),
+        name,
+        opens_at,
+        due_at,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to create assignment")?;
+
+    Ok(Queryable::parse(assignment))
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn add_assignment_exercises(
+    conn: &mut PgConnection,
+    id: AssignmentId,
+    exercise_ids: &[ExerciseId],
+) -> Result<()> {
+    let exercise_ids: Vec<Uuid> = exercise_ids.iter().map(|id| id.get()).collect();
+
+    sqlx::query!(
+        "INSERT INTO assignment_exercise (assignment_id, exercise_id)
+        SELECT $1, UNNEST($2::uuid[])
+        ON CONFLICT DO NOTHING",
+        id.get(),
+        &exercise_ids,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to add assignment exercises")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_assignment(
+    conn: &mut PgConnection,
+    id: AssignmentId,
+) -> Result<Option<Assignment>> {
+    let assignment = sqlx::query_as!(
+        AssignmentInner,
+        "SELECT id, cohort_id, name, opens_at, due_at FROM assignment WHERE id = $1",
+        id.get(),
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get assignment")?
+    .map(Queryable::parse);
+
+    Ok(assignment)
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_cohort_assignments(
+    conn: &mut PgConnection,
+    cohort_id: CohortId,
+) -> Result<Vec<AssignmentListItem>> {
+    let assignments = sqlx::query_as!(
+        AssignmentListItemInner,
+        r#"SELECT
+            assignment.id,
+            assignment.name,
+            assignment.opens_at,
+            assignment.due_at,
+            COUNT(assignment_exercise.exercise_id) AS "exercises!"
+        FROM assignment
+        LEFT OUTER JOIN assignment_exercise ON assignment.id = assignment_exercise.assignment_id
+        WHERE assignment.cohort_id = $1
+        GROUP BY assignment.id
+        ORDER BY assignment.due_at DESC
+        "#,
+        cohort_id.get(),
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get cohort assignments")?;
+
+    Ok(assignments.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_assignment_exercises(
+    conn: &mut PgConnection,
+    id: AssignmentId,
+    locale: &str,
+) -> Result<Vec<AssignmentExercise>> {
+    let exercises = sqlx::query_as!(
+        AssignmentExerciseInner,
+        r#"SELECT
+            exercise.id AS exercise_id,
+            COALESCE(exercise_translation.name, exercise.name) AS "name!"
+        FROM assignment_exercise
+        JOIN exercise ON exercise.id = assignment_exercise.exercise_id
+        LEFT OUTER JOIN exercise_translation ON
+            exercise.id = exercise_translation.exercise_id
+            AND exercise_translation.locale = $2
+        WHERE assignment_exercise.assignment_id = $1
+        ORDER BY 2
+        "#,
+        id.get(),
+        locale,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get assignment exercises")?;
+
+    Ok(exercises.into_iter().map(Queryable::parse).collect())
+}
+
+/// Lists assignments of the user's cohorts which are open right now, the closest
+/// due date first.
+#[tracing::instrument(skip(conn))]
+pub async fn get_open_assignments(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Vec<StudentAssignment>> {
+    let assignments = sqlx::query_as!(
+        StudentAssignmentInner,
+        r#"SELECT
+            assignment.id,
+            assignment.name,
+            cohort.name AS cohort_name,
+            assignment.due_at,
+            ARRAY_AGG(assignment_exercise.exercise_id) AS "exercise_ids!",
+            COUNT(*) FILTER (WHERE EXISTS (
+                SELECT 1
+                FROM user_solution
+                WHERE
+                    user_solution.user_id = $1
+                    AND user_solution.exercise_id = assignment_exercise.exercise_id
+                    AND user_solution.status = 'correct'
+                    AND user_solution.created_at <= assignment.due_at
+            )) AS "solved!"
+        FROM assignment
+        JOIN cohort ON cohort.id = assignment.cohort_id
+        JOIN cohort_member ON
+            cohort_member.cohort_id = assignment.cohort_id
+            AND cohort_member.user_id = $1
+        JOIN assignment_exercise ON assignment.id = assignment_exercise.assignment_id
+        WHERE
+            assignment.opens_at <= now()
+            AND assignment.due_at > now()
+        GROUP BY assignment.id, cohort.name
+        ORDER BY assignment.due_at
+        "#,
+        user_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get open assignments")?;
+
+    Ok(assignments.into_iter().map(Queryable::parse).collect())
+}
+
+/// Results of every cohort member on every exercise of the assignment.
+///
+/// Unlike the cohort progress, exercises a member hasn't attempted are included.
+#[tracing::instrument(skip(conn))]
+pub async fn get_assignment_results(
+    conn: &mut PgConnection,
+    id: AssignmentId,
+) -> Result<Vec<AssignmentResult>> {
+    let results = sqlx::query_as!(
+        AssignmentResultInner,
+        r#"SELECT
+            cohort_member.user_id,
+            assignment_exercise.exercise_id,
+            COUNT(user_solution.id) AS "attempts!",
+            MIN(user_solution.created_at) FILTER (WHERE user_solution.status = 'correct') AS solved_at
+        FROM assignment
+        JOIN cohort_member ON cohort_member.cohort_id = assignment.cohort_id
+        JOIN assignment_exercise ON assignment.id = assignment_exercise.assignment_id
+        LEFT OUTER JOIN user_solution ON
+            user_solution.user_id = cohort_member.user_id
+            AND user_solution.exercise_id = assignment_exercise.exercise_id
+        WHERE assignment.id = $1
+        GROUP BY cohort_member.user_id, assignment_exercise.exercise_id
+        ORDER BY cohort_member.user_id
+        "#,
+        id.get(),
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get assignment results")?;
+
+    Ok(results.into_iter().map(Queryable::parse).collect())
+}
@@ -0,0 +1,117 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+
+use sql_grimoire_id::Id;
+
+use crate::models::exercise::ExerciseId;
+
+#[tracing::instrument(skip(conn))]
+pub async fn is_bookmarked(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<bool> {
+    let bookmarked = sqlx::query_scalar!(
+        r#"SELECT EXISTS(
+            SELECT 1
+            FROM exercise_bookmark
+            WHERE user_id = $1 AND exercise_id = $2
+        ) AS "bookmarked!""#,
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to check exercise bookmark")?;
+
+    Ok(bookmarked)
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn set_bookmark(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    bookmarked: bool,
+) -> Result<()> {
+    if bookmarked {
+        sqlx::query!(
+            "INSERT INTO exercise_bookmark (user_id, exercise_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING",
+            user_id,
+            exercise_id.get(),
+        )
+        .execute(conn)
+        .await
+        .wrap_err("Failed to add exercise bookmark")?;
+    } else {
+        sqlx::query!(
+            "DELETE FROM exercise_bookmark
+            WHERE user_id = $1 AND exercise_id = $2",
+            user_id,
+            exercise_id.get(),
+        )
+        .execute(conn)
+        .await
+        .wrap_err("Failed to remove exercise bookmark")?;
+    }
+
+    Ok(())
+}
+
+/// The user's private notes on the exercise, if there are any.
+#[tracing::instrument(skip(conn))]
+pub async fn get_note(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<Option<String>> {
+    let body = sqlx::query_scalar!(
+        "SELECT body
+        FROM exercise_note
+        WHERE user_id = $1 AND exercise_id = $2",
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get exercise note")?;
+
+    Ok(body)
+}
+
+/// Saves the user's notes on the exercise, `None` removes them.
+#[tracing::instrument(skip(conn, body))]
+pub async fn save_note(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    body: Option<&str>,
+) -> Result<()> {
+    match body {
+        Some(body) => sqlx::query!(
+            "INSERT INTO exercise_note (user_id, exercise_id, body)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, exercise_id) DO UPDATE
+            SET body = EXCLUDED.body",
+            user_id,
+            exercise_id.get(),
+            body,
+        )
+        .execute(conn)
+        .await
+        .wrap_err("Failed to save exercise note")?,
+        None => sqlx::query!(
+            "DELETE FROM exercise_note
+            WHERE user_id = $1 AND exercise_id = $2",
+            user_id,
+            exercise_id.get(),
+        )
+        .execute(conn)
+        .await
+        .wrap_err("Failed to delete exercise note")?,
+    };
+
+    Ok(())
+}
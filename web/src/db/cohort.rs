@@ -0,0 +1,227 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+
+use sql_grimoire_id::Id;
+
+use crate::models::{
+    Queryable,
+    cohort::{Cohort, CohortId, CohortListItem, CohortMember, CohortProgressCell},
+};
+
+type CohortInner = <Cohort as Queryable>::Inner;
+type CohortListItemInner = <CohortListItem as Queryable>::Inner;
+type CohortMemberInner = <CohortMember as Queryable>::Inner;
+type CohortProgressCellInner = <CohortProgressCell as Queryable>::Inner;
+
+#[tracing::instrument(skip(conn))]
+pub async fn create_cohort(
+    conn: &mut PgConnection,
+    name: &str,
+    teacher_id: &str,
+) -> Result<Cohort> {
+    let cohort = sqlx::query_as!(
+        CohortInner,
+        "INSERT INTO cohort (name, teacher_id)
+        VALUES ($1, $2)
+        RETURNING id, name, teacher_id, invite_code",
+        name,
+        teacher_id,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to create cohort")?;
+
+    Ok(Queryable::parse(cohort))
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_cohort(conn: &mut PgConnection, id: CohortId) -> Result<Option<Cohort>> {
+    let cohort = sqlx::query_as!(
+        CohortInner,
+        "SELECT id, name, teacher_id, invite_code FROM cohort WHERE id = $1",
+        id.get(),
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get cohort")?
+    .map(Queryable::parse);
+
+    Ok(cohort)
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_cohort_by_invite_code(
+    conn: &mut PgConnection,
+    invite_code: &str,
+) -> Result<Option<Cohort>> {
+    let cohort = sqlx::query_as!(
+        CohortInner,
+        "SELECT id, name, teacher_id, invite_code FROM cohort WHERE invite_code = $1",
+        invite_code,
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get cohort by invite code")?
+    .map(Queryable::parse);
+
+    Ok(cohort)
+}
+
+/// Lists cohorts run by the teacher, or all of them if `teacher_id` is `None`.
+#[tracing::instrument(skip(conn))]
+pub async fn get_cohorts(
+    conn: &mut PgConnection,
+    teacher_id: Option<&str>,
+) -> Result<Vec<CohortListItem>> {
+    let cohorts = sqlx::query_as!(
+        CohortListItemInner,
+        r#"SELECT
+            cohort.id,
+            cohort.name,
+            COUNT(cohort_member.user_id) AS "members!",
+            cohort.created_at
+        FROM cohort
+        LEFT OUTER JOIN cohort_member ON cohort.id = cohort_member.cohort_id
+        WHERE
+            $1::text IS NULL
+            OR cohort.teacher_id = $1
+        GROUP BY cohort.id
+        ORDER BY cohort.created_at DESC
+        "#,
+        teacher_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get cohorts")?;
+
+    Ok(cohorts.into_iter().map(Queryable::parse).collect())
+}
+
+/// Lists cohorts the user is a member or the teacher of.
+#[tracing::instrument(skip(conn))]
+pub async fn get_user_cohorts(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Vec<CohortListItem>> {
+    let cohorts = sqlx::query_as!(
+        CohortListItemInner,
+        r#"SELECT
+            cohort.id,
+            cohort.name,
+            COUNT(cohort_member.user_id) AS "members!",
+            cohort.created_at
+        FROM cohort
+        LEFT OUTER JOIN cohort_member ON cohort.id = cohort_member.cohort_id
+        WHERE
+            cohort.teacher_id = $1
+            OR cohort.id IN (SELECT cohort_id FROM cohort_member WHERE user_id = $1)
+        GROUP BY cohort.id
+        ORDER BY cohort.name
+        "#,
+        user_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get user cohorts")?;
+
+    Ok(cohorts.into_iter().map(Queryable::parse).collect())
+}
+
+/// Adds the user to the cohort, does nothing if they're already a member.
+#[tracing::instrument(skip(conn))]
+pub async fn join_cohort(conn: &mut PgConnection, id: CohortId, user_id: &str) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO cohort_member (cohort_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING",
+        id.get(),
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to join cohort")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_cohort_members(
+    conn: &mut PgConnection,
+    id: CohortId,
+) -> Result<Vec<CohortMember>> {
+    let members = sqlx::query_as!(
+        CohortMemberInner,
+        "SELECT
+            cohort_member.user_id,
+            users.email,
+            users.display_name,
+            cohort_member.created_at AS joined_at
+        FROM cohort_member
+        JOIN users ON users.id = cohort_member.user_id
+        WHERE cohort_member.cohort_id = $1
+        ORDER BY COALESCE(users.display_name, users.id)",
+        id.get(),
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get cohort members")?;
+
+    Ok(members.into_iter().map(Queryable::parse).collect())
+}
+
+/// Aggregates attempts of the cohort members, per member and exercise.
+///
+/// Exercises a member hasn't attempted yet are left out.
+#[tracing::instrument(skip(conn))]
+pub async fn get_cohort_progress(
+    conn: &mut PgConnection,
+    id: CohortId,
+) -> Result<Vec<CohortProgressCell>> {
+    let cells = sqlx::query_as!(
+        CohortProgressCellInner,
+        r#"SELECT
+            user_solution.user_id,
+            user_solution.exercise_id,
+            COUNT(*) AS "attempts!",
+            BOOL_OR(user_solution.status = 'correct') AS "solved!",
+            (ARRAY_AGG(user_solution.query ORDER BY user_solution.created_at DESC))[1] AS "last_query!"
+        FROM user_solution
+        JOIN cohort_member ON
+            cohort_member.user_id = user_solution.user_id
+            AND cohort_member.cohort_id = $1
+        GROUP BY user_solution.user_id, user_solution.exercise_id
+        "#,
+        id.get(),
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get cohort progress")?;
+
+    Ok(cells.into_iter().map(Queryable::parse).collect())
+}
+
+/// Checks if the student is a member of any cohort run by the teacher.
+#[tracing::instrument(skip(conn))]
+pub async fn is_teacher_of(
+    conn: &mut PgConnection,
+    teacher_id: &str,
+    student_id: &str,
+) -> Result<bool> {
+    let is_teacher = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1
+            FROM cohort
+            JOIN cohort_member ON cohort.id = cohort_member.cohort_id
+            WHERE
+                cohort.teacher_id = $1
+                AND cohort_member.user_id = $2
+        ) AS "exists!""#,
+        teacher_id,
+        student_id,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to check cohort membership")?;
+
+    Ok(is_teacher)
+}
@@ -0,0 +1,137 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+
+use sql_grimoire_id::Id;
+
+use crate::models::{
+    Queryable,
+    comment::{Comment, CommentId},
+    exercise::ExerciseId,
+};
+
+type CommentInner = <Comment as Queryable>::Inner;
+
+/// Lists the discussion of the exercise, pinned comments first, then the oldest.
+#[tracing::instrument(skip(conn))]
+pub async fn get_exercise_comments(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    include_hidden: bool,
+) -> Result<Vec<Comment>> {
+    let comments = sqlx::query_as!(
+        CommentInner,
+        r#"SELECT
+            exercise_comment.id,
+            exercise_comment.user_id,
+            users.display_name AS author_name,
+            EXISTS(
+                SELECT 1
+                FROM user_solution
+                WHERE
+                    user_solution.user_id = exercise_comment.user_id
+                    AND user_solution.exercise_id = exercise_comment.exercise_id
+                    AND user_solution.status = 'correct'
+            ) AS "author_solved!",
+            exercise_comment.body,
+            exercise_comment.hidden,
+            exercise_comment.pinned,
+            exercise_comment.created_at
+        FROM exercise_comment
+        JOIN users ON users.id = exercise_comment.user_id
+        WHERE
+            exercise_comment.exercise_id = $1
+            AND ($2 OR NOT exercise_comment.hidden)
+        ORDER BY exercise_comment.pinned DESC, exercise_comment.created_at
+        "#,
+        exercise_id.get(),
+        include_hidden,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get exercise comments")?;
+
+    Ok(comments.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn, body))]
+pub async fn create_comment(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    user_id: &str,
+    body: &str,
+) -> Result<CommentId> {
+    let id = sqlx::query_scalar!(
+        "INSERT INTO exercise_comment (exercise_id, user_id, body)
+        VALUES ($1, $2, $3)
+        RETURNING id",
+        exercise_id.get(),
+        user_id,
+        body,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to create comment")?;
+
+    Ok(id.into())
+}
+
+/// Returns `false` if there's no such comment on the exercise.
+#[tracing::instrument(skip(conn))]
+pub async fn set_comment_hidden(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    comment_id: CommentId,
+    hidden: bool,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE exercise_comment SET hidden = $1 WHERE id = $2 AND exercise_id = $3",
+        hidden,
+        comment_id.get(),
+        exercise_id.get(),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set comment hidden")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns `false` if there's no such comment on the exercise.
+#[tracing::instrument(skip(conn))]
+pub async fn set_comment_pinned(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    comment_id: CommentId,
+    pinned: bool,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE exercise_comment SET pinned = $1 WHERE id = $2 AND exercise_id = $3",
+        pinned,
+        comment_id.get(),
+        exercise_id.get(),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set comment pinned")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns `false` if there's no such comment on the exercise.
+#[tracing::instrument(skip(conn))]
+pub async fn delete_comment(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    comment_id: CommentId,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "DELETE FROM exercise_comment WHERE id = $1 AND exercise_id = $2",
+        comment_id.get(),
+        exercise_id.get(),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to delete comment")?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -1,4 +1,5 @@
 use eyre::{Result, WrapErr};
+use serde_json::Value;
 use sqlx::postgres::PgConnection;
 
 use sql_grimoire_id::Id;
@@ -7,7 +8,8 @@ use crate::models::{
     Queryable,
     exercise::{
         Exercise, ExerciseId, ExerciseListItem, ExerciseSchema, ExerciseSchemaId,
-        ExerciseSchemaListItem, NewExercise,
+        ExerciseSchemaListItem, ExerciseTranslation, NewExercise, NewExerciseTranslation,
+        SqlDialect,
     },
 };
 
@@ -15,12 +17,13 @@ type ExerciseListItemInner = <ExerciseListItem as Queryable>::Inner;
 type ExerciseInner = <Exercise as Queryable>::Inner;
 type ExerciseSchemaListItemInner = <ExerciseSchemaListItem as Queryable>::Inner;
 type ExerciseSchemaInner = <ExerciseSchema as Queryable>::Inner;
+type ExerciseTranslationInner = <ExerciseTranslation as Queryable>::Inner;
 
 #[tracing::instrument(skip(conn))]
 pub async fn get_exercise_schemas(conn: &mut PgConnection) -> Result<Vec<ExerciseSchemaListItem>> {
     let schemas = sqlx::query_as!(
         ExerciseSchemaListItemInner,
-        "SELECT id, name FROM exercise_schema",
+        "SELECT id, name, dialect FROM exercise_schema",
     )
     .fetch_all(conn)
     .await
@@ -36,7 +39,7 @@ pub async fn get_exercise_schema(
 ) -> Result<Option<ExerciseSchema>> {
     let schema = sqlx::query_as!(
         ExerciseSchemaInner,
-        "SELECT id, name, schema FROM exercise_schema WHERE id = $1",
+        "SELECT id, name, schema, dialect FROM exercise_schema WHERE id = $1",
         id.get(),
     )
     .fetch_optional(conn)
@@ -52,13 +55,17 @@ pub async fn create_exercise_schema(
     conn: &mut PgConnection,
     name: String,
     schema: String,
+    dialect: SqlDialect,
 ) -> Result<ExerciseSchema> {
     Ok(Queryable::parse(
         sqlx::query_as!(
             ExerciseSchemaInner,
-            "INSERT INTO exercise_schema (name, schema) VALUES ($1, $2) RETURNING id, name, schema",
+            "INSERT INTO exercise_schema (name, schema, dialect)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, schema, dialect",
             name,
             schema,
+            dialect.to_string(),
         )
         .fetch_one(conn)
         .await
@@ -71,7 +78,7 @@ pub async fn update_exercise_schema(
     conn: &mut PgConnection,
     schema: ExerciseSchema,
 ) -> Result<ExerciseSchema> {
-    let (id, name, schema) = schema.dissolve();
+    let (id, name, schema, dialect) = schema.dissolve();
 
     Ok(Queryable::parse(
         sqlx::query_as!(
@@ -80,12 +87,14 @@ pub async fn update_exercise_schema(
                 exercise_schema
             SET
                 name = $1,
-                schema = $2
+                schema = $2,
+                dialect = $3
             WHERE
-                id = $3
-            RETURNING id, name, schema",
+                id = $4
+            RETURNING id, name, schema, dialect",
             name,
             schema,
+            dialect.to_string(),
             id.get(),
         )
         .fetch_one(conn)
@@ -94,19 +103,26 @@ pub async fn update_exercise_schema(
     ))
 }
 
+/// Lists all exercises, with names translated to `locale` where possible.
 #[tracing::instrument(skip(conn))]
 pub async fn get_exercise_list(
     conn: &mut PgConnection,
     user_id: &str,
+    locale: &str,
 ) -> Result<Vec<ExerciseListItem>> {
     let exercises = sqlx::query_as!(
         ExerciseListItemInner,
         r#"SELECT
             exercise.id,
-            exercise.name,
+            COALESCE(exercise_translation.name, exercise.name) AS "name!",
             'correct' = ANY(user_solution.status) AS solved,
-            exercise.published_at IS NOT NULL AS "published!"
+            exercise.published_at IS NOT NULL AS "published!",
+            exercise.author_id,
+            exercise_bookmark.user_id IS NOT NULL AS "bookmarked!"
         FROM exercise
+        LEFT OUTER JOIN exercise_bookmark ON
+            exercise.id = exercise_bookmark.exercise_id
+            AND exercise_bookmark.user_id = $1
         LEFT OUTER JOIN (
             SELECT
                 exercise_id,
@@ -116,9 +132,13 @@ pub async fn get_exercise_list(
                 user_id = $1
             GROUP BY exercise_id
         ) AS user_solution ON exercise.id = user_solution.exercise_id
-        ORDER BY exercise.name
+        LEFT OUTER JOIN exercise_translation ON
+            exercise.id = exercise_translation.exercise_id
+            AND exercise_translation.locale = $2
+        ORDER BY 2
         "#,
         user_id,
+        locale,
     )
     .fetch_all(conn)
     .await
@@ -131,7 +151,9 @@ pub async fn get_exercise_list(
 pub async fn get_exercise(conn: &mut PgConnection, id: ExerciseId) -> Result<Option<Exercise>> {
     let exercise = sqlx::query_as!(
         ExerciseInner,
-        "SELECT id, name, schema_id, question, expected_query, expected_result, published_at
+        "SELECT
+            id, name, schema_id, question, hint, editorial,
+            expected_query, expected_result, published_at, author_id
         FROM exercise
         WHERE id = $1",
         id.get(),
@@ -145,8 +167,12 @@ pub async fn get_exercise(conn: &mut PgConnection, id: ExerciseId) -> Result<Opt
 }
 
 #[tracing::instrument(skip(conn))]
-pub async fn create_exercise(conn: &mut PgConnection, exercise: NewExercise) -> Result<Exercise> {
-    let (schema_id, name, question, expected_query, expected_result, published_at) =
+pub async fn create_exercise(
+    conn: &mut PgConnection,
+    exercise: NewExercise,
+    author_id: &str,
+) -> Result<Exercise> {
+    let (schema_id, name, question, hint, editorial, expected_query, expected_result, published_at) =
         exercise.dissolve();
 
     let expected_result: serde_json::Value = serde_json::from_str(&expected_result)
@@ -156,17 +182,24 @@ pub async fn create_exercise(conn: &mut PgConnection, exercise: NewExercise) ->
         sqlx::query_as!(
             ExerciseInner,
             "INSERT INTO exercise
-                (schema_id, name, question, expected_query, expected_result, published_at)
+                (
+                    schema_id, name, question, hint, editorial,
+                    expected_query, expected_result, published_at, author_id
+                )
             VALUES
-                ($1, $2, $3, $4, $5, $6)
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING
-                id, schema_id, name, question, expected_query, expected_result, published_at",
+                id, schema_id, name, question, hint, editorial,
+                expected_query, expected_result, published_at, author_id",
             schema_id.get(),
             name,
             question,
+            hint,
+            editorial,
             expected_query,
             expected_result,
             published_at,
+            author_id,
         )
         .fetch_one(conn)
         .await
@@ -176,8 +209,19 @@ pub async fn create_exercise(conn: &mut PgConnection, exercise: NewExercise) ->
 
 #[tracing::instrument(skip(conn))]
 pub async fn update_exercise(conn: &mut PgConnection, exercise: Exercise) -> Result<Exercise> {
-    let (id, schema_id, name, question, expected_query, expected_result, published_at) =
-        exercise.dissolve();
+    let (
+        id,
+        schema_id,
+        name,
+        question,
+        hint,
+        editorial,
+        expected_query,
+        expected_result,
+        published_at,
+        // authorship doesn't change
+        _author_id,
+    ) = exercise.dissolve();
 
     Ok(Queryable::parse(
         sqlx::query_as!(
@@ -186,16 +230,21 @@ pub async fn update_exercise(conn: &mut PgConnection, exercise: Exercise) -> Res
             SET
                 name = $1,
                 question = $2,
-                schema_id = $3,
-                expected_query = $4,
-                expected_result = $5,
-                published_at = $6
+                hint = $3,
+                editorial = $4,
+                schema_id = $5,
+                expected_query = $6,
+                expected_result = $7,
+                published_at = $8
             WHERE
-                id = $7
+                id = $9
             RETURNING
-                id, schema_id, name, question, expected_query, expected_result, published_at",
+                id, schema_id, name, question, hint, editorial,
+                expected_query, expected_result, published_at, author_id",
             name,
             question,
+            hint,
+            editorial,
             schema_id.get(),
             expected_query,
             expected_result,
@@ -207,3 +256,166 @@ pub async fn update_exercise(conn: &mut PgConnection, exercise: Exercise) -> Res
         .wrap_err("Failed to update exercise")?,
     ))
 }
+
+#[tracing::instrument(skip(conn))]
+pub async fn count_schema_exercises(
+    conn: &mut PgConnection,
+    schema_id: ExerciseSchemaId,
+) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM exercise WHERE schema_id = $1"#,
+        schema_id.get(),
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count schema exercises")?;
+
+    Ok(count)
+}
+
+/// Copies all exercises of one schema onto another one.
+///
+/// Copies are always created unpublished and belong to `author_id`.
+/// Returns the IDs and expected queries of the copies.
+#[tracing::instrument(skip(conn))]
+pub async fn clone_schema_exercises(
+    conn: &mut PgConnection,
+    from: ExerciseSchemaId,
+    to: ExerciseSchemaId,
+    author_id: &str,
+) -> Result<Vec<(ExerciseId, String)>> {
+    let copies = sqlx::query!(
+        "INSERT INTO exercise
+            (
+                schema_id, name, question, hint, editorial,
+                expected_query, expected_result, published_at, author_id
+            )
+        SELECT
+            $2, name, question, hint, editorial, expected_query, expected_result, NULL, $3
+        FROM exercise
+        WHERE schema_id = $1
+        ORDER BY created_at
+        RETURNING id, expected_query",
+        from.get(),
+        to.get(),
+        author_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to clone schema exercises")?;
+
+    Ok(copies
+        .into_iter()
+        .map(|copy| (copy.id.into(), copy.expected_query))
+        .collect())
+}
+
+#[tracing::instrument(skip(conn, expected_result))]
+pub async fn set_expected_result(
+    conn: &mut PgConnection,
+    id: ExerciseId,
+    expected_result: &Value,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE exercise SET expected_result = $2 WHERE id = $1",
+        id.get(),
+        expected_result,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set expected result")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_exercise_translations(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+) -> Result<Vec<ExerciseTranslation>> {
+    let translations = sqlx::query_as!(
+        ExerciseTranslationInner,
+        "SELECT exercise_id, locale, name, question, hint, editorial
+        FROM exercise_translation
+        WHERE exercise_id = $1
+        ORDER BY locale",
+        exercise_id.get(),
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get exercise translations")?;
+
+    Ok(translations.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_exercise_translation(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    locale: &str,
+) -> Result<Option<ExerciseTranslation>> {
+    let translation = sqlx::query_as!(
+        ExerciseTranslationInner,
+        "SELECT exercise_id, locale, name, question, hint, editorial
+        FROM exercise_translation
+        WHERE exercise_id = $1 AND locale = $2",
+        exercise_id.get(),
+        locale,
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get exercise translation")?
+    .map(Queryable::parse);
+
+    Ok(translation)
+}
+
+/// Saves the translation of an exercise to `locale`, replacing the existing one.
+///
+/// Empty translations are deleted instead, so they don't show up as translated.
+#[tracing::instrument(skip(conn))]
+pub async fn save_exercise_translation(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    locale: &str,
+    translation: NewExerciseTranslation,
+) -> Result<()> {
+    if translation.is_empty() {
+        sqlx::query!(
+            "DELETE FROM exercise_translation WHERE exercise_id = $1 AND locale = $2",
+            exercise_id.get(),
+            locale,
+        )
+        .execute(conn)
+        .await
+        .wrap_err("Failed to delete exercise translation")?;
+
+        return Ok(());
+    }
+
+    let (name, question, hint, editorial) = translation.dissolve();
+
+    sqlx::query!(
+        "INSERT INTO exercise_translation
+            (exercise_id, locale, name, question, hint, editorial)
+        VALUES
+            ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (exercise_id, locale) DO UPDATE
+        SET
+            name = EXCLUDED.name,
+            question = EXCLUDED.question,
+            hint = EXCLUDED.hint,
+            editorial = EXCLUDED.editorial",
+        exercise_id.get(),
+        locale,
+        name,
+        question,
+        hint,
+        editorial,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to save exercise translation")?;
+
+    Ok(())
+}
@@ -1,14 +1,27 @@
 use eyre::{Result, WrapErr};
 use serde_json::Value;
-use sqlx::postgres::PgConnection;
+use sqlx::{PgPool, postgres::PgConnection};
+use time::PrimitiveDateTime;
+use tracing::{error, info};
 
 use sql_grimoire_id::Id;
 
-use crate::models::{
-    Queryable, exercise::ExerciseId, exercise_solution::UserSolution, user::UserClaims,
+use crate::{
+    fingerprint::fingerprint,
+    models::{
+        Queryable,
+        exercise::{ExerciseId, SqlDialect},
+        exercise_solution::{
+            CommunitySolution, UserSolution, UserSolutionId, UserSolutionListItem, UserSubmission,
+        },
+        user::UserClaims,
+    },
 };
 
 type UserSolutionInner = <UserSolution as Queryable>::Inner;
+type UserSolutionListItemInner = <UserSolutionListItem as Queryable>::Inner;
+type UserSubmissionInner = <UserSubmission as Queryable>::Inner;
+type CommunitySolutionInner = <CommunitySolution as Queryable>::Inner;
 
 #[tracing::instrument(skip(conn))]
 pub async fn get_last_user_solution(
@@ -18,7 +31,7 @@ pub async fn get_last_user_solution(
 ) -> Result<Option<UserSolution>> {
     let solution = sqlx::query_as!(
         UserSolutionInner,
-        "SELECT id, user_id, exercise_id, query, result, status
+        "SELECT id, user_id, exercise_id, query, result, status, created_at
         FROM user_solution
         WHERE user_id = $1 AND exercise_id = $2
         ORDER BY created_at DESC
@@ -34,11 +47,112 @@ pub async fn get_last_user_solution(
     Ok(solution)
 }
 
+/// Lists user's attempts at the exercise, newest first.
+#[tracing::instrument(skip(conn))]
+pub async fn get_user_solutions(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<UserSolutionListItem>> {
+    let solutions = sqlx::query_as!(
+        UserSolutionListItemInner,
+        "SELECT id, query, status, created_at
+        FROM user_solution
+        WHERE user_id = $1 AND exercise_id = $2
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4",
+        user_id,
+        exercise_id.get(),
+        limit,
+        offset,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get user solutions")?;
+
+    Ok(solutions.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn count_user_solutions(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!"
+        FROM user_solution
+        WHERE user_id = $1 AND exercise_id = $2"#,
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count user solutions")?;
+
+    Ok(count)
+}
+
+/// Lists all of the user's submissions, newest first.
+#[tracing::instrument(skip(conn))]
+pub async fn get_user_submissions(
+    conn: &mut PgConnection,
+    user_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<UserSubmission>> {
+    let submissions = sqlx::query_as!(
+        UserSubmissionInner,
+        "SELECT
+            user_solution.id,
+            user_solution.exercise_id,
+            exercise.name AS exercise_name,
+            exercise_schema.dialect,
+            user_solution.query,
+            user_solution.status,
+            user_solution.created_at
+        FROM user_solution
+        JOIN exercise ON exercise.id = user_solution.exercise_id
+        JOIN exercise_schema ON exercise_schema.id = exercise.schema_id
+        WHERE user_solution.user_id = $1
+        ORDER BY user_solution.created_at DESC
+        LIMIT $2 OFFSET $3",
+        user_id,
+        limit,
+        offset,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get user submissions")?;
+
+    Ok(submissions.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn count_user_submissions(conn: &mut PgConnection, user_id: &str) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!"
+        FROM user_solution
+        WHERE user_id = $1"#,
+        user_id,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count user submissions")?;
+
+    Ok(count)
+}
+
+/// Queries which can't be parsed get an empty fingerprint, so that `NULL` is only left on
+/// submissions from before fingerprints were stored, see [`backfill_query_fingerprints`].
 #[tracing::instrument(skip(conn))]
 pub async fn create_user_solution(
     conn: &mut PgConnection,
     user_id: &str,
     exercise_id: ExerciseId,
+    dialect: SqlDialect,
     query: &str,
     result: Value,
     status: &str,
@@ -46,12 +160,13 @@ pub async fn create_user_solution(
     Ok(Queryable::parse(
         sqlx::query_as!(
             UserSolutionInner,
-            "INSERT INTO user_solution (user_id, exercise_id, query, result, status)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, user_id, exercise_id, query, result, status",
+            "INSERT INTO user_solution (user_id, exercise_id, query, query_fingerprint, result, status)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, exercise_id, query, result, status, created_at",
             user_id,
             exercise_id.get(),
             query,
+            fingerprint(query, dialect).unwrap_or_default(),
             result,
             status,
         )
@@ -61,35 +176,297 @@ pub async fn create_user_solution(
     ))
 }
 
+const FINGERPRINT_BACKFILL_BATCH: i64 = 500;
+
+/// Fingerprints a batch of submissions made before fingerprints were stored,
+/// returning how many there were.
+#[tracing::instrument(skip(conn))]
+async fn backfill_query_fingerprints_batch(conn: &mut PgConnection) -> Result<usize> {
+    let solutions = sqlx::query!(
+        "SELECT user_solution.id, user_solution.query, exercise_schema.dialect
+        FROM user_solution
+        JOIN exercise ON exercise.id = user_solution.exercise_id
+        JOIN exercise_schema ON exercise_schema.id = exercise.schema_id
+        WHERE user_solution.query_fingerprint IS NULL
+        LIMIT $1",
+        FINGERPRINT_BACKFILL_BATCH,
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .wrap_err("Failed to get submissions without fingerprints")?;
+
+    let count = solutions.len();
+
+    for solution in solutions {
+        sqlx::query!(
+            "UPDATE user_solution SET query_fingerprint = $2 WHERE id = $1",
+            solution.id,
+            fingerprint(&solution.query, solution.dialect.into()).unwrap_or_default(),
+        )
+        .execute(&mut *conn)
+        .await
+        .wrap_err("Failed to save query fingerprint")?;
+    }
+
+    Ok(count)
+}
+
+/// Fingerprints the submissions made before fingerprints were stored, so they are grouped
+/// along with the newer ones. Runs once on startup, in the background.
+pub async fn backfill_query_fingerprints(pool: PgPool) {
+    let mut backfilled = 0;
+
+    loop {
+        let result = match pool.acquire().await {
+            Ok(mut conn) => backfill_query_fingerprints_batch(&mut conn).await,
+            Err(err) => Err(err).wrap_err("Failed to acquire DB connection"),
+        };
+
+        match result {
+            Ok(0) => break,
+            Ok(count) => backfilled += count,
+            Err(err) => {
+                error!(?err, "Failed to backfill query fingerprints");
+                return;
+            }
+        }
+    }
+
+    if backfilled > 0 {
+        info!(backfilled, "Backfilled query fingerprints");
+    }
+}
+
+/// The user's unsubmitted query for the exercise, if the editor has autosaved one.
 #[tracing::instrument(skip(conn))]
-pub async fn update_user_solution(
+pub async fn get_user_draft(
     conn: &mut PgConnection,
-    solution: UserSolution,
-) -> Result<UserSolution> {
-    let (id, user_id, exercise_id, query, result, status) = solution.dissolve();
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<Option<String>> {
+    let query = sqlx::query_scalar!(
+        "SELECT query
+        FROM user_draft
+        WHERE user_id = $1 AND exercise_id = $2",
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get user draft")?;
 
-    Ok(Queryable::parse(
-        sqlx::query_as!(
-            UserSolutionInner,
-            "UPDATE user_solution
-            SET
-                query = $1,
-                result = $2,
-                status = $3
+    Ok(query)
+}
+
+/// Saves the draft unless something was submitted after `last_submission`, the latest
+/// submission when the editor loaded, returning whether it was saved. A save still on its way
+/// when the user submits would otherwise bring back the draft the submission deleted.
+#[tracing::instrument(skip(conn, query))]
+pub async fn save_user_draft(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    query: &str,
+    last_submission: Option<UserSolutionId>,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "INSERT INTO user_draft (user_id, exercise_id, query)
+        SELECT $1, $2, $3
+        WHERE NOT EXISTS (
+            SELECT 1
+            FROM user_solution
+            WHERE user_id = $1
+                AND exercise_id = $2
+                AND created_at > COALESCE(
+                    (SELECT created_at FROM user_solution WHERE id = $4),
+                    '-infinity'
+                )
+        )
+        ON CONFLICT (user_id, exercise_id) DO UPDATE
+        SET query = EXCLUDED.query",
+        user_id,
+        exercise_id.get(),
+        query,
+        last_submission.map(|id| id.get()),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to save user draft")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn delete_user_draft(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<()> {
+    sqlx::query!(
+        "DELETE FROM user_draft
+        WHERE user_id = $1 AND exercise_id = $2",
+        user_id,
+        exercise_id.get(),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to delete user draft")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn has_solved_exercise(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<bool> {
+    let solved = sqlx::query_scalar!(
+        r#"SELECT EXISTS(
+            SELECT 1
+            FROM user_solution
+            WHERE user_id = $1 AND exercise_id = $2 AND status = 'correct'
+        ) AS "solved!""#,
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to check if user solved exercise")?;
+
+    Ok(solved)
+}
+
+/// Whether the exercise was solved at `since` or later, i.e. during a review.
+#[tracing::instrument(skip(conn))]
+pub async fn has_solved_since(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    since: PrimitiveDateTime,
+) -> Result<bool> {
+    let solved = sqlx::query_scalar!(
+        r#"SELECT EXISTS(
+            SELECT 1
+            FROM user_solution
             WHERE
-                id = $4
-                AND user_id = $5
-                AND exercise_id = $6
-            RETURNING id, user_id, exercise_id, query, result, status",
-            query,
-            result,
-            status,
-            id.get(),
-            user_id,
-            exercise_id.get(),
+                user_id = $1
+                AND exercise_id = $2
+                AND status = 'correct'
+                AND created_at >= $3
+        ) AS "solved!""#,
+        user_id,
+        exercise_id.get(),
+        since,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to check if user solved exercise")?;
+
+    Ok(solved)
+}
+
+/// Groups correct queries submitted for the exercise by their fingerprints,
+/// most upvoted first, then the most common.
+///
+/// Queries which couldn't be parsed are only compared ignoring whitespace.
+#[tracing::instrument(skip(conn))]
+pub async fn get_community_solutions(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    limit: i64,
+) -> Result<Vec<CommunitySolution>> {
+    let solutions = sqlx::query_as!(
+        CommunitySolutionInner,
+        r#"WITH clusters AS (
+            SELECT
+                COALESCE(
+                    NULLIF(query_fingerprint, ''),
+                    REGEXP_REPLACE(BTRIM(query), '\s+', ' ', 'g')
+                ) AS fingerprint,
+                (ARRAY_AGG(query ORDER BY created_at))[1] AS example,
+                (ARRAY_AGG(user_id ORDER BY created_at))[1] AS author_id,
+                COUNT(DISTINCT user_id) AS users
+            FROM user_solution
+            WHERE
+                exercise_id = $2
+                AND status = 'correct'
+            GROUP BY 1
         )
-        .fetch_one(conn)
-        .await
-        .wrap_err("Failed to update user solution")?,
-    ))
+        SELECT
+            clusters.fingerprint AS "fingerprint!",
+            clusters.example AS "example!",
+            CASE WHEN users.share_solution_name THEN users.display_name END AS author_name,
+            clusters.users AS "users!",
+            COUNT(solution_vote.user_id) AS "votes!",
+            COALESCE(BOOL_OR(solution_vote.user_id = $1), false) AS "voted!"
+        FROM clusters
+        JOIN users ON users.id = clusters.author_id
+        LEFT OUTER JOIN solution_vote ON
+            solution_vote.exercise_id = $2
+            AND solution_vote.fingerprint = clusters.fingerprint
+        GROUP BY clusters.fingerprint, clusters.example, users.id, clusters.users
+        ORDER BY 5 DESC, 4 DESC, 1
+        LIMIT $3
+        "#,
+        user_id,
+        exercise_id.get(),
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get community solutions")?;
+
+    Ok(solutions.into_iter().map(Queryable::parse).collect())
+}
+
+/// Upvotes the cluster of correct queries, or takes the upvote back if it was given already.
+///
+/// Returns `false` if there's no correct query with the fingerprint.
+#[tracing::instrument(skip(conn))]
+pub async fn toggle_solution_vote(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    fingerprint: &str,
+) -> Result<bool> {
+    let removed = sqlx::query!(
+        "DELETE FROM solution_vote
+        WHERE user_id = $1 AND exercise_id = $2 AND fingerprint = $3",
+        user_id,
+        exercise_id.get(),
+        fingerprint,
+    )
+    .execute(&mut *conn)
+    .await
+    .wrap_err("Failed to remove solution vote")?;
+
+    if removed.rows_affected() > 0 {
+        return Ok(true);
+    }
+
+    let added = sqlx::query!(
+        r#"INSERT INTO solution_vote (user_id, exercise_id, fingerprint)
+        SELECT $1, $2, $3
+        WHERE EXISTS(
+            SELECT 1
+            FROM user_solution
+            WHERE
+                exercise_id = $2
+                AND status = 'correct'
+                AND COALESCE(
+                    NULLIF(query_fingerprint, ''),
+                    REGEXP_REPLACE(BTRIM(query), '\s+', ' ', 'g')
+                ) = $3
+        )"#,
+        user_id,
+        exercise_id.get(),
+        fingerprint,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to add solution vote")?;
+
+    Ok(added.rows_affected() > 0)
 }
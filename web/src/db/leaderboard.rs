@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use eyre::{Result, WrapErr};
+use sqlx::{PgPool, postgres::PgConnection};
+use tracing::{debug, error};
+
+use sql_grimoire_id::Id;
+
+use crate::models::{
+    Queryable,
+    cohort::CohortId,
+    exercise::ExerciseSchemaId,
+    leaderboard::{LeaderboardEntry, LeaderboardMetric},
+};
+
+type LeaderboardEntryInner = <LeaderboardEntry as Queryable>::Inner;
+
+/// Ranks users by the metric, optionally only on one track and/or within one cohort.
+///
+/// Reads from the `leaderboard_stats` materialized view, so results lag behind a bit.
+#[tracing::instrument(skip(conn))]
+pub async fn get_leaderboard(
+    conn: &mut PgConnection,
+    metric: LeaderboardMetric,
+    schema_id: Option<ExerciseSchemaId>,
+    cohort_id: Option<CohortId>,
+    limit: i64,
+) -> Result<Vec<LeaderboardEntry>> {
+    let entries = sqlx::query_as!(
+        LeaderboardEntryInner,
+        r#"SELECT
+            RANK() OVER (ORDER BY CASE $3
+                WHEN 'score' THEN totals.score
+                WHEN 'first_try' THEN totals.first_try
+                ELSE totals.solved
+            END DESC) AS "rank!",
+            totals.user_id AS "user_id!",
+            CASE WHEN users.leaderboard_opt_out THEN NULL ELSE users.display_name END AS display_name,
+            totals.solved AS "solved!",
+            totals.score AS "score!",
+            totals.first_try AS "first_try!"
+        FROM (
+            SELECT
+                user_id,
+                SUM(solved)::bigint AS solved,
+                SUM(score)::bigint AS score,
+                SUM(first_try)::bigint AS first_try
+            FROM leaderboard_stats
+            WHERE
+                ($1::uuid IS NULL OR schema_id = $1)
+                AND ($2::uuid IS NULL OR user_id IN (
+                    SELECT user_id FROM cohort_member WHERE cohort_id = $2
+                ))
+            GROUP BY user_id
+        ) AS totals
+        JOIN users ON users.id = totals.user_id
+        ORDER BY 1, totals.user_id
+        LIMIT $4
+        "#,
+        schema_id.map(|id| id.get()),
+        cohort_id.map(|id| id.get()),
+        metric.as_str(),
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get leaderboard")?;
+
+    Ok(entries.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn refresh_leaderboard(conn: &mut PgConnection) -> Result<()> {
+    sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY leaderboard_stats")
+        .execute(conn)
+        .await
+        .wrap_err("Failed to refresh leaderboard")?;
+
+    Ok(())
+}
+
+/// Keeps the leaderboard fresh, never returns.
+pub async fn refresh_leaderboard_periodically(pool: PgPool, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        interval.tick().await;
+
+        let result = match pool.acquire().await {
+            Ok(mut conn) => refresh_leaderboard(&mut conn).await,
+            Err(err) => Err(err).wrap_err("Failed to acquire DB connection"),
+        };
+
+        match result {
+            Ok(()) => debug!("Refreshed leaderboard"),
+            Err(err) => error!(?err, "Failed to refresh leaderboard"),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_password_hash(conn: &mut PgConnection, user_id: &str) -> Result<Option<String>> {
+    let password_hash = sqlx::query_scalar!(
+        "SELECT password_hash
+        FROM local_account
+        WHERE user_id = $1",
+        user_id,
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get password hash")?;
+
+    Ok(password_hash)
+}
+
+/// Adds a password login to the user, who has to be saved already.
+#[tracing::instrument(skip(conn, password_hash))]
+pub async fn create_local_account(
+    conn: &mut PgConnection,
+    user_id: &str,
+    password_hash: &str,
+) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO local_account (user_id, password_hash)
+        VALUES ($1, $2)",
+        user_id,
+        password_hash,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to create local account")?;
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+use time::PrimitiveDateTime;
+
+use crate::models::{
+    Queryable,
+    progress::{ActivityDay, FirstTryStats, TrackProgress, UnsolvedExercise},
+};
+
+type TrackProgressInner = <TrackProgress as Queryable>::Inner;
+type FirstTryStatsInner = <FirstTryStats as Queryable>::Inner;
+type ActivityDayInner = <ActivityDay as Queryable>::Inner;
+type UnsolvedExerciseInner = <UnsolvedExercise as Queryable>::Inner;
+
+/// Counts published, attempted and solved exercises of every track (exercise schema).
+#[tracing::instrument(skip(conn))]
+pub async fn get_track_progress(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Vec<TrackProgress>> {
+    let progress = sqlx::query_as!(
+        TrackProgressInner,
+        r#"SELECT
+            exercise_schema.id AS schema_id,
+            exercise_schema.name,
+            COUNT(exercise.id) AS "total!",
+            COUNT(attempts.exercise_id) AS "attempted!",
+            COUNT(attempts.exercise_id) FILTER (WHERE attempts.solved) AS "solved!"
+        FROM exercise_schema
+        JOIN exercise ON
+            exercise.schema_id = exercise_schema.id
+            AND exercise.published_at IS NOT NULL
+        LEFT OUTER JOIN (
+            SELECT
+                exercise_id,
+                BOOL_OR(status = 'correct') AS solved
+            FROM user_solution
+            WHERE
+                user_id = $1
+            GROUP BY exercise_id
+        ) AS attempts ON exercise.id = attempts.exercise_id
+        GROUP BY exercise_schema.id, exercise_schema.name
+        ORDER BY exercise_schema.name
+        "#,
+        user_id,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get track progress")?;
+
+    Ok(progress.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_first_try_stats(conn: &mut PgConnection, user_id: &str) -> Result<FirstTryStats> {
+    let stats = sqlx::query_as!(
+        FirstTryStatsInner,
+        r#"SELECT
+            COUNT(*) AS "attempted!",
+            COUNT(*) FILTER (WHERE status = 'correct') AS "first_try!"
+        FROM (
+            SELECT DISTINCT ON (exercise_id)
+                status
+            FROM user_solution
+            WHERE
+                user_id = $1
+            ORDER BY exercise_id, created_at
+        ) AS first_attempts
+        "#,
+        user_id,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to get first try stats")?;
+
+    Ok(Queryable::parse(stats))
+}
+
+/// Counts attempts per day, for days since `since` with at least one attempt.
+#[tracing::instrument(skip(conn))]
+pub async fn get_activity(
+    conn: &mut PgConnection,
+    user_id: &str,
+    since: PrimitiveDateTime,
+) -> Result<Vec<ActivityDay>> {
+    let activity = sqlx::query_as!(
+        ActivityDayInner,
+        r#"SELECT
+            created_at::date AS "day!",
+            COUNT(*) AS "attempts!"
+        FROM user_solution
+        WHERE
+            user_id = $1
+            AND created_at >= $2
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+        user_id,
+        since,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get activity")?;
+
+    Ok(activity.into_iter().map(Queryable::parse).collect())
+}
+
+/// Lists attempted but not yet solved exercises, most recently attempted first.
+#[tracing::instrument(skip(conn))]
+pub async fn get_recent_unsolved(
+    conn: &mut PgConnection,
+    user_id: &str,
+    locale: &str,
+    limit: i64,
+) -> Result<Vec<UnsolvedExercise>> {
+    let exercises = sqlx::query_as!(
+        UnsolvedExerciseInner,
+        r#"SELECT
+            exercise.id,
+            COALESCE(exercise_translation.name, exercise.name) AS "name!",
+            MAX(user_solution.created_at) AS "last_attempt_at!"
+        FROM user_solution
+        JOIN exercise ON exercise.id = user_solution.exercise_id
+        LEFT OUTER JOIN exercise_translation ON
+            exercise.id = exercise_translation.exercise_id
+            AND exercise_translation.locale = $2
+        WHERE
+            user_solution.user_id = $1
+        GROUP BY exercise.id, exercise.name, exercise_translation.name
+        HAVING NOT BOOL_OR(user_solution.status = 'correct')
+        ORDER BY 3 DESC
+        LIMIT $3
+        "#,
+        user_id,
+        locale,
+        limit,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get recent unsolved exercises")?;
+
+    Ok(exercises.into_iter().map(Queryable::parse).collect())
+}
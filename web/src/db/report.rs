@@ -0,0 +1,109 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+
+use sql_grimoire_id::Id;
+
+use crate::models::{
+    Queryable,
+    exercise::ExerciseId,
+    report::{ExerciseReport, ReportId, ReportStatus},
+};
+
+type ExerciseReportInner = <ExerciseReport as Queryable>::Inner;
+
+#[tracing::instrument(skip(conn, query, message))]
+pub async fn create_report(
+    conn: &mut PgConnection,
+    exercise_id: ExerciseId,
+    user_id: &str,
+    query: &str,
+    message: &str,
+) -> Result<ReportId> {
+    let id = sqlx::query_scalar!(
+        "INSERT INTO exercise_report (exercise_id, user_id, query, message)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id",
+        exercise_id.get(),
+        user_id,
+        query,
+        message,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to create exercise report")?;
+
+    Ok(id.into())
+}
+
+/// Lists reports with the status, newest first.
+#[tracing::instrument(skip(conn))]
+pub async fn get_reports(
+    conn: &mut PgConnection,
+    status: ReportStatus,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ExerciseReport>> {
+    let reports = sqlx::query_as!(
+        ExerciseReportInner,
+        "SELECT
+            exercise_report.id,
+            exercise_report.exercise_id,
+            exercise.name AS exercise_name,
+            exercise_schema.dialect,
+            exercise_report.user_id,
+            users.display_name AS user_display_name,
+            exercise_report.query,
+            exercise_report.message,
+            exercise_report.status,
+            exercise_report.created_at
+        FROM exercise_report
+        JOIN exercise ON exercise.id = exercise_report.exercise_id
+        JOIN exercise_schema ON exercise_schema.id = exercise.schema_id
+        JOIN users ON users.id = exercise_report.user_id
+        WHERE exercise_report.status = $1
+        ORDER BY exercise_report.created_at DESC
+        LIMIT $2 OFFSET $3",
+        status.to_string(),
+        limit,
+        offset,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get exercise reports")?;
+
+    Ok(reports.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn count_reports(conn: &mut PgConnection, status: ReportStatus) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!"
+        FROM exercise_report
+        WHERE status = $1"#,
+        status.to_string(),
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count exercise reports")?;
+
+    Ok(count)
+}
+
+/// Returns `false` if there's no such report.
+#[tracing::instrument(skip(conn))]
+pub async fn set_report_status(
+    conn: &mut PgConnection,
+    report_id: ReportId,
+    status: ReportStatus,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE exercise_report SET status = $1 WHERE id = $2",
+        status.to_string(),
+        report_id.get(),
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set exercise report status")?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,156 @@
+use eyre::{Result, WrapErr};
+use sqlx::postgres::PgConnection;
+use time::PrimitiveDateTime;
+
+use sql_grimoire_id::Id;
+
+use crate::{
+    models::{Queryable, exercise::ExerciseId, review::ReviewCard},
+    review::ReviewSchedule,
+};
+
+type ReviewCardInner = <ReviewCard as Queryable>::Inner;
+
+/// Starts reviewing the exercise, a day after it was first solved.
+#[tracing::instrument(skip(conn))]
+pub async fn create_review_card(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<()> {
+    let initial = ReviewSchedule::INITIAL;
+
+    sqlx::query!(
+        "INSERT INTO review_card (user_id, exercise_id, ease, interval_days, repetitions, due_at)
+        VALUES ($1, $2, $3, $4, $5, now() + make_interval(days => $4))
+        ON CONFLICT DO NOTHING",
+        user_id,
+        exercise_id.get(),
+        initial.ease,
+        initial.interval_days,
+        initial.repetitions,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to create review card")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_review_card(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> Result<Option<ReviewCard>> {
+    let card = sqlx::query_as!(
+        ReviewCardInner,
+        "SELECT ease, interval_days, repetitions, due_at, reviewed_at
+        FROM review_card
+        WHERE user_id = $1 AND exercise_id = $2",
+        user_id,
+        exercise_id.get(),
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get review card")?
+    .map(Queryable::parse);
+
+    Ok(card)
+}
+
+/// The published exercise which has been due for review the longest.
+#[tracing::instrument(skip(conn))]
+pub async fn get_next_due_review(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Option<ExerciseId>> {
+    let exercise_id = sqlx::query_scalar!(
+        "SELECT review_card.exercise_id
+        FROM review_card
+        JOIN exercise ON exercise.id = review_card.exercise_id
+        WHERE
+            review_card.user_id = $1
+            AND review_card.due_at <= now()
+            AND exercise.published_at IS NOT NULL
+        ORDER BY review_card.due_at
+        LIMIT 1",
+        user_id,
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get next due review")?;
+
+    Ok(exercise_id.map(Into::into))
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn count_due_reviews(conn: &mut PgConnection, user_id: &str) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!"
+        FROM review_card
+        JOIN exercise ON exercise.id = review_card.exercise_id
+        WHERE
+            review_card.user_id = $1
+            AND review_card.due_at <= now()
+            AND exercise.published_at IS NOT NULL"#,
+        user_id,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count due reviews")?;
+
+    Ok(count)
+}
+
+/// When the next review falls due, `None` if nothing has been solved yet.
+#[tracing::instrument(skip(conn))]
+pub async fn get_next_review_at(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Option<PrimitiveDateTime>> {
+    let due_at = sqlx::query_scalar!(
+        "SELECT MIN(review_card.due_at)
+        FROM review_card
+        JOIN exercise ON exercise.id = review_card.exercise_id
+        WHERE
+            review_card.user_id = $1
+            AND exercise.published_at IS NOT NULL",
+        user_id,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to get next review time")?;
+
+    Ok(due_at)
+}
+
+/// Records the review, the card falls due again after the new interval.
+#[tracing::instrument(skip(conn))]
+pub async fn save_review(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+    schedule: ReviewSchedule,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE review_card
+        SET
+            ease = $3,
+            interval_days = $4,
+            repetitions = $5,
+            due_at = now() + make_interval(days => $4),
+            reviewed_at = now()
+        WHERE user_id = $1 AND exercise_id = $2",
+        user_id,
+        exercise_id.get(),
+        schedule.ease,
+        schedule.interval_days,
+        schedule.repetitions,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to save review")?;
+
+    Ok(())
+}
@@ -3,22 +3,36 @@ use sqlx::postgres::PgConnection;
 
 use crate::models::{
     Queryable,
-    user::{User, UserClaims, UserRole},
+    user::{User, UserClaims, UserListItem, UserRole},
 };
 
 type UserInner = <User as Queryable>::Inner;
+type UserListItemInner = <UserListItem as Queryable>::Inner;
 
+/// Creates the user on the first login, later logins refresh the profile data from claims.
+///
+/// The display name is only refreshed until the user sets one themselves.
 #[tracing::instrument(skip(conn))]
-pub async fn create_user(conn: &mut PgConnection, user: &UserClaims) -> Result<User> {
+pub async fn save_user(conn: &mut PgConnection, user: &UserClaims) -> Result<User> {
     let user = sqlx::query_as!(
         UserInner,
-        "INSERT INTO users (id, role) VALUES ($1, $2) RETURNING id, role",
+        "INSERT INTO users (id, role, email, display_name)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (id) DO UPDATE SET
+            email = EXCLUDED.email,
+            display_name = CASE
+                WHEN users.display_name_custom THEN users.display_name
+                ELSE EXCLUDED.display_name
+            END
+        RETURNING id, role, locale, email, display_name, leaderboard_opt_out, share_solution_name",
         user.sub(),
         UserRole::User.to_string(),
+        user.email(),
+        user.name().as_deref(),
     )
     .fetch_one(conn)
     .await
-    .wrap_err("Failed to create user")
+    .wrap_err("Failed to save user")
     .map(Queryable::parse)?;
 
     Ok(user)
@@ -28,7 +42,9 @@ pub async fn create_user(conn: &mut PgConnection, user: &UserClaims) -> Result<U
 pub async fn get_user(conn: &mut PgConnection, user: &UserClaims) -> Result<Option<User>> {
     let user = sqlx::query_as!(
         UserInner,
-        "SELECT id, role FROM users WHERE id = $1",
+        "SELECT id, role, locale, email, display_name, leaderboard_opt_out, share_solution_name
+        FROM users
+        WHERE id = $1",
         user.sub(),
     )
     .fetch_optional(conn)
@@ -38,3 +54,287 @@ pub async fn get_user(conn: &mut PgConnection, user: &UserClaims) -> Result<Opti
 
     Ok(user)
 }
+
+#[tracing::instrument(skip(conn))]
+pub async fn get_user_by_id(conn: &mut PgConnection, user_id: &str) -> Result<Option<User>> {
+    let user = sqlx::query_as!(
+        UserInner,
+        "SELECT id, role, locale, email, display_name, leaderboard_opt_out, share_solution_name
+        FROM users
+        WHERE id = $1",
+        user_id,
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get user")?
+    .map(Queryable::parse);
+
+    Ok(user)
+}
+
+/// Records that the user was seen just now.
+///
+/// The timestamp is only updated once in a few minutes, so it doesn't cause a write on every request.
+#[tracing::instrument(skip(conn))]
+pub async fn touch_user(conn: &mut PgConnection, user_id: &str) -> Result<()> {
+    sqlx::query!(
+        "UPDATE users
+        SET last_seen_at = now()
+        WHERE
+            id = $1
+            AND (last_seen_at IS NULL OR last_seen_at < now() - interval '5 minutes')",
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to update user last seen time")?;
+
+    Ok(())
+}
+
+/// Lists users whose ID, email or display name contains `search` (case-insensitively),
+/// most recently seen first.
+#[tracing::instrument(skip(conn))]
+pub async fn get_users(
+    conn: &mut PgConnection,
+    search: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<UserListItem>> {
+    let users = sqlx::query_as!(
+        UserListItemInner,
+        r#"SELECT
+            users.id,
+            users.email,
+            users.display_name,
+            users.role,
+            users.created_at,
+            users.last_seen_at,
+            COUNT(user_solution.id) AS "solutions!",
+            COUNT(DISTINCT user_solution.exercise_id)
+                FILTER (WHERE user_solution.status = 'correct') AS "solved!"
+        FROM users
+        LEFT OUTER JOIN user_solution ON users.id = user_solution.user_id
+        WHERE
+            users.id ILIKE '%' || $1 || '%'
+            OR users.email ILIKE '%' || $1 || '%'
+            OR users.display_name ILIKE '%' || $1 || '%'
+        GROUP BY users.id
+        ORDER BY users.last_seen_at DESC NULLS LAST, users.created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        search,
+        limit,
+        offset,
+    )
+    .fetch_all(conn)
+    .await
+    .wrap_err("Failed to get users")?;
+
+    Ok(users.into_iter().map(Queryable::parse).collect())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn count_users(conn: &mut PgConnection, search: &str) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!"
+        FROM users
+        WHERE
+            id ILIKE '%' || $1 || '%'
+            OR email ILIKE '%' || $1 || '%'
+            OR display_name ILIKE '%' || $1 || '%'"#,
+        search,
+    )
+    .fetch_one(conn)
+    .await
+    .wrap_err("Failed to count users")?;
+
+    Ok(count)
+}
+
+/// Changes the user's role. Returns `false` if there's no such user.
+#[tracing::instrument(skip(conn))]
+pub async fn set_user_role(
+    conn: &mut PgConnection,
+    user_id: &str,
+    role: &UserRole,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE users SET role = $1 WHERE id = $2",
+        role.to_string(),
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set user role")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn set_user_locale(
+    conn: &mut PgConnection,
+    user_id: &str,
+    locale: Option<&str>,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE users SET locale = $1 WHERE id = $2",
+        locale,
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set user locale")?;
+
+    Ok(())
+}
+
+/// Sets the name the user picked, `None` goes back to the name from claims on the next login.
+#[tracing::instrument(skip(conn))]
+pub async fn set_user_display_name(
+    conn: &mut PgConnection,
+    user_id: &str,
+    display_name: Option<&str>,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE users
+        SET display_name = $1, display_name_custom = $1::text IS NOT NULL
+        WHERE id = $2",
+        display_name,
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set user display name")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn set_user_leaderboard_opt_out(
+    conn: &mut PgConnection,
+    user_id: &str,
+    opt_out: bool,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE users SET leaderboard_opt_out = $1 WHERE id = $2",
+        opt_out,
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set user leaderboard opt-out")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(conn))]
+pub async fn set_user_share_solution_name(
+    conn: &mut PgConnection,
+    user_id: &str,
+    share: bool,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE users SET share_solution_name = $1 WHERE id = $2",
+        share,
+        user_id,
+    )
+    .execute(conn)
+    .await
+    .wrap_err("Failed to set user solution name sharing")?;
+
+    Ok(())
+}
+
+/// Everything stored about the user, for data-subject requests. `None` if there's no such user.
+#[tracing::instrument(skip(conn))]
+pub async fn get_user_data(
+    conn: &mut PgConnection,
+    user_id: &str,
+) -> Result<Option<serde_json::Value>> {
+    let data = sqlx::query_scalar!(
+        r#"SELECT jsonb_build_object(
+            'user', to_jsonb(users),
+            'solutions', COALESCE((
+                SELECT jsonb_agg(to_jsonb(user_solution) ORDER BY user_solution.created_at)
+                FROM user_solution
+                WHERE user_solution.user_id = users.id
+            ), '[]'::jsonb),
+            'achievements', COALESCE((
+                SELECT jsonb_agg(to_jsonb(user_achievement) ORDER BY user_achievement.created_at)
+                FROM user_achievement
+                WHERE user_achievement.user_id = users.id
+            ), '[]'::jsonb),
+            'drafts', COALESCE((
+                SELECT jsonb_agg(to_jsonb(user_draft) ORDER BY user_draft.updated_at)
+                FROM user_draft
+                WHERE user_draft.user_id = users.id
+            ), '[]'::jsonb),
+            'solution_votes', COALESCE((
+                SELECT jsonb_agg(to_jsonb(solution_vote) ORDER BY solution_vote.created_at)
+                FROM solution_vote
+                WHERE solution_vote.user_id = users.id
+            ), '[]'::jsonb),
+            'comments', COALESCE((
+                SELECT jsonb_agg(to_jsonb(exercise_comment) ORDER BY exercise_comment.created_at)
+                FROM exercise_comment
+                WHERE exercise_comment.user_id = users.id
+            ), '[]'::jsonb),
+            'reports', COALESCE((
+                SELECT jsonb_agg(to_jsonb(exercise_report) ORDER BY exercise_report.created_at)
+                FROM exercise_report
+                WHERE exercise_report.user_id = users.id
+            ), '[]'::jsonb),
+            'bookmarks', COALESCE((
+                SELECT jsonb_agg(to_jsonb(exercise_bookmark) ORDER BY exercise_bookmark.created_at)
+                FROM exercise_bookmark
+                WHERE exercise_bookmark.user_id = users.id
+            ), '[]'::jsonb),
+            'notes', COALESCE((
+                SELECT jsonb_agg(to_jsonb(exercise_note) ORDER BY exercise_note.created_at)
+                FROM exercise_note
+                WHERE exercise_note.user_id = users.id
+            ), '[]'::jsonb),
+            'review_cards', COALESCE((
+                SELECT jsonb_agg(to_jsonb(review_card) ORDER BY review_card.created_at)
+                FROM review_card
+                WHERE review_card.user_id = users.id
+            ), '[]'::jsonb),
+            'hint_views', COALESCE((
+                SELECT jsonb_agg(to_jsonb(hint_view) ORDER BY hint_view.created_at)
+                FROM hint_view
+                WHERE hint_view.user_id = users.id
+            ), '[]'::jsonb),
+            'cohorts', COALESCE((
+                SELECT jsonb_agg(jsonb_build_object(
+                    'name', cohort.name,
+                    'joined_at', cohort_member.created_at
+                ) ORDER BY cohort_member.created_at)
+                FROM cohort_member
+                JOIN cohort ON cohort.id = cohort_member.cohort_id
+                WHERE cohort_member.user_id = users.id
+            ), '[]'::jsonb)
+        ) AS "data!"
+        FROM users
+        WHERE users.id = $1"#,
+        user_id,
+    )
+    .fetch_optional(conn)
+    .await
+    .wrap_err("Failed to get user data")?;
+
+    Ok(data)
+}
+
+/// Deletes the user along with their solutions, achievements, memberships and the cohorts they teach.
+///
+/// Returns `false` if there's no such user.
+#[tracing::instrument(skip(conn))]
+pub async fn delete_user(conn: &mut PgConnection, user_id: &str) -> Result<bool> {
+    let result = sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
+        .execute(conn)
+        .await
+        .wrap_err("Failed to delete user")?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,336 @@
+//! Server-side introspection of exercise schemas, done by parsing their DDL.
+
+use std::fmt::Write;
+
+use derive_getters::Getters;
+use serde::Serialize;
+use sqlparser::{
+    ast::{
+        AlterTableOperation, ColumnDef, ColumnOption, Ident, ObjectName, Statement, TableConstraint,
+    },
+    parser::{Parser, ParserError},
+};
+
+use crate::models::exercise::SqlDialect;
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct SchemaInfo {
+    tables: Vec<TableInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct TableInfo {
+    name: String,
+    columns: Vec<ColumnInfo>,
+    primary_key: Vec<String>,
+    foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ColumnInfo {
+    name: String,
+    data_type: String,
+    nullable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Getters)]
+pub struct ForeignKeyInfo {
+    columns: Vec<String>,
+    foreign_table: String,
+    /// Columns of the foreign table, its primary key if not specified explicitly.
+    referred_columns: Vec<String>,
+}
+
+impl SchemaInfo {
+    /// Finds a table by name, case-insensitively.
+    pub fn table(&self, name: &str) -> Option<&TableInfo> {
+        self.tables
+            .iter()
+            .find(|table| table.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Checks if any of the tables has a column with the given name, case-insensitively.
+    pub fn has_column(&self, name: &str) -> bool {
+        self.tables.iter().any(|table| table.column(name).is_some())
+    }
+
+    /// Renders the schema as a Mermaid entity relationship diagram.
+    pub fn mermaid(&self) -> String {
+        let mut diagram = String::from("erDiagram\n");
+
+        for table in &self.tables {
+            let _ = writeln!(diagram, "    {} {{", table.name);
+
+            for column in &table.columns {
+                let key = match (
+                    table.is_primary_key(&column.name),
+                    table.is_foreign_key(&column.name),
+                ) {
+                    (true, true) => " PK, FK",
+                    (true, false) => " PK",
+                    (false, true) => " FK",
+                    (false, false) => "",
+                };
+                let comment = if column.nullable { "null" } else { "not null" };
+
+                // name goes first, same as in diagrams drawn in the browser
+                let _ = writeln!(
+                    diagram,
+                    "        {} {}{key} \"{comment}\"",
+                    column.name,
+                    mermaid_type(&column.data_type),
+                );
+            }
+
+            diagram.push_str("    }\n");
+        }
+
+        for table in &self.tables {
+            for foreign_key in &table.foreign_keys {
+                let _ = writeln!(
+                    diagram,
+                    "    {} ||--o{{ {} : \"{}({}) -> {}({})\"",
+                    foreign_key.foreign_table,
+                    table.name,
+                    table.name,
+                    foreign_key.columns.join(", "),
+                    foreign_key.foreign_table,
+                    foreign_key.referred_columns.join(", "),
+                );
+            }
+        }
+
+        diagram
+    }
+}
+
+/// Mermaid only allows words as attribute types, so `numeric(10, 2)` becomes `numeric(10_2)`.
+fn mermaid_type(data_type: &str) -> String {
+    data_type
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .filter_map(|c| match c {
+            ',' => Some('_'),
+            c if c.is_ascii_alphanumeric() || "_()[]".contains(c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+impl TableInfo {
+    /// Finds a column by name, case-insensitively.
+    pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_primary_key(&self, column: &str) -> bool {
+        self.primary_key.iter().any(|name| name == column)
+    }
+
+    pub fn is_foreign_key(&self, column: &str) -> bool {
+        self.foreign_keys
+            .iter()
+            .any(|foreign_key| foreign_key.columns.iter().any(|name| name == column))
+    }
+
+    fn add_constraint(&mut self, constraint: &TableConstraint) {
+        match constraint {
+            TableConstraint::PrimaryKey { columns, .. } => {
+                self.primary_key = columns.iter().map(ident_name).collect();
+            }
+            TableConstraint::ForeignKey {
+                columns,
+                foreign_table,
+                referred_columns,
+                ..
+            } => self.foreign_keys.push(ForeignKeyInfo {
+                columns: columns.iter().map(ident_name).collect(),
+                foreign_table: object_name(foreign_table),
+                referred_columns: referred_columns.iter().map(ident_name).collect(),
+            }),
+            _ => {}
+        }
+    }
+
+    fn from_columns(name: String, column_defs: &[ColumnDef]) -> Self {
+        let mut table = TableInfo {
+            name,
+            columns: Vec::with_capacity(column_defs.len()),
+            primary_key: Vec::new(),
+            foreign_keys: Vec::new(),
+        };
+
+        for column_def in column_defs {
+            let name = ident_name(&column_def.name);
+            let mut nullable = true;
+
+            for option in &column_def.options {
+                match &option.option {
+                    ColumnOption::NotNull => nullable = false,
+                    ColumnOption::Unique {
+                        is_primary: true, ..
+                    } => table.primary_key = vec![name.clone()],
+                    ColumnOption::ForeignKey {
+                        foreign_table,
+                        referred_columns,
+                        ..
+                    } => table.foreign_keys.push(ForeignKeyInfo {
+                        columns: vec![name.clone()],
+                        foreign_table: object_name(foreign_table),
+                        referred_columns: referred_columns.iter().map(ident_name).collect(),
+                    }),
+                    _ => {}
+                }
+            }
+
+            table.columns.push(ColumnInfo {
+                name,
+                data_type: column_def.data_type.to_string(),
+                nullable,
+            });
+        }
+
+        table
+    }
+}
+
+/// Postgres folds unquoted identifiers to lower case.
+///
+/// Names from other dialects are folded as well: lookups are case-insensitive anyway.
+fn ident_name(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+fn object_name(name: &ObjectName) -> String {
+    // schema-qualified names are shown without the schema
+    name.0.last().map(ident_name).unwrap_or_default()
+}
+
+/// Parses schema DDL, collecting all the tables created by it, along with their keys.
+///
+/// Constraints added by `ALTER TABLE` are taken into account, other statements
+/// (inserts, indexes, etc.) are ignored.
+pub fn parse(ddl: &str, dialect: SqlDialect) -> Result<SchemaInfo, ParserError> {
+    let statements = Parser::parse_sql(dialect.parser_dialect(), ddl)?;
+
+    let mut tables: Vec<TableInfo> = Vec::new();
+
+    for statement in statements {
+        match statement {
+            Statement::CreateTable(create_table) => {
+                let mut table =
+                    TableInfo::from_columns(object_name(&create_table.name), &create_table.columns);
+
+                for constraint in &create_table.constraints {
+                    table.add_constraint(constraint);
+                }
+
+                tables.push(table);
+            }
+            Statement::AlterTable {
+                name, operations, ..
+            } => {
+                let name = object_name(&name);
+
+                if let Some(table) = tables.iter_mut().find(|table| table.name == name) {
+                    for operation in &operations {
+                        if let AlterTableOperation::AddConstraint(constraint) = operation {
+                            table.add_constraint(constraint);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // primary key columns are implicitly not null
+    for table in &mut tables {
+        for column in &mut table.columns {
+            if table.primary_key.contains(&column.name) {
+                column.nullable = false;
+            }
+        }
+    }
+
+    // `REFERENCES table` without columns refers to the primary key
+    let primary_keys: Vec<(String, Vec<String>)> = tables
+        .iter()
+        .map(|table| (table.name.clone(), table.primary_key.clone()))
+        .collect();
+
+    for table in &mut tables {
+        for foreign_key in &mut table.foreign_keys {
+            if foreign_key.referred_columns.is_empty()
+                && let Some((_, primary_key)) = primary_keys
+                    .iter()
+                    .find(|(name, _)| *name == foreign_key.foreign_table)
+            {
+                foreign_key.referred_columns = primary_key.clone();
+            }
+        }
+    }
+
+    Ok(SchemaInfo { tables })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DDL: &str = "
+        CREATE TABLE authors (
+            id serial PRIMARY KEY,
+            name varchar(100) NOT NULL
+        );
+
+        CREATE TABLE books (
+            id integer,
+            author_id integer REFERENCES authors,
+            price numeric(10, 2),
+            PRIMARY KEY (id)
+        );
+
+        CREATE TABLE reviews (book_id integer, body text);
+        ALTER TABLE reviews ADD CONSTRAINT reviews_book_fk FOREIGN KEY (book_id) REFERENCES books (id);
+    ";
+
+    #[test]
+    fn collects_keys() {
+        let schema = parse(DDL, SqlDialect::Postgres).unwrap();
+
+        let authors = schema.table("authors").unwrap();
+        assert_eq!(authors.primary_key(), &["id"]);
+        assert!(!authors.column("name").unwrap().nullable());
+
+        let books = schema.table("books").unwrap();
+        assert_eq!(books.primary_key(), &["id"]);
+        assert!(!books.column("id").unwrap().nullable());
+        assert_eq!(books.foreign_keys()[0].foreign_table(), "authors");
+        // implicit reference to the primary key
+        assert_eq!(books.foreign_keys()[0].referred_columns(), &["id"]);
+
+        let reviews = schema.table("reviews").unwrap();
+        assert!(reviews.is_foreign_key("book_id"));
+        assert_eq!(reviews.foreign_keys()[0].foreign_table(), "books");
+    }
+
+    #[test]
+    fn renders_mermaid() {
+        let diagram = parse(DDL, SqlDialect::Postgres).unwrap().mermaid();
+
+        assert!(diagram.starts_with("erDiagram\n"));
+        assert!(diagram.contains("        id integer PK \"not null\""));
+        assert!(diagram.contains("        price numeric(10_2) \"null\""));
+        assert!(diagram.contains("        author_id integer FK \"null\""));
+        assert!(diagram.contains("    authors ||--o{ books : \"books(author_id) -> authors(id)\""));
+    }
+}
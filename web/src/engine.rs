@@ -0,0 +1,215 @@
+//! Server-side SQL engines, used to validate and grade exercises.
+//!
+//! Only embedded engines are run here. Exercises in dialects without one (Postgres)
+//! are run in the browser only, and graded by the results reported from there.
+
+use std::time::{Duration, Instant};
+
+use displaydoc::Display;
+use rusqlite::{
+    Connection,
+    hooks::{AuthAction, AuthContext, Authorization},
+    limits::Limit,
+    types::ValueRef,
+};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::models::exercise::SqlDialect;
+
+/// Schemas and queries running longer than this are interrupted.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Maximum number of rows a graded query may return.
+const MAX_ROWS: usize = 10_000;
+
+/// Maximum size of a single string or blob, in bytes, so `zeroblob(1e9)` can't eat the memory.
+const MAX_VALUE_LENGTH: i32 = 1_000_000;
+
+#[derive(Debug, Error, Display)]
+/// Errors which can be encountered while running SQL on the server.
+pub enum EngineError {
+    /// Schema can't be created: {0}
+    Schema(rusqlite::Error),
+    /// Query failed: {0}
+    Query(rusqlite::Error),
+    /// Only queries reading data can be graded
+    NotReadOnly,
+    /// Query returned too many rows
+    TooManyRows,
+    /// Engine task failed: {0}
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// Creates the schema in a scratch database, to check it for errors.
+///
+/// Schemas of dialects without an embedded engine aren't checked.
+pub async fn validate_schema(dialect: SqlDialect, schema: String) -> Result<(), EngineError> {
+    match dialect {
+        SqlDialect::Postgres => Ok(()),
+        SqlDialect::Sqlite => {
+            tokio::task::spawn_blocking(move || sqlite_with_schema(&schema).map(drop)).await?
+        }
+    }
+}
+
+/// Runs the query in a scratch database with the schema created.
+///
+/// Rows are returned as a JSON array of objects, in the same shape as the browser reports them.
+/// Returns `None` for dialects without an embedded engine.
+pub async fn run_query(
+    dialect: SqlDialect,
+    schema: String,
+    query: String,
+) -> Option<Result<Value, EngineError>> {
+    match dialect {
+        SqlDialect::Postgres => None,
+        SqlDialect::Sqlite => Some(
+            tokio::task::spawn_blocking(move || sqlite_query(&schema, &query))
+                .await
+                .map_err(EngineError::from)
+                .and_then(|result| result),
+        ),
+    }
+}
+
+fn sqlite_with_schema(schema: &str) -> Result<Connection, EngineError> {
+    let conn = Connection::open_in_memory().map_err(EngineError::Schema)?;
+
+    // `ATTACH` and `VACUUM INTO` would create files anywhere the server can write
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+    conn.set_limit(Limit::SQLITE_LIMIT_LENGTH, MAX_VALUE_LENGTH);
+
+    // the same deadline covers both the schema and the query
+    let deadline = Instant::now() + TIMEOUT;
+    conn.progress_handler(1_000, Some(move || Instant::now() > deadline));
+
+    conn.execute_batch(schema).map_err(EngineError::Schema)?;
+
+    Ok(conn)
+}
+
+/// Only lets the query read data, checked when it's prepared.
+fn read_only(context: AuthContext<'_>) -> Authorization {
+    match context.action {
+        AuthAction::Select
+        | AuthAction::Read { .. }
+        | AuthAction::Function { .. }
+        | AuthAction::Recursive => Authorization::Allow,
+        _ => Authorization::Deny,
+    }
+}
+
+fn sqlite_query(schema: &str, query: &str) -> Result<Value, EngineError> {
+    let conn = sqlite_with_schema(schema)?;
+    conn.authorizer(Some(read_only));
+
+    let mut statement = conn.prepare(query).map_err(EngineError::Query)?;
+    // statements without authorizer actions, like `VACUUM`, are caught here
+    if !statement.readonly() {
+        return Err(EngineError::NotReadOnly);
+    }
+
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+
+    let mut rows = statement.query([]).map_err(EngineError::Query)?;
+    let mut result = Vec::new();
+
+    while let Some(row) = rows.next().map_err(EngineError::Query)? {
+        if result.len() == MAX_ROWS {
+            return Err(EngineError::TooManyRows);
+        }
+
+        let mut object = Map::with_capacity(columns.len());
+
+        for (idx, column) in columns.iter().enumerate() {
+            let value = row.get_ref(idx).map_err(EngineError::Query)?;
+            object.insert(column.clone(), json_value(value));
+        }
+
+        result.push(Value::Object(object));
+    }
+
+    Ok(Value::Array(result))
+}
+
+fn json_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(int) => int.into(),
+        // JSON coming from the browser doesn't tell `1.0` from `1`
+        ValueRef::Real(real) if real.fract() == 0.0 && real.abs() < (1u64 << 53) as f64 => {
+            (real as i64).into()
+        }
+        ValueRef::Real(real) => real.into(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+        ValueRef::Blob(blob) => blob.to_vec().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "
+        CREATE TABLE items (id integer PRIMARY KEY, name text, price real);
+        INSERT INTO items VALUES (1, 'apple', 2.0), (2, 'pear', 2.5), (3, NULL, NULL);
+    ";
+
+    #[test]
+    fn returns_rows_as_objects() {
+        let result = sqlite_query(SCHEMA, "SELECT name, price FROM items ORDER BY id").unwrap();
+
+        assert_eq!(
+            result,
+            serde_json::json!([
+                { "name": "apple", "price": 2 },
+                { "name": "pear", "price": 2.5 },
+                { "name": null, "price": null },
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert!(matches!(
+            sqlite_with_schema("CREATE TABLE broken ("),
+            Err(EngineError::Schema(_))
+        ));
+        assert!(matches!(
+            sqlite_query(SCHEMA, "SELECT missing FROM items"),
+            Err(EngineError::Query(_))
+        ));
+        // huge values can't be allocated
+        assert!(matches!(
+            sqlite_query(SCHEMA, "SELECT zeroblob(1000000000)"),
+            Err(EngineError::Query(_))
+        ));
+        // runaway queries are interrupted
+        assert!(matches!(
+            sqlite_query(
+                SCHEMA,
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT count(*) FROM n"
+            ),
+            Err(EngineError::Query(_))
+        ));
+    }
+
+    #[test]
+    fn only_reads() {
+        let dir = std::env::temp_dir().join(format!("grimoire-engine-{}", std::process::id()));
+        let path = dir.to_string_lossy();
+
+        assert!(sqlite_query(SCHEMA, &format!("ATTACH DATABASE '{path}' AS other")).is_err());
+        assert!(sqlite_query(SCHEMA, &format!("VACUUM INTO '{path}'")).is_err());
+        assert!(!dir.exists());
+
+        assert!(sqlite_query(SCHEMA, "DELETE FROM items").is_err());
+        assert!(sqlite_query(SCHEMA, "PRAGMA table_info(items)").is_err());
+        assert!(sqlite_query(SCHEMA, "SELECT count(*) FROM items").is_ok());
+    }
+}
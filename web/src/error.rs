@@ -23,6 +23,8 @@ pub enum SqlGrimoireError {
     // 4xx errors:
     /// Not found: {0:?}
     NotFound(String),
+    /// Forbidden: {0}
+    Forbidden(String),
 }
 
 impl axum::response::IntoResponse for SqlGrimoireError {
@@ -34,6 +36,7 @@ impl axum::response::IntoResponse for SqlGrimoireError {
 
         let code = match self {
             Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::AuthExtractorError(AuthExtractorError::NoSessionCookie) => {
                 StatusCode::UNAUTHORIZED
             }
@@ -48,4 +51,8 @@ impl SqlGrimoireError {
     pub fn not_found(details: impl AsRef<str>) -> Self {
         Self::NotFound(details.as_ref().to_owned())
     }
+
+    pub fn forbidden(details: impl AsRef<str>) -> Self {
+        Self::Forbidden(details.as_ref().to_owned())
+    }
 }
@@ -1,17 +1,20 @@
+use std::convert::Infallible;
+
 use axum::{
     extract::{FromRequestParts, OptionalFromRequestParts},
     http::{self, request::Parts},
     response::{IntoResponse, Response},
 };
-use axum_extra::extract::cookie::CookieJar;
-use axum_jwt_auth::{Error as JwtDecoderError, JwtDecoder};
+use axum_extra::extract::{Cached, cookie::CookieJar};
+use axum_jwt_auth::Error as JwtDecoderError;
 use displaydoc::Display;
 use eyre::WrapErr;
 use thiserror::Error;
-use tracing::{error, trace};
+use tracing::trace;
 
 use crate::{
     db::user,
+    i18n,
     models::user::{User, UserClaims},
     state::AppState,
 };
@@ -23,6 +26,8 @@ pub enum AuthExtractorError {
     NoSessionCookie,
     /// JWT Validation error: {0:?}
     JWTValidationError(JwtDecoderError),
+    /// Session token is invalid: {0:?}
+    InvalidSessionToken(jsonwebtoken::errors::Error),
     /// Token is expired
     TokenExpired,
     /// Token is not yet valid
@@ -48,8 +53,6 @@ impl IntoResponse for AuthExtractorError {
     }
 }
 
-const SESSION_COOKIE: &str = "cbo_session_token";
-
 struct SessionToken(String);
 
 impl OptionalFromRequestParts<AppState> for SessionToken {
@@ -66,7 +69,7 @@ impl OptionalFromRequestParts<AppState> for SessionToken {
 
         trace!("Got signed cookie jar from request");
 
-        let session_token = match jar.get(SESSION_COOKIE) {
+        let session_token = match jar.get(state.auth().session_cookie()) {
             Some(cookie) => cookie.value().to_string(),
             None => return Ok(None),
         };
@@ -113,11 +116,7 @@ impl OptionalFromRequestParts<AppState> for UserClaims {
                 None => return Ok(None),
             };
 
-        let claims: UserClaims = state
-            .jwks_decoder()
-            .decode(&session_token.0)
-            .map_err(AuthExtractorError::JWTValidationError)
-            .map(|token_data| token_data.claims)?;
+        let claims = state.auth().validate(&session_token.0)?;
 
         let now = time::OffsetDateTime::now_utc();
 
@@ -129,12 +128,6 @@ impl OptionalFromRequestParts<AppState> for UserClaims {
             return Err(AuthExtractorError::TokenNotYetValid);
         }
 
-        if claims.iss() != state.config().corbado_host() {
-            error!("Invalid issuer: {}", claims.iss());
-
-            return Err(AuthExtractorError::InvalidIssuer);
-        }
-
         Ok(Some(claims))
     }
 }
@@ -179,10 +172,16 @@ impl OptionalFromRequestParts<AppState> for User {
             .await
             .wrap_err("Failed to query user from DB")?;
 
-        match user {
-            Some(user) => Ok(Some(user)),
-            None => Err(AuthExtractorError::UserNotFound),
-        }
+        let user = match user {
+            Some(user) => user,
+            None => return Err(AuthExtractorError::UserNotFound),
+        };
+
+        user::touch_user(&mut conn, user.id())
+            .await
+            .wrap_err("Failed to update user last seen time")?;
+
+        Ok(Some(user))
     }
 }
 
@@ -202,3 +201,35 @@ impl FromRequestParts<AppState> for User {
         }
     }
 }
+
+/// Locale to show content in, see [`i18n::negotiate`].
+#[derive(Debug, Clone)]
+pub struct Locale(pub String);
+
+impl FromRequestParts<AppState> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // anonymous users just don't have a preference
+        let preference = Cached::<User>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|Cached(user)| user.locale().clone());
+
+        let accept_language = parts
+            .headers
+            .get(http::header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+
+        let locale = i18n::negotiate(
+            &state.config().supported_locales(),
+            preference.as_deref(),
+            accept_language,
+        );
+
+        Ok(Locale(locale.to_owned()))
+    }
+}
@@ -0,0 +1,229 @@
+//! Normalizing submitted queries, so attempts which only differ in formatting,
+//! aliases or literal values can be grouped together.
+
+use std::{collections::HashMap, ops::ControlFlow};
+
+use sqlparser::{
+    ast::{
+        Expr, Ident, ObjectName, Query, SelectItem, SetExpr, TableFactor, Value, Visit, VisitMut,
+        Visitor, VisitorMut,
+    },
+    parser::Parser,
+};
+
+use crate::models::exercise::SqlDialect;
+
+/// Placeholder all literals are replaced with.
+const PLACEHOLDER: &str = "?";
+
+/// Unquoted identifiers are case-insensitive, quoted ones are kept as is (without quotes).
+fn canonical_ident(ident: &Ident) -> Ident {
+    match ident.quote_style {
+        Some(_) => Ident::new(&ident.value),
+        None => Ident::new(ident.value.to_lowercase()),
+    }
+}
+
+fn table_name(name: &ObjectName) -> String {
+    name.0
+        .last()
+        .map(|ident| canonical_ident(ident).value)
+        .unwrap_or_default()
+}
+
+fn is_placeholder(expr: &Expr) -> bool {
+    matches!(expr, Expr::Value(Value::Placeholder(placeholder)) if placeholder == PLACEHOLDER)
+}
+
+/// Collects table aliases, mapping each one to a name which doesn't depend on the user's choice:
+/// the table name itself, numbered if the same table is used more than once.
+#[derive(Default)]
+struct AliasCollector {
+    aliases: HashMap<String, String>,
+    occurrences: HashMap<String, usize>,
+}
+
+impl AliasCollector {
+    fn canonical_name(&mut self, base: String) -> String {
+        let count = self.occurrences.entry(base.clone()).or_default();
+        *count += 1;
+
+        match count {
+            1 => base,
+            _ => format!("{base}_{count}"),
+        }
+    }
+}
+
+impl Visitor for AliasCollector {
+    type Break = ();
+
+    fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<()> {
+        let (base, alias) = match table_factor {
+            TableFactor::Table { name, alias, .. } => (table_name(name), alias),
+            TableFactor::Derived { alias, .. } => ("subquery".to_string(), alias),
+            _ => return ControlFlow::Continue(()),
+        };
+
+        let canonical = self.canonical_name(base);
+
+        if let Some(alias) = alias {
+            self.aliases
+                .insert(canonical_ident(&alias.name).value, canonical);
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+struct Normalizer {
+    aliases: HashMap<String, String>,
+}
+
+impl Normalizer {
+    fn strip_column_aliases(set_expr: &mut SetExpr) {
+        match set_expr {
+            SetExpr::Select(select) => {
+                for item in &mut select.projection {
+                    if let SelectItem::ExprWithAlias { expr, .. } = item {
+                        *item = SelectItem::UnnamedExpr(expr.clone());
+                    }
+                }
+            }
+            SetExpr::SetOperation { left, right, .. } => {
+                Self::strip_column_aliases(left);
+                Self::strip_column_aliases(right);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl VisitorMut for Normalizer {
+    type Break = ();
+
+    fn pre_visit_relation(&mut self, relation: &mut ObjectName) -> ControlFlow<()> {
+        for ident in &mut relation.0 {
+            *ident = canonical_ident(ident);
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &mut TableFactor) -> ControlFlow<()> {
+        let (base, alias) = match table_factor {
+            TableFactor::Table { name, alias, .. } => (Some(table_name(name)), alias),
+            TableFactor::Derived { alias, .. } => (None, alias),
+            _ => return ControlFlow::Continue(()),
+        };
+
+        if let Some(table_alias) = alias {
+            match self.aliases.get(&canonical_ident(&table_alias.name).value) {
+                // `FROM orders o` is the same as `FROM orders`
+                Some(canonical) if base.as_ref() == Some(canonical) => *alias = None,
+                Some(canonical) => table_alias.name = Ident::new(canonical),
+                None => {}
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, query: &mut Query) -> ControlFlow<()> {
+        Self::strip_column_aliases(&mut query.body);
+
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::Value(Value::Null) => {}
+            Expr::Value(value) => *value = Value::Placeholder(PLACEHOLDER.to_string()),
+            Expr::Identifier(ident) => *ident = canonical_ident(ident),
+            Expr::Function(function) => {
+                for ident in &mut function.name.0 {
+                    *ident = canonical_ident(ident);
+                }
+            }
+            Expr::CompoundIdentifier(idents) => {
+                for ident in idents.iter_mut() {
+                    *ident = canonical_ident(ident);
+                }
+
+                // the qualifier goes right before the column name
+                if let [.., qualifier, _] = &mut idents[..]
+                    && let Some(canonical) = self.aliases.get(&qualifier.value)
+                {
+                    *qualifier = Ident::new(canonical);
+                }
+            }
+            // `IN (1, 2)` is the same as `IN (1, 2, 3)`
+            Expr::InList { list, .. } if list.iter().all(is_placeholder) => list.truncate(1),
+            _ => {}
+        }
+
+        ControlFlow::Continue(())
+    }
+}
+
+/// Normalizes a query: formatting is unified, literals are replaced with `?`,
+/// identifiers and function names are lower-cased (unless quoted) and aliases are replaced with table names.
+///
+/// Returns `None` if the query can't be parsed.
+pub fn fingerprint(query: &str, dialect: SqlDialect) -> Option<String> {
+    let mut statements = Parser::parse_sql(dialect.parser_dialect(), query).ok()?;
+
+    let mut collector = AliasCollector::default();
+    let _ = Visit::visit(&statements, &mut collector);
+
+    let mut normalizer = Normalizer {
+        aliases: collector.aliases,
+    };
+    let _ = VisitMut::visit(&mut statements, &mut normalizer);
+
+    Some(
+        statements
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint_pg(query: &str) -> String {
+        fingerprint(query, SqlDialect::Postgres).unwrap()
+    }
+
+    #[test]
+    fn ignores_formatting_and_literals() {
+        assert_eq!(
+            fingerprint_pg("select NAME from Users\n  where id = 1 and role IN ('a', 'b')"),
+            fingerprint_pg("SELECT name FROM users WHERE id = 42 AND role IN ('c');"),
+        );
+        assert_eq!(
+            fingerprint_pg("SELECT name FROM users WHERE id = 1"),
+            "SELECT name FROM users WHERE id = ?"
+        );
+        assert!(fingerprint("SELEC name", SqlDialect::Postgres).is_none());
+    }
+
+    #[test]
+    fn canonicalizes_aliases() {
+        assert_eq!(
+            fingerprint_pg(
+                "SELECT u.name AS user_name, COUNT(*) AS n FROM users u JOIN orders o ON o.user_id = u.id GROUP BY u.name"
+            ),
+            fingerprint_pg(
+                "SELECT users.name, count(*) FROM users JOIN orders ON orders.user_id = users.id GROUP BY users.name"
+            ),
+        );
+        assert_eq!(
+            fingerprint_pg("SELECT a.id FROM users a JOIN users b ON a.id = b.parent_id"),
+            "SELECT users.id FROM users JOIN users AS users_2 ON users.id = users_2.parent_id"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Choosing the locale user-facing content is shown in.
+
+/// Checks if `tag` (i.e. `en-US`) matches the supported `locale` (i.e. `en`),
+/// either exactly or by its primary language subtag.
+fn matches(tag: &str, locale: &str) -> bool {
+    let tag = tag.trim();
+
+    tag.eq_ignore_ascii_case(locale)
+        || tag
+            .split_once('-')
+            .is_some_and(|(language, _)| language.eq_ignore_ascii_case(locale))
+}
+
+/// Parses an `Accept-Language` header value into language tags, most preferred first.
+fn accepted_languages(accept_language: &str) -> Vec<&str> {
+    let mut languages: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = parts.next()?.trim();
+
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.parse().ok())
+                .unwrap_or(1.0);
+
+            (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+
+    // stable sort keeps the header order for equal qualities
+    languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    languages.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Picks the locale to show content in.
+///
+/// An explicit user preference wins, then the most preferred language from `Accept-Language`
+/// which is supported, then the default locale (the first of `supported`).
+pub fn negotiate<'a>(
+    supported: &[&'a str],
+    preference: Option<&str>,
+    accept_language: Option<&str>,
+) -> &'a str {
+    let find = |tag: &str| {
+        supported
+            .iter()
+            .find(|locale| matches(tag, locale))
+            .copied()
+    };
+
+    preference
+        .and_then(find)
+        .or_else(|| {
+            accept_language
+                .map(accepted_languages)
+                .unwrap_or_default()
+                .into_iter()
+                .find_map(find)
+        })
+        .unwrap_or(supported[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPORTED: &[&str] = &["en", "ru", "de"];
+
+    #[test]
+    fn prefers_user_choice() {
+        assert_eq!(negotiate(SUPPORTED, Some("de"), Some("ru")), "de");
+        // unsupported preferences are ignored
+        assert_eq!(negotiate(SUPPORTED, Some("fr"), Some("ru")), "ru");
+    }
+
+    #[test]
+    fn follows_accept_language_quality() {
+        assert_eq!(
+            negotiate(SUPPORTED, None, Some("fr-FR, ru;q=0.5, de-AT;q=0.8")),
+            "de"
+        );
+        assert_eq!(negotiate(SUPPORTED, None, Some("ru-RU,ru;q=0.9")), "ru");
+        assert_eq!(negotiate(SUPPORTED, None, Some("ru;q=0")), "en");
+    }
+
+    #[test]
+    fn falls_back_to_default() {
+        assert_eq!(negotiate(SUPPORTED, None, None), "en");
+        assert_eq!(negotiate(SUPPORTED, None, Some("fr, *;q=0.1")), "en");
+    }
+}
@@ -1,15 +1,25 @@
+use std::net::SocketAddr;
+
 use eyre::WrapErr;
 use tracing::info;
 
 use sql_grimoire_observability::setup as setup_observability;
 
+mod achievements;
+mod auth;
 mod config;
 mod db;
+mod ddl;
+mod engine;
 mod error;
 mod extractors;
+mod fingerprint;
+mod i18n;
+mod markdown;
 mod middlewares;
 mod models;
 mod partials;
+mod review;
 mod routes;
 mod setup;
 mod state;
@@ -31,7 +41,13 @@ async fn main() -> Result<(), eyre::Report> {
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
 
-    axum::serve(listener, app).await.unwrap();
+    // peer addresses are used to throttle logins, see `auth::rate_limit`
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 
     Ok(())
 }
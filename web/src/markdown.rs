@@ -0,0 +1,323 @@
+//! Markdown rendering for texts shown to users: exercise questions, hints, editorials, comments.
+//!
+//! Markdown is parsed with GFM extensions (tables, footnotes, strikethrough, task lists),
+//! SQL code blocks are highlighted server-side, and the resulting HTML is sanitized
+//! against an allow-list, so raw HTML in the source can't inject anything into the page.
+//! Comments can also hide `||spoilers||` until they're clicked.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use lazy_static::lazy_static;
+use maud::{Markup, PreEscaped, html};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd, TextMergeStream};
+
+use crate::models::exercise::SqlDialect;
+
+mod highlight;
+
+pub use highlight::highlight_sql;
+
+/// Code fence languages which are highlighted as SQL, along with their dialects.
+const SQL_LANGUAGES: &[(&str, SqlDialect)] = &[
+    ("sql", SqlDialect::Postgres),
+    ("pgsql", SqlDialect::Postgres),
+    ("postgres", SqlDialect::Postgres),
+    ("postgresql", SqlDialect::Postgres),
+    ("sqlite", SqlDialect::Sqlite),
+];
+
+/// Prefix for all `id` attributes in rendered markdown (i.e. footnotes),
+/// so they can't clash with the ones of the page itself.
+const ID_PREFIX: &str = "md-";
+
+/// Marks both ends of a spoiler in comments.
+const SPOILER_DELIMITER: &str = "||";
+
+const SQL_HIGHLIGHT_CLASSES: &[&str] = &[
+    "sql-hl-keyword",
+    "sql-hl-function",
+    "sql-hl-number",
+    "sql-hl-string",
+    "sql-hl-special",
+    "sql-hl-bracket",
+    "sql-hl-comment",
+];
+
+lazy_static! {
+    static ref SANITIZER: ammonia::Builder<'static> = {
+        let mut builder = ammonia::Builder::default();
+
+        builder
+            // task lists
+            .add_tags(["input"])
+            .add_tag_attributes("input", ["checked", "disabled"])
+            .add_tag_attribute_values("input", "type", ["checkbox"])
+            // highlighted code
+            .add_allowed_classes("code", ["language-sql"])
+            .add_allowed_classes("span", SQL_HIGHLIGHT_CLASSES)
+            .add_allowed_classes("span", ["spoiler"])
+            // footnotes
+            .add_allowed_classes("sup", ["footnote-reference", "footnote-definition-label"])
+            .add_allowed_classes("div", ["footnote-definition"])
+            .add_tag_attributes("div", ["id"])
+            .id_prefix(Some(ID_PREFIX))
+            .attribute_filter(|element, attribute, value| match (element, attribute) {
+                // keep links to footnotes working with prefixed ids
+                ("a", "href") if value.starts_with('#') => {
+                    Some(Cow::Owned(format!("#{ID_PREFIX}{}", &value[1..])))
+                }
+                _ => Some(Cow::Borrowed(value)),
+            });
+
+        builder
+    };
+}
+
+fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+}
+
+fn sql_dialect(language: &str) -> Option<SqlDialect> {
+    SQL_LANGUAGES
+        .iter()
+        .find(|(sql, _)| sql.eq_ignore_ascii_case(language.trim()))
+        .map(|(_, dialect)| *dialect)
+}
+
+/// Renders markdown into sanitized HTML.
+pub fn render(source: &str) -> Markup {
+    render_events(highlight_code_blocks(source))
+}
+
+/// Renders markdown of a user comment, which can contain spoilers.
+pub fn render_comment(source: &str) -> Markup {
+    render_events(spoilers(highlight_code_blocks(source)))
+}
+
+fn render_events<'a>(events: Vec<Event<'a>>) -> Markup {
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+
+    PreEscaped(SANITIZER.clean(&html).to_string())
+}
+
+fn highlight_code_blocks(source: &str) -> Vec<Event<'_>> {
+    let mut events = Vec::new();
+    let mut sql_code: Option<(String, SqlDialect)> = None;
+
+    for event in TextMergeStream::new(Parser::new_ext(source, options())) {
+        if let Some((code, dialect)) = sql_code.as_mut() {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => {
+                    let highlighted = html! {
+                        pre { code class="language-sql" { (highlight_sql(code, *dialect)) } }
+                    };
+                    events.push(Event::Html(highlighted.into_string().into()));
+                    sql_code = None;
+                }
+                _ => {}
+            }
+
+            continue;
+        }
+
+        let dialect = match &event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(language))) => sql_dialect(language),
+            _ => None,
+        };
+
+        match dialect {
+            Some(dialect) => sql_code = Some((String::new(), dialect)),
+            None => events.push(event),
+        }
+    }
+
+    events
+}
+
+/// Ends of formatting inside a paragraph, a spoiler can span over them.
+fn is_inline_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image
+    )
+}
+
+/// Checks whether a spoiler opened just before `rest` is closed in the same block.
+fn spoiler_closes(rest: &str, following: &[Event]) -> bool {
+    rest.contains(SPOILER_DELIMITER)
+        || following
+            .iter()
+            .take_while(|event| match event {
+                Event::End(tag) => is_inline_end(tag),
+                _ => true,
+            })
+            .any(|event| matches!(event, Event::Text(text) if text.contains(SPOILER_DELIMITER)))
+}
+
+/// Wraps text between pairs of `||` into spoilers, unpaired delimiters are kept as they are.
+fn spoilers(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut result = Vec::with_capacity(events.len());
+    let mut open = false;
+
+    for (index, event) in events.iter().enumerate() {
+        let text = match event {
+            Event::Text(text) if text.contains(SPOILER_DELIMITER) => text,
+            Event::End(tag) if open && !is_inline_end(tag) => {
+                result.push(Event::InlineHtml("</span>".into()));
+                result.push(event.clone());
+                open = false;
+                continue;
+            }
+            _ => {
+                result.push(event.clone());
+                continue;
+            }
+        };
+
+        let mut rest: &str = text;
+
+        while let Some(position) = rest.find(SPOILER_DELIMITER) {
+            let after = &rest[position + SPOILER_DELIMITER.len()..];
+
+            if !open && !spoiler_closes(after, &events[index + 1..]) {
+                break;
+            }
+
+            result.push(Event::Text(CowStr::from(rest[..position].to_owned())));
+            result.push(Event::InlineHtml(
+                if open {
+                    "</span>"
+                } else {
+                    r#"<span class="spoiler">"#
+                }
+                .into(),
+            ));
+            open = !open;
+            rest = after;
+        }
+
+        if !rest.is_empty() {
+            result.push(Event::Text(CowStr::from(rest.to_owned())));
+        }
+    }
+
+    result
+}
+
+/// Returns contents of all inline code spans in markdown.
+pub fn inline_code(source: &str) -> Vec<String> {
+    Parser::new_ext(source, options())
+        .filter_map(|event| match event {
+            Event::Code(code) => Some(code.into_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+struct CachedRender {
+    revision: u64,
+    html: Arc<str>,
+}
+
+/// Cache of rendered markdown texts.
+///
+/// Texts are keyed by their owner (i.e. `ex-...:question`). Only the latest revision
+/// of each text is kept: when the source changes, it's re-rendered and replaced.
+#[derive(Default)]
+pub struct RenderCache(RwLock<HashMap<String, CachedRender>>);
+
+impl RenderCache {
+    /// Renders markdown via [`render`], reusing the previous result if the source hasn't changed.
+    pub fn render(&self, key: &str, source: &str) -> PreEscaped<Arc<str>> {
+        let revision = {
+            let mut hasher = DefaultHasher::new();
+            source.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        if let Some(cached) = self
+            .0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .filter(|cached| cached.revision == revision)
+        {
+            return PreEscaped(Arc::clone(&cached.html));
+        }
+
+        let html: Arc<str> = render(source).into_string().into();
+
+        self.0
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                key.to_owned(),
+                CachedRender {
+                    revision,
+                    html: Arc::clone(&html),
+                },
+            );
+
+        PreEscaped(html)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_raw_html() {
+        let html = render("Hello <script>alert(1)</script><b onclick=\"x()\">world</b>").0;
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onclick"));
+        assert!(html.contains("<b>world</b>"));
+    }
+
+    #[test]
+    fn renders_gfm_extensions() {
+        let html = render("| a | b |\n|---|---|\n| 1 | 2 |\n\n~~old~~\n\n- [x] done").0;
+
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<del>old</del>"));
+        assert!(html.contains(r#"type="checkbox""#));
+    }
+
+    #[test]
+    fn highlights_sql_fences() {
+        let html = render("```sql\nSELECT count(*) FROM users\n```").0;
+
+        assert!(html.contains(r#"<code class="language-sql">"#));
+        assert!(html.contains(r#"<span class="sql-hl-keyword">SELECT</span>"#));
+        assert!(html.contains(r#"<span class="sql-hl-function">count</span>"#));
+    }
+
+    #[test]
+    fn renders_spoilers_in_comments() {
+        let html = render_comment("Use ||`GROUP BY` and *COUNT*|| here, a || b").0;
+
+        assert!(html.contains(
+            r#"<span class="spoiler"><code>GROUP BY</code> and <em>COUNT</em></span> here, a || b"#
+        ));
+        assert!(render("||not a spoiler||").0.contains("||not a spoiler||"));
+    }
+
+    #[test]
+    fn prefixes_footnote_ids() {
+        let html = render("Text[^note]\n\n[^note]: Footnote").0;
+
+        assert!(html.contains(r##"href="#md-note""##));
+        assert!(html.contains(r#"id="md-note""#));
+    }
+}
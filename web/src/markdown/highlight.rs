@@ -0,0 +1,163 @@
+use maud::{Markup, html};
+use sqlparser::{
+    keywords::Keyword,
+    tokenizer::{Token, Tokenizer, Whitespace},
+};
+
+use crate::models::exercise::SqlDialect;
+
+/// Keywords which are highlighted as such.
+///
+/// `sqlparser` treats lots of common column names (`name`, `id`, `status`...) as keywords,
+/// so we can't just highlight every [`Keyword`] it recognizes.
+const HIGHLIGHTED_KEYWORDS: &[Keyword] = &[
+    // queries
+    Keyword::SELECT,
+    Keyword::DISTINCT,
+    Keyword::FROM,
+    Keyword::WHERE,
+    Keyword::JOIN,
+    Keyword::INNER,
+    Keyword::LEFT,
+    Keyword::RIGHT,
+    Keyword::FULL,
+    Keyword::OUTER,
+    Keyword::CROSS,
+    Keyword::LATERAL,
+    Keyword::ON,
+    Keyword::USING,
+    Keyword::GROUP,
+    Keyword::ORDER,
+    Keyword::BY,
+    Keyword::HAVING,
+    Keyword::LIMIT,
+    Keyword::OFFSET,
+    Keyword::UNION,
+    Keyword::INTERSECT,
+    Keyword::EXCEPT,
+    Keyword::WITH,
+    Keyword::RECURSIVE,
+    Keyword::AS,
+    Keyword::ASC,
+    Keyword::DESC,
+    Keyword::NULLS,
+    Keyword::FIRST,
+    Keyword::LAST,
+    // expressions
+    Keyword::AND,
+    Keyword::OR,
+    Keyword::NOT,
+    Keyword::NULL,
+    Keyword::TRUE,
+    Keyword::FALSE,
+    Keyword::IS,
+    Keyword::IN,
+    Keyword::LIKE,
+    Keyword::ILIKE,
+    Keyword::BETWEEN,
+    Keyword::EXISTS,
+    Keyword::ALL,
+    Keyword::ANY,
+    Keyword::CASE,
+    Keyword::WHEN,
+    Keyword::THEN,
+    Keyword::ELSE,
+    Keyword::END,
+    Keyword::INTERVAL,
+    // window functions
+    Keyword::OVER,
+    Keyword::PARTITION,
+    Keyword::WINDOW,
+    Keyword::FILTER,
+    Keyword::ROWS,
+    Keyword::RANGE,
+    Keyword::PRECEDING,
+    Keyword::FOLLOWING,
+    Keyword::UNBOUNDED,
+    Keyword::CURRENT,
+    Keyword::ROW,
+    // DDL and DML
+    Keyword::CREATE,
+    Keyword::TABLE,
+    Keyword::INDEX,
+    Keyword::INSERT,
+    Keyword::INTO,
+    Keyword::VALUES,
+    Keyword::UPDATE,
+    Keyword::SET,
+    Keyword::DELETE,
+    Keyword::PRIMARY,
+    Keyword::FOREIGN,
+    Keyword::KEY,
+    Keyword::REFERENCES,
+    Keyword::UNIQUE,
+    Keyword::CHECK,
+    Keyword::DEFAULT,
+    Keyword::CONSTRAINT,
+    // types
+    Keyword::INT,
+    Keyword::INTEGER,
+    Keyword::BIGINT,
+    Keyword::SMALLINT,
+    Keyword::NUMERIC,
+    Keyword::DECIMAL,
+    Keyword::REAL,
+    Keyword::BOOLEAN,
+    Keyword::TEXT,
+    Keyword::VARCHAR,
+    Keyword::CHAR,
+    Keyword::DATE,
+    Keyword::TIMESTAMP,
+];
+
+/// Highlights SQL code written in `dialect`.
+///
+/// Emits the same `sql-hl-*` classes as the client-side `sql-highlight` library,
+/// so both share the styles in `main.css`. Code which can't be tokenized is returned as is.
+pub fn highlight_sql(sql: &str, dialect: SqlDialect) -> Markup {
+    let tokens = match Tokenizer::new(dialect.parser_dialect(), sql).tokenize() {
+        Ok(tokens) => tokens,
+        Err(_) => return html! { (sql) },
+    };
+
+    html! {
+        @for (idx, token) in tokens.iter().enumerate() {
+            @match token_class(token, next_significant(&tokens[idx + 1..])) {
+                Some(class) => span class={"sql-hl-" (class)} { (token.to_string()) },
+                None => (token.to_string()),
+            }
+        }
+    }
+}
+
+fn next_significant(tokens: &[Token]) -> Option<&Token> {
+    tokens
+        .iter()
+        .find(|token| !matches!(token, Token::Whitespace(_)))
+}
+
+fn token_class(token: &Token, next: Option<&Token>) -> Option<&'static str> {
+    match token {
+        Token::Word(word) if word.quote_style.is_none() => {
+            if HIGHLIGHTED_KEYWORDS.contains(&word.keyword) {
+                Some("keyword")
+            } else if next == Some(&Token::LParen) {
+                Some("function")
+            } else {
+                None
+            }
+        }
+        Token::Word(_) => None,
+        Token::Number(..) => Some("number"),
+        Token::SingleQuotedString(_)
+        | Token::DollarQuotedString(_)
+        | Token::EscapedStringLiteral(_)
+        | Token::NationalStringLiteral(_)
+        | Token::UnicodeStringLiteral(_) => Some("string"),
+        Token::LParen | Token::RParen | Token::LBracket | Token::RBracket => Some("bracket"),
+        Token::Whitespace(Whitespace::SingleLineComment { .. })
+        | Token::Whitespace(Whitespace::MultiLineComment(_)) => Some("comment"),
+        Token::Whitespace(_) | Token::EOF => None,
+        _ => Some("special"),
+    }
+}
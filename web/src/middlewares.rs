@@ -1,17 +1,11 @@
 use axum::{
     body::Body,
-    extract::State,
     http::{self, Method, Request},
     middleware::Next,
     response::{IntoResponse, Redirect},
 };
-use axum_extra::extract::cookie::{self, Cookie, CookieJar};
-use time::Duration;
 
-use crate::{
-    models::user::{User, UserClaims},
-    state::AppState,
-};
+use crate::models::user::UserClaims;
 
 /// HTTP header to disable caching.
 ///
@@ -45,45 +39,3 @@ pub async fn require_auth(
 
     next.run(request).await
 }
-
-/// Middleware to check if the user is admin.
-pub async fn require_admin(user: User, request: Request<Body>, next: Next) -> impl IntoResponse {
-    if !user.is_admin() {
-        return (http::StatusCode::FORBIDDEN, "Forbidden").into_response();
-    }
-
-    next.run(request).await
-}
-
-const CORBADO_PROJECT_ID_COOKIE: &str = "corbado_project_id";
-
-/// Middleware to add the Corbado project ID to cookies, if it's not already set.
-///
-/// Gets the projectId from the state.
-pub async fn add_corbado_project_id(
-    state: State<AppState>,
-    cookies: CookieJar,
-    request: Request<Body>,
-    next: Next,
-) -> impl IntoResponse {
-    let response = next.run(request).await;
-
-    let project_id = state.config().corbado_project_id();
-
-    if let Some(cookie) = cookies.get(CORBADO_PROJECT_ID_COOKIE) {
-        if cookie.value() == project_id {
-            return response.into_response();
-        }
-    }
-
-    let cookies = {
-        let mut cookie = Cookie::new(CORBADO_PROJECT_ID_COOKIE, project_id.to_string());
-        cookie.set_same_site(cookie::SameSite::Lax);
-        cookie.set_path("/");
-        cookie.set_max_age(Duration::days(365));
-
-        cookies.add(cookie)
-    };
-
-    (cookies, response).into_response()
-}
@@ -1,9 +1,30 @@
+pub mod achievement;
+pub mod analytics;
+pub mod assignment;
+pub mod cohort;
+pub mod comment;
 pub mod exercise;
 pub mod exercise_solution;
+pub mod leaderboard;
+pub mod progress;
+pub mod report;
+pub mod review;
 pub mod user;
 
+use serde::{Deserialize, Deserializer};
+
 pub trait Queryable {
     type Inner;
 
     fn parse(inner: Self::Inner) -> Self;
 }
+
+/// Deserializes empty strings (i.e. blank optional form fields) as `None`.
+pub fn empty_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+
+    Ok(value.filter(|value| !value.trim().is_empty()))
+}
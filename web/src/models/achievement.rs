@@ -0,0 +1,35 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
+
+use super::Queryable;
+use crate::achievements::Achievement;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct UserAchievement {
+    key: String,
+    earned_at: PrimitiveDateTime,
+}
+
+impl UserAchievement {
+    /// `None` if the achievement was removed from the code.
+    pub fn achievement(&self) -> Option<Achievement> {
+        Achievement::from_key(&self.key)
+    }
+}
+
+pub struct UserAchievementInner {
+    pub key: String,
+    pub earned_at: PrimitiveDateTime,
+}
+
+impl Queryable for UserAchievement {
+    type Inner = UserAchievementInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            key: inner.key,
+            earned_at: inner.earned_at,
+        }
+    }
+}
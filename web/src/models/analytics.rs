@@ -0,0 +1,110 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{Queryable, exercise::ExerciseId};
+
+/// How students fare with an exercise, aggregated over all of their attempts.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct ExerciseStats {
+    exercise_id: ExerciseId,
+    name: String,
+    attempts: i64,
+    users: i64,
+    solvers: i64,
+    /// Median number of attempts it took to solve, counting the correct one.
+    median_attempts_to_solve: Option<f64>,
+    /// Median time between the first attempt and the first correct one.
+    median_seconds_to_solve: Option<f64>,
+}
+
+impl ExerciseStats {
+    /// Share of users who attempted the exercise and solved it, in percent.
+    pub fn solve_rate(&self) -> Option<i64> {
+        (self.users > 0).then(|| self.solvers * 100 / self.users)
+    }
+}
+
+pub struct ExerciseStatsInner {
+    pub exercise_id: Uuid,
+    pub name: String,
+    pub attempts: i64,
+    pub users: i64,
+    pub solvers: i64,
+    pub median_attempts_to_solve: Option<f64>,
+    pub median_seconds_to_solve: Option<f64>,
+}
+
+impl Queryable for ExerciseStats {
+    type Inner = ExerciseStatsInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            exercise_id: inner.exercise_id.into(),
+            name: inner.name,
+            attempts: inner.attempts,
+            users: inner.users,
+            solvers: inner.solvers,
+            median_attempts_to_solve: inner.median_attempts_to_solve,
+            median_seconds_to_solve: inner.median_seconds_to_solve,
+        }
+    }
+}
+
+/// Incorrect result which was submitted repeatedly.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct CommonResult {
+    result: Value,
+    count: i64,
+    users: i64,
+}
+
+pub struct CommonResultInner {
+    pub result: Value,
+    pub count: i64,
+    pub users: i64,
+}
+
+impl Queryable for CommonResult {
+    type Inner = CommonResultInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            result: inner.result,
+            count: inner.count,
+            users: inner.users,
+        }
+    }
+}
+
+/// Cluster of incorrect queries which share the same fingerprint,
+/// i.e. only differ in formatting, aliases and literal values.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct CommonQuery {
+    fingerprint: String,
+    /// The latest query submitted in the cluster.
+    example: String,
+    count: i64,
+    users: i64,
+}
+
+pub struct CommonQueryInner {
+    pub fingerprint: String,
+    pub example: String,
+    pub count: i64,
+    pub users: i64,
+}
+
+impl Queryable for CommonQuery {
+    type Inner = CommonQueryInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            fingerprint: inner.fingerprint,
+            example: inner.example,
+            count: inner.count,
+            users: inner.users,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
+use uuid::Uuid;
+
+use sql_grimoire_id::define_id;
+
+use super::{Queryable, cohort::CohortId, exercise::ExerciseId};
+
+define_id!(AssignmentId, "as");
+
+/// Set of exercises a cohort has to solve between two dates.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct Assignment {
+    id: AssignmentId,
+    cohort_id: CohortId,
+    name: String,
+    opens_at: PrimitiveDateTime,
+    due_at: PrimitiveDateTime,
+}
+
+pub struct AssignmentInner {
+    pub id: Uuid,
+    pub cohort_id: Uuid,
+    pub name: String,
+    pub opens_at: PrimitiveDateTime,
+    pub due_at: PrimitiveDateTime,
+}
+
+impl Queryable for Assignment {
+    type Inner = AssignmentInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            cohort_id: inner.cohort_id.into(),
+            name: inner.name,
+            opens_at: inner.opens_at,
+            due_at: inner.due_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct AssignmentListItem {
+    id: AssignmentId,
+    name: String,
+    opens_at: PrimitiveDateTime,
+    due_at: PrimitiveDateTime,
+    exercises: i64,
+}
+
+pub struct AssignmentListItemInner {
+    pub id: Uuid,
+    pub name: String,
+    pub opens_at: PrimitiveDateTime,
+    pub due_at: PrimitiveDateTime,
+    pub exercises: i64,
+}
+
+impl Queryable for AssignmentListItem {
+    type Inner = AssignmentListItemInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            name: inner.name,
+            opens_at: inner.opens_at,
+            due_at: inner.due_at,
+            exercises: inner.exercises,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct AssignmentExercise {
+    exercise_id: ExerciseId,
+    name: String,
+}
+
+pub struct AssignmentExerciseInner {
+    pub exercise_id: Uuid,
+    pub name: String,
+}
+
+impl Queryable for AssignmentExercise {
+    type Inner = AssignmentExerciseInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            exercise_id: inner.exercise_id.into(),
+            name: inner.name,
+        }
+    }
+}
+
+/// Open assignment as seen by a student of the cohort.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct StudentAssignment {
+    id: AssignmentId,
+    name: String,
+    cohort_name: String,
+    due_at: PrimitiveDateTime,
+    exercise_ids: Vec<ExerciseId>,
+    /// Exercises solved before the due date
+    solved: i64,
+}
+
+pub struct StudentAssignmentInner {
+    pub id: Uuid,
+    pub name: String,
+    pub cohort_name: String,
+    pub due_at: PrimitiveDateTime,
+    pub exercise_ids: Vec<Uuid>,
+    pub solved: i64,
+}
+
+impl Queryable for StudentAssignment {
+    type Inner = StudentAssignmentInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            name: inner.name,
+            cohort_name: inner.cohort_name,
+            due_at: inner.due_at,
+            exercise_ids: inner.exercise_ids.into_iter().map(Into::into).collect(),
+            solved: inner.solved,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentStatus {
+    /// Solved before the due date
+    OnTime,
+    /// Solved, but only after the due date
+    Late,
+    /// Attempted, but not solved
+    Unsolved,
+    /// Not attempted at all
+    Missing,
+}
+
+impl AssignmentStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AssignmentStatus::OnTime => "on_time",
+            AssignmentStatus::Late => "late",
+            AssignmentStatus::Unsolved => "unsolved",
+            AssignmentStatus::Missing => "missing",
+        }
+    }
+}
+
+/// How a student did on an exercise of an assignment.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct AssignmentResult {
+    user_id: String,
+    exercise_id: ExerciseId,
+    attempts: i64,
+    /// First correct submission, late or not
+    solved_at: Option<PrimitiveDateTime>,
+}
+
+pub struct AssignmentResultInner {
+    pub user_id: String,
+    pub exercise_id: Uuid,
+    pub attempts: i64,
+    pub solved_at: Option<PrimitiveDateTime>,
+}
+
+impl Queryable for AssignmentResult {
+    type Inner = AssignmentResultInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            user_id: inner.user_id,
+            exercise_id: inner.exercise_id.into(),
+            attempts: inner.attempts,
+            solved_at: inner.solved_at,
+        }
+    }
+}
+
+impl AssignmentResult {
+    pub fn status(&self, due_at: PrimitiveDateTime) -> AssignmentStatus {
+        match self.solved_at {
+            Some(solved_at) if solved_at <= due_at => AssignmentStatus::OnTime,
+            Some(_) => AssignmentStatus::Late,
+            None if self.attempts > 0 => AssignmentStatus::Unsolved,
+            None => AssignmentStatus::Missing,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
+use uuid::Uuid;
+
+use sql_grimoire_id::define_id;
+
+use super::{Queryable, exercise::ExerciseId, user::user_name};
+
+define_id!(CohortId, "co");
+
+/// Group of students following the same course, run by a teacher.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct Cohort {
+    id: CohortId,
+    name: String,
+    teacher_id: String,
+    invite_code: String,
+}
+
+pub struct CohortInner {
+    pub id: Uuid,
+    pub name: String,
+    pub teacher_id: String,
+    pub invite_code: String,
+}
+
+impl Queryable for Cohort {
+    type Inner = CohortInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            name: inner.name,
+            teacher_id: inner.teacher_id,
+            invite_code: inner.invite_code,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct CohortListItem {
+    id: CohortId,
+    name: String,
+    members: i64,
+    created_at: PrimitiveDateTime,
+}
+
+pub struct CohortListItemInner {
+    pub id: Uuid,
+    pub name: String,
+    pub members: i64,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl Queryable for CohortListItem {
+    type Inner = CohortListItemInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            name: inner.name,
+            members: inner.members,
+            created_at: inner.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct CohortMember {
+    user_id: String,
+    email: Option<String>,
+    display_name: Option<String>,
+    joined_at: PrimitiveDateTime,
+}
+
+impl CohortMember {
+    pub fn name(&self) -> &str {
+        user_name(&self.user_id, self.display_name.as_deref())
+    }
+}
+
+pub struct CohortMemberInner {
+    pub user_id: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub joined_at: PrimitiveDateTime,
+}
+
+impl Queryable for CohortMember {
+    type Inner = CohortMemberInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            user_id: inner.user_id,
+            email: inner.email,
+            display_name: inner.display_name,
+            joined_at: inner.joined_at,
+        }
+    }
+}
+
+/// How a student does on an exercise, a cell of the cohort progress matrix.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct CohortProgressCell {
+    user_id: String,
+    exercise_id: ExerciseId,
+    attempts: i64,
+    solved: bool,
+    last_query: String,
+}
+
+pub struct CohortProgressCellInner {
+    pub user_id: String,
+    pub exercise_id: Uuid,
+    pub attempts: i64,
+    pub solved: bool,
+    pub last_query: String,
+}
+
+impl Queryable for CohortProgressCell {
+    type Inner = CohortProgressCellInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            user_id: inner.user_id,
+            exercise_id: inner.exercise_id.into(),
+            attempts: inner.attempts,
+            solved: inner.solved,
+            last_query: inner.last_query,
+        }
+    }
+}
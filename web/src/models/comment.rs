@@ -0,0 +1,52 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
+use uuid::Uuid;
+
+use sql_grimoire_id::define_id;
+
+use super::Queryable;
+
+define_id!(CommentId, "cm");
+
+/// Comment in the discussion thread of an exercise.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct Comment {
+    id: CommentId,
+    user_id: String,
+    author_name: Option<String>,
+    /// Whether the author has solved the exercise by now.
+    author_solved: bool,
+    body: String,
+    hidden: bool,
+    pinned: bool,
+    created_at: PrimitiveDateTime,
+}
+
+pub struct CommentInner {
+    pub id: Uuid,
+    pub user_id: String,
+    pub author_name: Option<String>,
+    pub author_solved: bool,
+    pub body: String,
+    pub hidden: bool,
+    pub pinned: bool,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl Queryable for Comment {
+    type Inner = CommentInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            user_id: inner.user_id,
+            author_name: inner.author_name,
+            author_solved: inner.author_solved,
+            body: inner.body,
+            hidden: inner.hidden,
+            pinned: inner.pinned,
+            created_at: inner.created_at,
+        }
+    }
+}
@@ -5,15 +5,70 @@ use uuid::Uuid;
 
 use sql_grimoire_id::define_id;
 
-use super::Queryable;
+use super::{Queryable, empty_as_none};
 
 define_id!(ExerciseSchemaId, "ex_schema");
 
+/// SQL dialect of a schema and the exercises on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialect {
+    #[default]
+    Postgres,
+    Sqlite,
+}
+
+impl SqlDialect {
+    pub const ALL: [SqlDialect; 2] = [SqlDialect::Postgres, SqlDialect::Sqlite];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "PostgreSQL",
+            SqlDialect::Sqlite => "SQLite",
+        }
+    }
+
+    /// Language name for code highlighting and the editor.
+    pub fn code_language(&self) -> &'static str {
+        match self {
+            SqlDialect::Postgres => "pgsql",
+            SqlDialect::Sqlite => "sql",
+        }
+    }
+
+    pub fn parser_dialect(&self) -> &'static dyn sqlparser::dialect::Dialect {
+        match self {
+            SqlDialect::Postgres => &sqlparser::dialect::PostgreSqlDialect {},
+            SqlDialect::Sqlite => &sqlparser::dialect::SQLiteDialect {},
+        }
+    }
+}
+
+impl From<String> for SqlDialect {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "sqlite" => SqlDialect::Sqlite,
+            // DB constraint only allows known dialects
+            _ => SqlDialect::Postgres,
+        }
+    }
+}
+
+impl std::fmt::Display for SqlDialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SqlDialect::Postgres => write!(f, "postgres"),
+            SqlDialect::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Getters, Dissolve)]
 pub struct ExerciseSchema {
     id: ExerciseSchemaId,
     name: String,
     schema: String,
+    dialect: SqlDialect,
 }
 
 impl ExerciseSchema {
@@ -24,12 +79,17 @@ impl ExerciseSchema {
     pub fn set_schema(&mut self, schema: String) {
         self.schema = schema;
     }
+
+    pub fn set_dialect(&mut self, dialect: SqlDialect) {
+        self.dialect = dialect;
+    }
 }
 
 pub struct ExerciseSchemaInner {
     pub id: Uuid,
     pub name: String,
     pub schema: String,
+    pub dialect: SqlDialect,
 }
 
 impl Queryable for ExerciseSchema {
@@ -40,6 +100,7 @@ impl Queryable for ExerciseSchema {
             id: inner.id.into(),
             name: inner.name,
             schema: inner.schema,
+            dialect: inner.dialect,
         }
     }
 }
@@ -48,11 +109,13 @@ impl Queryable for ExerciseSchema {
 pub struct ExerciseSchemaListItem {
     id: ExerciseSchemaId,
     name: String,
+    dialect: SqlDialect,
 }
 
 pub struct ExerciseSchemaListItemInner {
     pub id: Uuid,
     pub name: String,
+    pub dialect: SqlDialect,
 }
 
 impl Queryable for ExerciseSchemaListItem {
@@ -62,6 +125,7 @@ impl Queryable for ExerciseSchemaListItem {
         Self {
             id: inner.id.into(),
             name: inner.name,
+            dialect: inner.dialect,
         }
     }
 }
@@ -74,30 +138,66 @@ pub struct Exercise {
     schema_id: ExerciseSchemaId,
     name: String,
     question: String,
+    hint: Option<String>,
+    editorial: Option<String>,
     expected_query: String,
     expected_result: serde_json::Value,
     published_at: Option<OffsetDateTime>,
+    /// User who created the exercise, unknown for ones created before authorship was tracked.
+    author_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Dissolve)]
+#[derive(Debug, Clone, Serialize, Deserialize, Getters, Dissolve)]
 pub struct NewExercise {
     schema_id: ExerciseSchemaId,
     name: String,
     question: String,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    hint: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    editorial: Option<String>,
     expected_query: String,
     expected_result: String,
     published_at: Option<OffsetDateTime>,
 }
 
+impl NewExercise {
+    pub fn set_expected_result(&mut self, expected_result: &serde_json::Value) {
+        self.expected_result = expected_result.to_string();
+    }
+
+    pub fn set_published_at(&mut self, published_at: Option<OffsetDateTime>) {
+        self.published_at = published_at;
+    }
+}
+
 impl Exercise {
     pub fn update(&mut self, new_exercise: NewExercise) {
         self.schema_id = new_exercise.schema_id;
         self.name = new_exercise.name;
         self.question = new_exercise.question;
+        self.hint = new_exercise.hint;
+        self.editorial = new_exercise.editorial;
         self.expected_query = new_exercise.expected_query;
         self.expected_result = serde_json::from_str(&new_exercise.expected_result).unwrap();
         self.published_at = new_exercise.published_at;
     }
+
+    /// Replaces texts with their translations, keeping the ones which aren't translated.
+    pub fn translate(&mut self, translation: ExerciseTranslation) {
+        let ExerciseTranslation {
+            name,
+            question,
+            hint,
+            editorial,
+            ..
+        } = translation;
+
+        self.name = name.unwrap_or_else(|| std::mem::take(&mut self.name));
+        self.question = question.unwrap_or_else(|| std::mem::take(&mut self.question));
+        self.hint = hint.or_else(|| self.hint.take());
+        self.editorial = editorial.or_else(|| self.editorial.take());
+    }
 }
 
 pub struct ExerciseInner {
@@ -105,9 +205,12 @@ pub struct ExerciseInner {
     pub schema_id: Uuid,
     pub name: String,
     pub question: String,
+    pub hint: Option<String>,
+    pub editorial: Option<String>,
     pub expected_query: String,
     pub expected_result: serde_json::Value,
     pub published_at: Option<OffsetDateTime>,
+    pub author_id: Option<String>,
 }
 
 impl Queryable for Exercise {
@@ -119,9 +222,70 @@ impl Queryable for Exercise {
             schema_id: inner.schema_id.into(),
             name: inner.name,
             question: inner.question,
+            hint: inner.hint,
+            editorial: inner.editorial,
             expected_query: inner.expected_query,
             expected_result: inner.expected_result,
             published_at: inner.published_at,
+            author_id: inner.author_id,
+        }
+    }
+}
+
+/// Texts of an exercise in a non-default locale.
+///
+/// Missing texts fall back to the ones of the exercise itself.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct ExerciseTranslation {
+    exercise_id: ExerciseId,
+    locale: String,
+    name: Option<String>,
+    question: Option<String>,
+    hint: Option<String>,
+    editorial: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Dissolve)]
+pub struct NewExerciseTranslation {
+    #[serde(default, deserialize_with = "empty_as_none")]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    question: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    hint: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    editorial: Option<String>,
+}
+
+impl NewExerciseTranslation {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.question.is_none()
+            && self.hint.is_none()
+            && self.editorial.is_none()
+    }
+}
+
+pub struct ExerciseTranslationInner {
+    pub exercise_id: Uuid,
+    pub locale: String,
+    pub name: Option<String>,
+    pub question: Option<String>,
+    pub hint: Option<String>,
+    pub editorial: Option<String>,
+}
+
+impl Queryable for ExerciseTranslation {
+    type Inner = ExerciseTranslationInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            exercise_id: inner.exercise_id.into(),
+            locale: inner.locale,
+            name: inner.name,
+            question: inner.question,
+            hint: inner.hint,
+            editorial: inner.editorial,
         }
     }
 }
@@ -132,6 +296,8 @@ pub struct ExerciseListItem {
     name: String,
     solved: bool,
     published: bool,
+    author_id: Option<String>,
+    bookmarked: bool,
 }
 
 pub struct ExerciseListItemInner {
@@ -139,6 +305,8 @@ pub struct ExerciseListItemInner {
     pub name: String,
     pub solved: Option<bool>,
     pub published: bool,
+    pub author_id: Option<String>,
+    pub bookmarked: bool,
 }
 
 impl Queryable for ExerciseListItem {
@@ -150,6 +318,8 @@ impl Queryable for ExerciseListItem {
             name: inner.name,
             solved: inner.solved.unwrap_or(false),
             published: inner.published,
+            author_id: inner.author_id,
+            bookmarked: inner.bookmarked,
         }
     }
 }
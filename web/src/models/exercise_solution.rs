@@ -1,15 +1,19 @@
-use derive_getters::{Dissolve, Getters};
+use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use time::PrimitiveDateTime;
 use uuid::Uuid;
 
 use sql_grimoire_id::define_id;
 
-use super::{Queryable, exercise::ExerciseId};
+use super::{
+    Queryable,
+    exercise::{ExerciseId, SqlDialect},
+};
 
 define_id!(UserSolutionId, "us");
 
-#[derive(Debug, Clone, Serialize, Deserialize, Getters, Dissolve)]
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
 pub struct UserSolution {
     id: UserSolutionId,
     user_id: String,
@@ -17,6 +21,7 @@ pub struct UserSolution {
     query: String,
     result: Value,
     status: String,
+    created_at: PrimitiveDateTime,
 }
 
 pub struct UserSolutionInner {
@@ -26,6 +31,7 @@ pub struct UserSolutionInner {
     pub query: String,
     pub result: Value,
     pub status: String,
+    pub created_at: PrimitiveDateTime,
 }
 
 impl Queryable for UserSolution {
@@ -39,6 +45,111 @@ impl Queryable for UserSolution {
             query: inner.query,
             result: inner.result,
             status: inner.status,
+            created_at: inner.created_at,
+        }
+    }
+}
+
+/// A past attempt at an exercise, as shown in the attempt history.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct UserSolutionListItem {
+    id: UserSolutionId,
+    query: String,
+    status: String,
+    created_at: PrimitiveDateTime,
+}
+
+pub struct UserSolutionListItemInner {
+    pub id: Uuid,
+    pub query: String,
+    pub status: String,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl Queryable for UserSolutionListItem {
+    type Inner = UserSolutionListItemInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            query: inner.query,
+            status: inner.status,
+            created_at: inner.created_at,
+        }
+    }
+}
+
+/// A user's submission along with its exercise, as shown to admins.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct UserSubmission {
+    id: UserSolutionId,
+    exercise_id: ExerciseId,
+    exercise_name: String,
+    dialect: SqlDialect,
+    query: String,
+    status: String,
+    created_at: PrimitiveDateTime,
+}
+
+pub struct UserSubmissionInner {
+    pub id: Uuid,
+    pub exercise_id: Uuid,
+    pub exercise_name: String,
+    pub dialect: SqlDialect,
+    pub query: String,
+    pub status: String,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl Queryable for UserSubmission {
+    type Inner = UserSubmissionInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            exercise_id: inner.exercise_id.into(),
+            exercise_name: inner.exercise_name,
+            dialect: inner.dialect,
+            query: inner.query,
+            status: inner.status,
+            created_at: inner.created_at,
+        }
+    }
+}
+
+/// Cluster of correct queries sharing the fingerprint, as shown to users who solved the exercise.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct CommunitySolution {
+    fingerprint: String,
+    /// The first query submitted in the cluster.
+    example: String,
+    /// Name of the example's author, `None` unless they opted in.
+    author_name: Option<String>,
+    users: i64,
+    votes: i64,
+    voted: bool,
+}
+
+pub struct CommunitySolutionInner {
+    pub fingerprint: String,
+    pub example: String,
+    pub author_name: Option<String>,
+    pub users: i64,
+    pub votes: i64,
+    pub voted: bool,
+}
+
+impl Queryable for CommunitySolution {
+    type Inner = CommunitySolutionInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            fingerprint: inner.fingerprint,
+            example: inner.example,
+            author_name: inner.author_name,
+            users: inner.users,
+            votes: inner.votes,
+            voted: inner.voted,
         }
     }
 }
@@ -0,0 +1,73 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use super::Queryable;
+
+/// What leaderboards are ranked by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardMetric {
+    #[default]
+    Solved,
+    Score,
+    FirstTry,
+}
+
+impl LeaderboardMetric {
+    pub const ALL: &[LeaderboardMetric] = &[
+        LeaderboardMetric::Solved,
+        LeaderboardMetric::Score,
+        LeaderboardMetric::FirstTry,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardMetric::Solved => "solved",
+            LeaderboardMetric::Score => "score",
+            LeaderboardMetric::FirstTry => "first_try",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LeaderboardMetric::Solved => "Solved",
+            LeaderboardMetric::Score => "Score",
+            LeaderboardMetric::FirstTry => "First Try",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct LeaderboardEntry {
+    rank: i64,
+    user_id: String,
+    /// `None` for users who opted out of leaderboards or haven't picked a name
+    display_name: Option<String>,
+    solved: i64,
+    score: i64,
+    first_try: i64,
+}
+
+pub struct LeaderboardEntryInner {
+    pub rank: i64,
+    pub user_id: String,
+    pub display_name: Option<String>,
+    pub solved: i64,
+    pub score: i64,
+    pub first_try: i64,
+}
+
+impl Queryable for LeaderboardEntry {
+    type Inner = LeaderboardEntryInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            rank: inner.rank,
+            user_id: inner.user_id,
+            display_name: inner.display_name,
+            solved: inner.solved,
+            score: inner.score,
+            first_try: inner.first_try,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::{Date, PrimitiveDateTime};
+use uuid::Uuid;
+
+use super::{
+    Queryable,
+    exercise::{ExerciseId, ExerciseSchemaId},
+};
+
+/// User's progress on the published exercises of a track (i.e. exercise schema).
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct TrackProgress {
+    schema_id: ExerciseSchemaId,
+    name: String,
+    total: i64,
+    attempted: i64,
+    solved: i64,
+}
+
+pub struct TrackProgressInner {
+    pub schema_id: Uuid,
+    pub name: String,
+    pub total: i64,
+    pub attempted: i64,
+    pub solved: i64,
+}
+
+impl Queryable for TrackProgress {
+    type Inner = TrackProgressInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            schema_id: inner.schema_id.into(),
+            name: inner.name,
+            total: inner.total,
+            attempted: inner.attempted,
+            solved: inner.solved,
+        }
+    }
+}
+
+/// How many attempted exercises were solved with the very first attempt.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct FirstTryStats {
+    attempted: i64,
+    first_try: i64,
+}
+
+impl FirstTryStats {
+    /// Share of exercises solved on the first try, in percent.
+    pub fn rate(&self) -> Option<i64> {
+        (self.attempted > 0).then(|| self.first_try * 100 / self.attempted)
+    }
+}
+
+pub struct FirstTryStatsInner {
+    pub attempted: i64,
+    pub first_try: i64,
+}
+
+impl Queryable for FirstTryStats {
+    type Inner = FirstTryStatsInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            attempted: inner.attempted,
+            first_try: inner.first_try,
+        }
+    }
+}
+
+/// Number of attempts made during a day (in UTC).
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct ActivityDay {
+    day: Date,
+    attempts: i64,
+}
+
+pub struct ActivityDayInner {
+    pub day: Date,
+    pub attempts: i64,
+}
+
+impl Queryable for ActivityDay {
+    type Inner = ActivityDayInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            day: inner.day,
+            attempts: inner.attempts,
+        }
+    }
+}
+
+/// Exercise which was attempted, but not solved yet.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct UnsolvedExercise {
+    id: ExerciseId,
+    name: String,
+    last_attempt_at: PrimitiveDateTime,
+}
+
+pub struct UnsolvedExerciseInner {
+    pub id: Uuid,
+    pub name: String,
+    pub last_attempt_at: PrimitiveDateTime,
+}
+
+impl Queryable for UnsolvedExercise {
+    type Inner = UnsolvedExerciseInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            name: inner.name,
+            last_attempt_at: inner.last_attempt_at,
+        }
+    }
+}
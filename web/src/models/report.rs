@@ -0,0 +1,113 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
+use uuid::Uuid;
+
+use sql_grimoire_id::define_id;
+
+use super::{
+    Queryable,
+    exercise::{ExerciseId, SqlDialect},
+    user::user_name,
+};
+
+define_id!(ReportId, "rp");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    #[default]
+    Open,
+    Resolved,
+    WontFix,
+}
+
+impl ReportStatus {
+    pub const ALL: [ReportStatus; 3] = [
+        ReportStatus::Open,
+        ReportStatus::Resolved,
+        ReportStatus::WontFix,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportStatus::Open => "Open",
+            ReportStatus::Resolved => "Resolved",
+            ReportStatus::WontFix => "Won't Fix",
+        }
+    }
+}
+
+impl From<String> for ReportStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "resolved" => ReportStatus::Resolved,
+            "wont_fix" => ReportStatus::WontFix,
+            // DB constraint only allows known statuses
+            _ => ReportStatus::Open,
+        }
+    }
+}
+
+impl std::fmt::Display for ReportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportStatus::Open => write!(f, "open"),
+            ReportStatus::Resolved => write!(f, "resolved"),
+            ReportStatus::WontFix => write!(f, "wont_fix"),
+        }
+    }
+}
+
+/// Problem with an exercise reported by a student, as shown in the admin inbox.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct ExerciseReport {
+    id: ReportId,
+    exercise_id: ExerciseId,
+    exercise_name: String,
+    dialect: SqlDialect,
+    user_id: String,
+    user_display_name: Option<String>,
+    query: String,
+    message: String,
+    status: ReportStatus,
+    created_at: PrimitiveDateTime,
+}
+
+impl ExerciseReport {
+    pub fn user_name(&self) -> &str {
+        user_name(&self.user_id, self.user_display_name.as_deref())
+    }
+}
+
+pub struct ExerciseReportInner {
+    pub id: Uuid,
+    pub exercise_id: Uuid,
+    pub exercise_name: String,
+    pub dialect: SqlDialect,
+    pub user_id: String,
+    pub user_display_name: Option<String>,
+    pub query: String,
+    pub message: String,
+    pub status: ReportStatus,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl Queryable for ExerciseReport {
+    type Inner = ExerciseReportInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id.into(),
+            exercise_id: inner.exercise_id.into(),
+            exercise_name: inner.exercise_name,
+            dialect: inner.dialect,
+            user_id: inner.user_id,
+            user_display_name: inner.user_display_name,
+            query: inner.query,
+            message: inner.message,
+            status: inner.status,
+            created_at: inner.created_at,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
+
+use super::Queryable;
+use crate::review::ReviewSchedule;
+
+/// Spaced repetition schedule of an exercise the user has solved.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct ReviewCard {
+    ease: f64,
+    interval_days: i32,
+    repetitions: i32,
+    due_at: PrimitiveDateTime,
+    reviewed_at: Option<PrimitiveDateTime>,
+}
+
+impl ReviewCard {
+    pub fn schedule(&self) -> ReviewSchedule {
+        ReviewSchedule {
+            ease: self.ease,
+            interval_days: self.interval_days,
+            repetitions: self.repetitions,
+        }
+    }
+}
+
+pub struct ReviewCardInner {
+    pub ease: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub due_at: PrimitiveDateTime,
+    pub reviewed_at: Option<PrimitiveDateTime>,
+}
+
+impl Queryable for ReviewCard {
+    type Inner = ReviewCardInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            ease: inner.ease,
+            interval_days: inner.interval_days,
+            repetitions: inner.repetitions,
+            due_at: inner.due_at,
+            reviewed_at: inner.reviewed_at,
+        }
+    }
+}
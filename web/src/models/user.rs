@@ -1,19 +1,109 @@
 use derive_getters::Getters;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Serialize};
+use time::PrimitiveDateTime;
 
 use super::Queryable;
+use crate::error::SqlGrimoireError;
+
+/// Actions which are only allowed to some roles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Permission {
+    /// Create exercises and edit the own ones
+    AuthorExercises,
+    /// Edit exercises of any author
+    EditAllExercises,
+    /// Publish and unpublish exercises
+    PublishExercises,
+    /// Create and edit exercise schemas
+    EditSchemas,
+    /// See how students do on exercises
+    ViewAnalytics,
+    /// Create cohorts and follow the progress and submissions of their students
+    TeachCohorts,
+    /// See submissions of any user
+    ViewSubmissions,
+    /// Change roles of other users
+    ManageUsers,
+    /// Hide, pin and delete comments on exercises
+    ModerateComments,
+    /// Go through problems with exercises reported by students
+    TriageReports,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UserRole {
     Admin,
+    Reviewer,
+    Teacher,
+    Author,
     User,
+    /// Role missing from this version of the app (i.e. after a rollback).
+    ///
+    /// Treated as a regular user without any extra permissions.
     Unknown(String),
 }
 
+impl UserRole {
+    /// Roles which can be assigned to users, from the least privileged one.
+    pub const ALL: &[UserRole] = &[
+        UserRole::User,
+        UserRole::Author,
+        UserRole::Reviewer,
+        UserRole::Teacher,
+        UserRole::Admin,
+    ];
+
+    pub fn label(&self) -> &str {
+        match self {
+            UserRole::Admin => "Admin",
+            UserRole::Reviewer => "Reviewer",
+            UserRole::Teacher => "Teacher",
+            UserRole::Author => "Author",
+            UserRole::User => "User",
+            UserRole::Unknown(value) => value,
+        }
+    }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+
+        match self {
+            UserRole::Admin => &[
+                AuthorExercises,
+                EditAllExercises,
+                PublishExercises,
+                EditSchemas,
+                ViewAnalytics,
+                TeachCohorts,
+                ViewSubmissions,
+                ManageUsers,
+                ModerateComments,
+                TriageReports,
+            ],
+            UserRole::Reviewer => &[
+                AuthorExercises,
+                EditAllExercises,
+                PublishExercises,
+                EditSchemas,
+                ViewAnalytics,
+                TriageReports,
+            ],
+            // submissions of their own students only, see `db::cohort::is_teacher_of`
+            UserRole::Teacher => &[TeachCohorts],
+            // schemas are shared between exercises, so only reviewers and admins edit them
+            UserRole::Author => &[AuthorExercises, ViewAnalytics],
+            UserRole::User | UserRole::Unknown(_) => &[],
+        }
+    }
+}
+
 impl From<String> for UserRole {
     fn from(value: String) -> Self {
         match value.as_str() {
             "admin" => UserRole::Admin,
+            "reviewer" => UserRole::Reviewer,
+            "teacher" => UserRole::Teacher,
+            "author" => UserRole::Author,
             "user" => UserRole::User,
             _ => UserRole::Unknown(value),
         }
@@ -24,6 +114,9 @@ impl std::fmt::Display for UserRole {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             UserRole::Admin => write!(f, "admin"),
+            UserRole::Reviewer => write!(f, "reviewer"),
+            UserRole::Teacher => write!(f, "teacher"),
+            UserRole::Author => write!(f, "author"),
             UserRole::User => write!(f, "user"),
             UserRole::Unknown(value) => write!(f, "{}", value),
         }
@@ -34,17 +127,54 @@ impl std::fmt::Display for UserRole {
 pub struct User {
     id: String,
     role: UserRole,
+    locale: Option<String>,
+    email: Option<String>,
+    display_name: Option<String>,
+    leaderboard_opt_out: bool,
+    share_solution_name: bool,
+}
+
+/// Name to show for a user: the display name if there's one, the ID otherwise.
+pub fn user_name<'a>(id: &'a str, display_name: Option<&'a str>) -> &'a str {
+    display_name.unwrap_or(id)
 }
 
 impl User {
-    pub fn is_admin(&self) -> bool {
-        self.role == UserRole::Admin
+    pub fn name(&self) -> &str {
+        user_name(&self.id, self.display_name.as_deref())
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.permissions().contains(&permission)
+    }
+
+    /// Checks the permission, failing with `403 Forbidden` if it's missing.
+    pub fn require(&self, permission: Permission) -> Result<(), SqlGrimoireError> {
+        if self.can(permission) {
+            Ok(())
+        } else {
+            Err(SqlGrimoireError::forbidden(format!(
+                "{} role can't do that",
+                self.role.label()
+            )))
+        }
+    }
+
+    /// Authors can only edit their own exercises, reviewers and admins can edit any.
+    pub fn can_edit_exercise(&self, author_id: Option<&str>) -> bool {
+        self.can(Permission::EditAllExercises)
+            || (self.can(Permission::AuthorExercises) && author_id == Some(self.id.as_str()))
     }
 }
 
 pub struct UserInner {
     pub id: String,
     pub role: UserRole,
+    pub locale: Option<String>,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub leaderboard_opt_out: bool,
+    pub share_solution_name: bool,
 }
 
 impl Queryable for User {
@@ -54,6 +184,58 @@ impl Queryable for User {
         Self {
             id: inner.id,
             role: inner.role,
+            locale: inner.locale,
+            email: inner.email,
+            display_name: inner.display_name,
+            leaderboard_opt_out: inner.leaderboard_opt_out,
+            share_solution_name: inner.share_solution_name,
+        }
+    }
+}
+
+/// User as shown in the admin user list, along with their activity.
+#[derive(Debug, Clone, Serialize, Deserialize, Getters)]
+pub struct UserListItem {
+    id: String,
+    email: Option<String>,
+    display_name: Option<String>,
+    role: UserRole,
+    created_at: PrimitiveDateTime,
+    last_seen_at: Option<PrimitiveDateTime>,
+    solutions: i64,
+    solved: i64,
+}
+
+pub struct UserListItemInner {
+    pub id: String,
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    pub role: UserRole,
+    pub created_at: PrimitiveDateTime,
+    pub last_seen_at: Option<PrimitiveDateTime>,
+    pub solutions: i64,
+    pub solved: i64,
+}
+
+impl UserListItem {
+    pub fn name(&self) -> &str {
+        user_name(&self.id, self.display_name.as_deref())
+    }
+}
+
+impl Queryable for UserListItem {
+    type Inner = UserListItemInner;
+
+    fn parse(inner: Self::Inner) -> Self {
+        Self {
+            id: inner.id,
+            email: inner.email,
+            display_name: inner.display_name,
+            role: inner.role,
+            created_at: inner.created_at,
+            last_seen_at: inner.last_seen_at,
+            solutions: inner.solutions,
+            solved: inner.solved,
         }
     }
 }
@@ -62,20 +244,34 @@ impl Queryable for User {
 pub struct UserClaims {
     iss: String,
     sub: String,
-    #[serde(deserialize_with = "deserialize_datetime_from_timestamp")]
+    #[serde(with = "time::serde::timestamp")]
     exp: time::OffsetDateTime,
-    #[serde(deserialize_with = "deserialize_datetime_from_timestamp")]
+    #[serde(with = "time::serde::timestamp")]
     nbf: time::OffsetDateTime,
     email: String,
+    /// Not every identity provider sends a name
+    #[serde(default)]
+    name: Option<String>,
 }
 
-fn deserialize_datetime_from_timestamp<'de, D>(
-    deserializer: D,
-) -> Result<time::OffsetDateTime, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let timestamp = i64::deserialize(deserializer)?;
+impl UserClaims {
+    /// Claims of a session the app issues itself, valid from now on for `lifetime`.
+    pub fn new(
+        iss: String,
+        sub: String,
+        email: String,
+        name: Option<String>,
+        lifetime: time::Duration,
+    ) -> Self {
+        let now = time::OffsetDateTime::now_utc();
 
-    time::OffsetDateTime::from_unix_timestamp(timestamp).map_err(serde::de::Error::custom)
+        Self {
+            iss,
+            sub,
+            exp: now + lifetime,
+            nbf: now,
+            email,
+            name,
+        }
+    }
 }
@@ -1,7 +1,8 @@
 use maud::{DOCTYPE, Markup, html};
+use tracing::warn;
 
 use crate::{
-    models::user::{User, UserRole},
+    models::user::{Permission, User, UserRole},
     static_files,
 };
 
@@ -40,13 +41,7 @@ pub fn head_custom_content(page_title: &str, head_content: Markup) -> Markup {
             // Turbo
             script type="module" src="https://cdn.jsdelivr.net/npm/@hotwired/turbo@8.0.11/+esm" {}
 
-            // Corbado
-            link
-                rel="stylesheet"
-                href="https://unpkg.com/@corbado/web-js@2/dist/bundle/index.css"
-                crossorigin="anonymous"
-                referrerpolicy="no-referrer";
-            script src="https://unpkg.com/@corbado/web-js@2/dist/bundle/index.js" {}
+            // Corbado, only loaded when it's the auth provider
             script type="module" src={"/static/" (static_files::corbado_init.name)} {}
 
             // Goatcounter
@@ -72,18 +67,27 @@ pub fn head_custom_content(page_title: &str, head_content: Markup) -> Markup {
 
 #[derive(Debug, Clone, Copy)]
 pub enum AuthState {
-    User,
-    Admin,
+    /// Signed in user, along with the permissions of their role.
+    User(&'static [Permission]),
     Unauthenticated,
 }
 
+impl AuthState {
+    pub fn can(&self, permission: Permission) -> bool {
+        match self {
+            AuthState::User(permissions) => permissions.contains(&permission),
+            AuthState::Unauthenticated => false,
+        }
+    }
+}
+
 impl User {
     pub fn auth_state(&self) -> AuthState {
-        match *self.role() {
-            UserRole::Admin => AuthState::Admin,
-            UserRole::User => AuthState::User,
-            UserRole::Unknown(_) => AuthState::Unauthenticated,
+        if let UserRole::Unknown(role) = self.role() {
+            warn!(user_id = %self.id(), %role, "Unknown user role, treating as a regular user");
         }
+
+        AuthState::User(self.role().permissions())
     }
 }
 
@@ -91,7 +95,7 @@ impl User {
 pub fn app_layout(content: Markup, title: &str, auth_state: AuthState) -> Markup {
     let title_href = match auth_state {
         AuthState::Unauthenticated => "#",
-        AuthState::User | AuthState::Admin => "/",
+        AuthState::User(_) => "/",
     };
 
     html! {
@@ -103,12 +107,58 @@ pub fn app_layout(content: Markup, title: &str, auth_state: AuthState) -> Markup
                         span class="nav__title" { (title) }
                     }
                     div class="nav__menu" {
-                        @if let AuthState::Admin = auth_state {
+                        @if let AuthState::User(_) = auth_state {
+                            a href="/me/" class="nav__link" {
+                                i data-lucide="trending-up" class="nav__link-icon" {}
+                                span { "My Progress" }
+                            }
+                            a href="/review/" class="nav__link" {
+                                i data-lucide="repeat" class="nav__link-icon" {}
+                                span { "Review" }
+                            }
+                            a href="/leaderboard/" class="nav__link" {
+                                i data-lucide="trophy" class="nav__link-icon" {}
+                                span { "Leaderboard" }
+                            }
+                        }
+                        @if auth_state.can(Permission::TeachCohorts) {
+                            a href="/cohorts/" class="nav__link" {
+                                i data-lucide="graduation-cap" class="nav__link-icon" {}
+                                span { "Cohorts" }
+                            }
+                        }
+                        @if auth_state.can(Permission::ViewAnalytics) {
+                            a href="/admin/analytics/" class="nav__link" {
+                                i data-lucide="activity" class="nav__link-icon" {}
+                                span { "Analytics" }
+                            }
+                        }
+                        @if auth_state.can(Permission::EditSchemas) {
                             a href="/admin/exercise/schemas/" class="nav__link" {
                                 i data-lucide="database" class="nav__link-icon" {}
                                 span { "Schemas" }
                             }
                         }
+                        @if auth_state.can(Permission::TriageReports) {
+                            a href="/admin/reports/" class="nav__link" {
+                                i data-lucide="flag" class="nav__link-icon" {}
+                                span { "Reports" }
+                            }
+                        }
+                        @if auth_state.can(Permission::ManageUsers) {
+                            a href="/admin/users/" class="nav__link" {
+                                i data-lucide="users" class="nav__link-icon" {}
+                                span { "Users" }
+                            }
+                        }
+                        @if let AuthState::User(_) = auth_state {
+                            form class="nav__logout" method="post" action="/auth/logout/" {
+                                button type="submit" class="nav__link nav__link--button" {
+                                    i data-lucide="log-out" class="nav__link-icon" {}
+                                    span { "Sign Out" }
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -120,6 +170,68 @@ pub fn app_layout(content: Markup, title: &str, auth_state: AuthState) -> Markup
     }
 }
 
+/// Buttons to choose the content locale, saving it as the user's preference.
+///
+/// "Auto" resets the preference, so the locale is picked from browser settings.
+/// Renders nothing if there's only one locale to choose from.
+pub fn locale_switcher(supported: &[&str], preference: Option<&str>) -> Markup {
+    html! {
+        @if supported.len() > 1 {
+            form class="locale-switcher" method="post" action="/settings/locale/" {
+                button
+                    type="submit"
+                    name="locale"
+                    value=""
+                    class={"locale-switcher__option" @if preference.is_none() { " locale-switcher__option--active" }}
+                {
+                    "Auto"
+                }
+                @for locale in supported {
+                    button
+                        type="submit"
+                        name="locale"
+                        value=(locale)
+                        class={"locale-switcher__option" @if preference == Some(*locale) { " locale-switcher__option--active" }}
+                    {
+                        (locale.to_uppercase())
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Formats a timestamp (stored in UTC) for display, i.e. `2025-01-12 14:03 UTC`.
+pub fn format_datetime(datetime: time::PrimitiveDateTime) -> String {
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02} UTC",
+        datetime.year(),
+        u8::from(datetime.month()),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+    )
+}
+
+/// Formats the time left until a deadline, i.e. `due in 3 days`.
+pub fn format_due(due_at: time::PrimitiveDateTime, now: time::PrimitiveDateTime) -> String {
+    let left = due_at - now;
+
+    let (amount, unit) = if left.is_negative() || left.is_zero() {
+        return "overdue".to_string();
+    } else if left.whole_days() > 0 {
+        (left.whole_days(), "day")
+    } else if left.whole_hours() > 0 {
+        (left.whole_hours(), "hour")
+    } else {
+        (left.whole_minutes().max(1), "minute")
+    };
+
+    let plural = if amount == 1 { "" } else { "s" };
+
+    format!("due in {amount} {unit}{plural}")
+}
+
 /// Footer template.
 ///
 /// It's better to use `page`, instead of using this directly.
@@ -145,3 +257,25 @@ pub fn footer() -> Markup {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, macros::datetime};
+
+    use super::*;
+
+    #[test]
+    fn formats_time_left() {
+        let now = datetime!(2025-01-12 14:03);
+
+        assert_eq!(format_due(now + Duration::days(3), now), "due in 3 days");
+        assert_eq!(format_due(now + Duration::hours(36), now), "due in 1 day");
+        assert_eq!(format_due(now + Duration::hours(5), now), "due in 5 hours");
+        assert_eq!(
+            format_due(now + Duration::seconds(30), now),
+            "due in 1 minute"
+        );
+        assert_eq!(format_due(now, now), "overdue");
+        assert_eq!(format_due(now - Duration::days(1), now), "overdue");
+    }
+}
@@ -0,0 +1,143 @@
+//! Spaced repetition of solved exercises, scheduled with SM-2.
+//!
+//! Every solved exercise becomes a review card. Once it's due, the user solves it again
+//! from memory and rates how hard it was, which decides when it comes back.
+
+use serde::{Deserialize, Serialize};
+
+/// Self-rated difficulty of a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewRating {
+    /// Couldn't solve it, the card starts over.
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl ReviewRating {
+    pub const ALL: [ReviewRating; 4] = [
+        ReviewRating::Again,
+        ReviewRating::Hard,
+        ReviewRating::Good,
+        ReviewRating::Easy,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReviewRating::Again => "Again",
+            ReviewRating::Hard => "Hard",
+            ReviewRating::Good => "Good",
+            ReviewRating::Easy => "Easy",
+        }
+    }
+
+    /// Response quality on the 0-5 scale of SM-2, below 3 is a lapse.
+    fn quality(&self) -> f64 {
+        match self {
+            ReviewRating::Again => 2.0,
+            ReviewRating::Hard => 3.0,
+            ReviewRating::Good => 4.0,
+            ReviewRating::Easy => 5.0,
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReviewRating::Again => write!(f, "again"),
+            ReviewRating::Hard => write!(f, "hard"),
+            ReviewRating::Good => write!(f, "good"),
+            ReviewRating::Easy => write!(f, "easy"),
+        }
+    }
+}
+
+const MIN_EASE: f64 = 1.3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReviewSchedule {
+    pub ease: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+}
+
+impl ReviewSchedule {
+    /// Solving the exercise for the first time counts as the first repetition.
+    pub const INITIAL: ReviewSchedule = ReviewSchedule {
+        ease: 2.5,
+        interval_days: 1,
+        repetitions: 1,
+    };
+
+    pub fn next(&self, rating: ReviewRating) -> ReviewSchedule {
+        let quality = rating.quality();
+        let ease =
+            (self.ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(MIN_EASE);
+
+        if quality < 3.0 {
+            return ReviewSchedule {
+                ease,
+                interval_days: 1,
+                repetitions: 0,
+            };
+        }
+
+        let interval_days = match self.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (f64::from(self.interval_days) * ease).round() as i32,
+        };
+
+        ReviewSchedule {
+            ease,
+            interval_days,
+            repetitions: self.repetitions + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_intervals() {
+        let second = ReviewSchedule::INITIAL.next(ReviewRating::Good);
+        assert_eq!(second.interval_days, 6);
+        assert_eq!(second.repetitions, 2);
+        assert!((second.ease - 2.5).abs() < 1e-9);
+
+        let third = second.next(ReviewRating::Good);
+        assert_eq!(third.interval_days, 15);
+
+        let easy = second.next(ReviewRating::Easy);
+        assert!(easy.ease > second.ease);
+        assert!(easy.interval_days > third.interval_days);
+    }
+
+    #[test]
+    fn lapses_start_over() {
+        let schedule = ReviewSchedule::INITIAL
+            .next(ReviewRating::Good)
+            .next(ReviewRating::Again);
+
+        assert_eq!(schedule.interval_days, 1);
+        assert_eq!(schedule.repetitions, 0);
+        assert!(schedule.ease < ReviewSchedule::INITIAL.ease);
+
+        assert_eq!(schedule.next(ReviewRating::Good).interval_days, 1);
+    }
+
+    #[test]
+    fn limits_ease() {
+        let mut schedule = ReviewSchedule::INITIAL;
+        for _ in 0..20 {
+            schedule = schedule.next(ReviewRating::Again);
+        }
+
+        assert_eq!(schedule.ease, MIN_EASE);
+    }
+}
@@ -1,6 +1,14 @@
+pub mod account;
 pub mod admin;
+pub mod assignment;
+pub mod bookmark;
+pub mod cohort;
+pub mod discussion;
 pub mod exercise_run;
 pub mod health;
-pub mod login;
+pub mod leaderboard;
 pub mod main;
+pub mod me;
+pub mod review;
 pub mod serve_static;
+pub mod settings;
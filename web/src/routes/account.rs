@@ -0,0 +1,132 @@
+use axum::{
+    debug_handler,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::{
+    Cached,
+    cookie::{Cookie, CookieJar},
+};
+use eyre::{OptionExt, WrapErr};
+use maud::{Markup, html};
+use tracing::warn;
+
+use crate::{
+    db::{leaderboard, user},
+    error::Result,
+    models::user::User,
+    partials::{app_layout, page},
+    state::AppState,
+};
+
+/// Serves everything stored about a user as a JSON file.
+pub(crate) fn data_download(user_id: &str, data: serde_json::Value) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_owned()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"sql-grimoire-{user_id}.json\""),
+            ),
+        ],
+        serde_json::to_string_pretty(&data).unwrap_or_default(),
+    )
+        .into_response()
+}
+
+/// Confirmation page for deleting an account, the form posts back to the same URL.
+pub(crate) fn delete_confirmation(title: &str, cancel_href: &str) -> Markup {
+    html! {
+        div class="content" {
+            form class="form" method="post" {
+                h1 class="form__title" { (title) }
+                p class="form__hint" {
+                    "All submitted solutions, achievements and cohort memberships will be deleted, "
+                    "along with the cohorts taught by this account. This can't be undone."
+                }
+                p class="form__hint" {
+                    "Logging in again later creates a new, empty account."
+                }
+                div class="form__actions" {
+                    a class="button button--secondary" href=(cancel_href) { "Cancel" }
+                    button type="submit" class="button button--danger" {
+                        i data-lucide="trash-2" class="button__icon" {}
+                        "Delete Account"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Removes the user's data from the leaderboards right away, instead of on the next refresh.
+pub(crate) async fn refresh_leaderboard_after_deletion(state: &AppState) {
+    let result = match state.db().acquire().await {
+        Ok(mut conn) => leaderboard::refresh_leaderboard(&mut conn).await,
+        Err(err) => Err(err).wrap_err("Failed to acquire DB connection"),
+    };
+
+    if let Err(err) = result {
+        warn!(?err, "Failed to refresh leaderboard after deleting a user");
+    }
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn data_export(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let data = user::get_user_data(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to get user data")?
+        .ok_or_eyre("User not found")?;
+
+    Ok(data_download(user.id(), data))
+}
+
+#[debug_handler(state = AppState)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_account(Cached(user): Cached<User>) -> Result<impl IntoResponse> {
+    Ok(page(
+        "SQL Grimoire - Delete Account",
+        app_layout(
+            delete_confirmation("Delete your account?", "/me/"),
+            "SQL Grimoire",
+            user.auth_state(),
+        ),
+    )
+    .into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn delete_account_post(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    cookies: CookieJar,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    user::delete_user(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to delete user")?;
+
+    refresh_leaderboard_after_deletion(&state).await;
+
+    // the session is useless without the user, log out right away
+    let cookies = cookies.remove(Cookie::build(state.auth().session_cookie()).path("/"));
+
+    Ok((cookies, Redirect::to("/auth/login/")).into_response())
+}
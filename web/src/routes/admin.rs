@@ -1,8 +1,18 @@
+mod exercise_analytics;
 mod exercise_edit;
+mod exercise_preview;
+mod exercise_report;
 
 mod exercise_schema_edit;
 mod exercise_schema_list;
+mod exercise_translation;
+mod user_list;
 
+pub use exercise_analytics::*;
 pub use exercise_edit::*;
+pub use exercise_preview::*;
+pub use exercise_report::*;
 pub use exercise_schema_edit::*;
 pub use exercise_schema_list::*;
+pub use exercise_translation::*;
+pub use user_list::*;
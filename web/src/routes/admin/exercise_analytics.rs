@@ -0,0 +1,306 @@
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http,
+    response::IntoResponse,
+};
+use axum_extra::extract::Cached;
+use eyre::{OptionExt, WrapErr};
+use maud::{Markup, html};
+use serde_json::Value;
+
+use crate::{
+    db::{analytics, exercise},
+    error::Result,
+    markdown::highlight_sql,
+    models::{
+        analytics::ExerciseStats,
+        exercise::ExerciseId,
+        user::{Permission, User},
+    },
+    partials::{app_layout, page},
+    state::AppState,
+};
+
+/// Number of common incorrect results and queries shown for an exercise.
+const COMMON_LIMIT: i64 = 10;
+
+/// Maximum length of a result shown in the list of common incorrect ones.
+const RESULT_PREVIEW_LENGTH: usize = 300;
+
+/// Formats a duration in seconds roughly, i.e. `3m 20s` or `2d 5h`.
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as i64;
+
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m {}s", seconds / 60, seconds % 60),
+        3600..86400 => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+        _ => format!("{}d {}h", seconds / 86400, seconds % 86400 / 3600),
+    }
+}
+
+fn format_median(median: Option<f64>) -> String {
+    median.map_or_else(|| "–".to_string(), |median| format!("{median:.1}"))
+}
+
+fn format_rate(rate: Option<i64>) -> String {
+    rate.map_or_else(|| "–".to_string(), |rate| format!("{rate}%"))
+}
+
+fn result_preview(result: &Value) -> String {
+    let mut preview = result.to_string();
+
+    if preview.len() > RESULT_PREVIEW_LENGTH {
+        let end = preview.floor_char_boundary(RESULT_PREVIEW_LENGTH);
+        preview.truncate(end);
+        preview.push('…');
+    }
+
+    preview
+}
+
+fn stats_cards(stats: &ExerciseStats) -> Markup {
+    html! {
+        div class="stats" {
+            div class="stats__card" {
+                span class="stats__value" { (stats.attempts()) }
+                span class="stats__label" { "Attempts" }
+            }
+            div class="stats__card" {
+                span class="stats__value" { (stats.users()) }
+                span class="stats__label" { "Unique users" }
+            }
+            div class="stats__card" {
+                span class="stats__value" { (format_rate(stats.solve_rate())) }
+                span class="stats__label" { "Solve rate" }
+            }
+            div class="stats__card" {
+                span class="stats__value" { (format_median(*stats.median_attempts_to_solve())) }
+                span class="stats__label" { "Median attempts to solve" }
+            }
+            div class="stats__card" {
+                span class="stats__value" {
+                    (stats.median_seconds_to_solve().map_or_else(|| "–".to_string(), format_duration))
+                }
+                span class="stats__label" { "Median time to solve" }
+            }
+        }
+    }
+}
+
+/// Analytics of all exercises, to spot the ones which are too hard.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn analytics_overview(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::ViewAnalytics)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let stats = analytics::get_exercise_stats(&mut conn, None)
+        .await
+        .wrap_err("Failed to get exercise stats")?;
+
+    let active_users = analytics::count_active_users(&mut conn)
+        .await
+        .wrap_err("Failed to count active users")?;
+
+    let attempts: i64 = stats.iter().map(|stats| stats.attempts()).sum();
+    let attempted: i64 = stats.iter().map(|stats| stats.users()).sum();
+    let solved: i64 = stats.iter().map(|stats| stats.solvers()).sum();
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "Exercise Analytics" }
+            }
+
+            div class="stats" {
+                div class="stats__card" {
+                    span class="stats__value" { (attempts) }
+                    span class="stats__label" { "Attempts" }
+                }
+                div class="stats__card" {
+                    span class="stats__value" { (active_users) }
+                    span class="stats__label" { "Unique users" }
+                }
+                div class="stats__card" {
+                    span class="stats__value" {
+                        (format_rate((attempted > 0).then(|| solved * 100 / attempted)))
+                    }
+                    span class="stats__label" { "Attempted exercises solved" }
+                }
+            }
+
+            div class="table-container panel--progress" {
+                table class="table" {
+                    thead {
+                        tr {
+                            th class="table__header" { "Exercise" }
+                            th class="table__header" { "Attempts" }
+                            th class="table__header" { "Users" }
+                            th class="table__header" { "Solve Rate" }
+                            th class="table__header" { "Median Attempts" }
+                            th class="table__header" { "Median Time" }
+                        }
+                    }
+                    tbody {
+                        @for exercise_stats in &stats {
+                            tr class="table__row" {
+                                td class="table__cell" {
+                                    a
+                                        href={"/admin/exercise/" (exercise_stats.exercise_id()) "/analytics/"}
+                                        class="exercise-link"
+                                    {
+                                        (exercise_stats.name())
+                                    }
+                                }
+                                td class="table__cell" { (exercise_stats.attempts()) }
+                                td class="table__cell" { (exercise_stats.users()) }
+                                td class="table__cell" { (format_rate(exercise_stats.solve_rate())) }
+                                td class="table__cell" {
+                                    (format_median(*exercise_stats.median_attempts_to_solve()))
+                                }
+                                td class="table__cell" {
+                                    (exercise_stats.median_seconds_to_solve().map_or_else(|| "–".to_string(), format_duration))
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Exercise Analytics",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+/// Analytics of a single exercise, along with the most common mistakes.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_analytics(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::ViewAnalytics)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let stats = match analytics::get_exercise_stats(&mut conn, Some(exercise_id))
+        .await
+        .wrap_err("Failed to get exercise stats")?
+        .pop()
+    {
+        Some(stats) => stats,
+        None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
+    };
+
+    let schema_id = *exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .ok_or_eyre("Exercise not found")?
+        .schema_id();
+
+    let dialect = *exercise::get_exercise_schema(&mut conn, schema_id)
+        .await
+        .wrap_err("Failed to query exercise schema")?
+        .ok_or_eyre("Exercise schema not found")?
+        .dialect();
+
+    let results = analytics::get_common_incorrect_results(&mut conn, exercise_id, COMMON_LIMIT)
+        .await
+        .wrap_err("Failed to get common incorrect results")?;
+
+    let queries = analytics::get_common_incorrect_queries(&mut conn, exercise_id, COMMON_LIMIT)
+        .await
+        .wrap_err("Failed to get common incorrect queries")?;
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                a class="button button--text" href="/admin/analytics/" {
+                    i data-lucide="chevron-left" class="button__icon" {}
+                    "Back to Analytics"
+                }
+                a class="button button--secondary" href={"/admin/exercise/" (exercise_id) "/"} {
+                    i data-lucide="edit" class="button__icon" {}
+                    "Edit Exercise"
+                }
+            }
+            h1 class="content__title" { "Analytics: " (stats.name()) }
+
+            (stats_cards(&stats))
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Most Common Incorrect Results" }
+                @if results.is_empty() {
+                    p class="panel__text" { "No incorrect submissions yet." }
+                } @else {
+                    ol class="mistakes" {
+                        @for result in &results {
+                            li class="mistakes__item" {
+                                div class="mistakes__meta" {
+                                    (result.count()) " submissions by " (result.users()) " users"
+                                }
+                                pre class="mistakes__result" { (result_preview(result.result())) }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Most Common Incorrect Queries" }
+                p class="panel__text" {
+                    "Queries are grouped by their fingerprints: literals are shown as "
+                    code { "?" }
+                    ", aliases are replaced with table names."
+                }
+                @if queries.is_empty() {
+                    p class="panel__text" { "No incorrect submissions yet." }
+                } @else {
+                    ol class="mistakes" {
+                        @for query in &queries {
+                            li class="mistakes__item" {
+                                div class="mistakes__meta" {
+                                    (query.count()) " submissions by " (query.users()) " users"
+                                }
+                                pre class="mistakes__query" {
+                                    code { (highlight_sql(query.fingerprint(), dialect)) }
+                                }
+                                details class="mistakes__example" {
+                                    summary { "Latest submission" }
+                                    pre class="mistakes__query" {
+                                        code { (highlight_sql(query.example(), dialect)) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Exercise Analytics",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
@@ -12,40 +12,80 @@ use time::OffsetDateTime;
 
 use crate::{
     db::exercise,
+    engine,
     error::Result,
     models::{
         exercise::{Exercise, ExerciseId, ExerciseSchemaId, ExerciseSchemaListItem, NewExercise},
-        user::User,
+        user::{Permission, User},
     },
     partials::{app_layout, page},
     state::AppState,
     static_files,
 };
 
+use super::exercise_translation::locale_tabs;
+
 #[derive(Debug, Deserialize)]
 pub struct NewExerciseQuery {
     schema_id: Option<ExerciseSchemaId>,
 }
 
+/// What the exercise form is being rendered for.
+#[derive(Clone, Copy)]
+enum ExerciseFormKind<'a> {
+    /// Blank form, optionally with a schema preselected.
+    New(Option<ExerciseSchemaId>),
+    /// Editing an existing exercise in place.
+    Edit(&'a Exercise),
+    /// New exercise, prefilled with an unpublished copy of an existing one.
+    Clone(&'a Exercise),
+}
+
 fn exercise_form(
-    exercise: Option<&Exercise>,
+    kind: ExerciseFormKind<'_>,
     schemas: &[ExerciseSchemaListItem],
-    schema_preselected: Option<ExerciseSchemaId>,
+    can_publish: bool,
 ) -> Markup {
-    let title = match exercise {
-        Some(exercise) => format!("Editing Exercise \"{}\"", exercise.name()),
-        None => "New Exercise".to_string(),
+    let (title, exercise, action) = match kind {
+        ExerciseFormKind::New(_) => ("New Exercise".to_string(), None, None),
+        ExerciseFormKind::Edit(exercise) => (
+            format!("Editing Exercise \"{}\"", exercise.name()),
+            Some(exercise),
+            None,
+        ),
+        ExerciseFormKind::Clone(exercise) => (
+            format!("Cloning Exercise \"{}\"", exercise.name()),
+            Some(exercise),
+            Some("/admin/exercise/new/"),
+        ),
     };
 
-    let selected_schema_id = exercise.map(|e| *e.schema_id()).or(schema_preselected);
+    let selected_schema_id = match kind {
+        ExerciseFormKind::New(schema_preselected) => schema_preselected,
+        ExerciseFormKind::Edit(exercise) | ExerciseFormKind::Clone(exercise) => {
+            Some(*exercise.schema_id())
+        }
+    };
+
+    let name = match kind {
+        ExerciseFormKind::New(_) => String::new(),
+        ExerciseFormKind::Edit(exercise) => exercise.name().to_owned(),
+        ExerciseFormKind::Clone(exercise) => format!("Copy of {}", exercise.name()),
+    };
 
-    let submit_text = if exercise.is_some() {
+    let editing = matches!(kind, ExerciseFormKind::Edit(_));
+
+    let submit_text = if editing {
         "Update Exercise"
     } else {
         "Create Exercise"
     };
 
+    // clones always start unpublished
+    let published = editing && exercise.is_some_and(|ex| ex.published_at().is_some());
+
     let published_at = exercise
+        .filter(|_| editing)
         // either the already existing published_at
         .and_then(|ex| *ex.published_at())
         // or the current time
@@ -54,12 +94,13 @@ fn exercise_form(
     html! {
         form
             #db
-            data-controller="sql-run schema-hidden db"
+            data-controller="sql-run schema-hidden db markdown-preview"
             data-action="schema-hidden:schema-updated->db#schemaUpdated"
             data-sql-run-editor-outlet="#editor"
             data-sql-run-db-outlet="#db"
             class="form"
             method="post"
+            action=[action]
         {
             h1 class="form__title" { (title) }
 
@@ -71,7 +112,8 @@ fn exercise_form(
                         name="schema_id"
                         required
                         data-schema-hidden-target="schemaSelector"
-                        data-action="schema-hidden#fetchSchema"
+                        data-markdown-preview-target="schemaSelector"
+                        data-action="schema-hidden#fetchSchema markdown-preview#update"
                     {
                         @for schema in schemas {
                             option
@@ -99,7 +141,7 @@ fn exercise_form(
                     name="name"
                     placeholder="Enter exercise name"
                     required
-                    value=(exercise.map(|ex| ex.name().to_owned()).unwrap_or_default())
+                    value=(name)
                 ;
             }
 
@@ -109,6 +151,8 @@ fn exercise_form(
                     class="form__textarea"
                     name="question"
                     placeholder="Enter exercise question"
+                    data-markdown-preview-target="source"
+                    data-action="markdown-preview#update"
                     required
                     {
                         (exercise.map(|ex| ex.question().to_owned()).unwrap_or_default())
@@ -116,14 +160,49 @@ fn exercise_form(
                 ;
             }
 
+            div class="form__group" {
+                label class="form__label" { "Question Preview" }
+                div
+                    class="markdown-preview"
+                    data-markdown-preview-target="preview" {}
+            }
+
+            div class="form__group" {
+                label class="form__label" { "Hint (optional)" }
+                textarea
+                    class="form__textarea"
+                    name="hint"
+                    placeholder="Enter a hint, shown on request"
+                    {
+                        (exercise.and_then(|ex| ex.hint().to_owned()).unwrap_or_default())
+                    }
+                ;
+            }
+
+            div class="form__group" {
+                label class="form__label" { "Editorial (optional)" }
+                textarea
+                    class="form__textarea"
+                    name="editorial"
+                    placeholder="Enter an explanation, shown after the exercise is solved"
+                    {
+                        (exercise.and_then(|ex| ex.editorial().to_owned()).unwrap_or_default())
+                    }
+                ;
+            }
+
             div class="form__group" {
                 label class="form__label" { "Published" }
                 input
                     type="checkbox"
                     name="published_at"
-                    checked[exercise.map(|ex| ex.published_at().is_some()).unwrap_or(false)]
+                    checked[published]
+                    disabled[!can_publish]
                     value=(published_at)
                 {}
+                @if !can_publish {
+                    p class="form__hint" { "Exercises are published by reviewers." }
+                }
             }
 
             div class="form__group"
@@ -177,13 +256,20 @@ fn exercise_form(
                 {
                     "Cancel"
                 }
-                @if let Some(ex) = exercise {
+                @if let ExerciseFormKind::Edit(ex) = kind {
                     a
                         class="button button--secondary"
                         href={"/admin/exercise/new/?schema_id=" (ex.schema_id())}
                     {
                         "Create new"
                     }
+                    a
+                        class="button button--secondary"
+                        href={"/admin/exercise/" (ex.id()) "/clone/"}
+                    {
+                        i data-lucide="copy" class="button__icon" {}
+                        "Clone"
+                    }
                 }
                 input
                     type="submit"
@@ -194,6 +280,7 @@ fn exercise_form(
 
         script type="module" src={"/static/" (static_files::schema_hidden_controller.name)} {}
         script type="module" src={"/static/" (static_files::editor_controller.name)} {}
+        script type="module" src={"/static/" (static_files::markdown_preview_controller.name)} {}
         script defer type="module" src={"/static/" (static_files::db_controller.name)} {}
         script defer type="module" src={"/static/" (static_files::sql_run_controller.name)} {}
     }
@@ -220,11 +307,30 @@ pub async fn exercise_edit(
         None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
     };
 
+    if !user.can_edit_exercise(exercise.author_id().as_deref()) {
+        return Ok((
+            http::StatusCode::FORBIDDEN,
+            "You can only edit your own exercises",
+        )
+            .into_response());
+    }
+
     let schemas = exercise::get_exercise_schemas(&mut conn)
         .await
         .wrap_err("Failed to query exercise schemas")?;
 
-    let form = exercise_form(Some(&exercise), &schemas, None);
+    let translations = exercise::get_exercise_translations(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise translations")?;
+
+    let config = state.config();
+    let locales = config.supported_locales();
+
+    let form = exercise_form(
+        ExerciseFormKind::Edit(&exercise),
+        &schemas,
+        user.can(Permission::PublishExercises),
+    );
 
     let inner = app_layout(
         html! {
@@ -234,6 +340,7 @@ pub async fn exercise_edit(
                     "Back to Exercises"
                 }
             }
+            (locale_tabs(exercise_id, &locales, &translations, locales[0]))
             (form)
         },
         "SQL Grimoire - Exercise Edit",
@@ -250,6 +357,8 @@ pub async fn exercise_new(
     Query(schema_preselected): Query<NewExerciseQuery>,
     Cached(user): Cached<User>,
 ) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
     let mut conn = state
         .db()
         .acquire()
@@ -260,7 +369,11 @@ pub async fn exercise_new(
         .await
         .wrap_err("Failed to query exercise schemas")?;
 
-    let form = exercise_form(None, &schemas, schema_preselected.schema_id);
+    let form = exercise_form(
+        ExerciseFormKind::New(schema_preselected.schema_id),
+        &schemas,
+        user.can(Permission::PublishExercises),
+    );
 
     let inner = app_layout(
         html! {
@@ -279,19 +392,101 @@ pub async fn exercise_new(
     Ok(page("SQL Grimoire - New Exercise", inner).into_response())
 }
 
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_clone(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let exercise = match exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+    {
+        Some(exercise) => exercise,
+        None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
+    };
+
+    let schemas = exercise::get_exercise_schemas(&mut conn)
+        .await
+        .wrap_err("Failed to query exercise schemas")?;
+
+    let form = exercise_form(
+        ExerciseFormKind::Clone(&exercise),
+        &schemas,
+        user.can(Permission::PublishExercises),
+    );
+
+    let inner = app_layout(
+        html! {
+            div class="content__header" {
+                a class="button button--text" href={"/admin/exercise/" (exercise.id()) "/"} {
+                    i data-lucide="chevron-left" class="button__icon" {}
+                    "Back to Exercise"
+                }
+            }
+            (form)
+        },
+        "SQL Grimoire - Clone Exercise",
+        user.auth_state(),
+    );
+
+    Ok(page("SQL Grimoire - Clone Exercise", inner).into_response())
+}
+
 #[debug_handler]
 #[tracing::instrument(skip_all)]
 pub async fn exercise_post(
     State(state): State<AppState>,
     id: Option<Path<ExerciseId>>,
-    Form(form): Form<NewExercise>,
+    Cached(user): Cached<User>,
+    Form(mut form): Form<NewExercise>,
 ) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
     let mut txn = state
         .db()
         .begin()
         .await
         .wrap_err("Failed to begin transaction")?;
 
+    let schema = match exercise::get_exercise_schema(&mut txn, *form.schema_id())
+        .await
+        .wrap_err("Failed to query exercise schema")?
+    {
+        Some(schema) => schema,
+        None => {
+            return Ok((http::StatusCode::NOT_FOUND, "Exercise schema not found").into_response());
+        }
+    };
+
+    // exercises graded on the server expect exactly what the server computes
+    match engine::run_query(
+        *schema.dialect(),
+        schema.schema().clone(),
+        form.expected_query().clone(),
+    )
+    .await
+    {
+        Some(Ok(expected_result)) => form.set_expected_result(&expected_result),
+        Some(Err(e)) => {
+            return Ok((
+                http::StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Expected query can't be run: {e}"),
+            )
+                .into_response());
+        }
+        None => {}
+    }
+
     let id = {
         if let Some(id) = id {
             let mut exercise = match exercise::get_exercise(&mut txn, id.0)
@@ -304,6 +499,18 @@ pub async fn exercise_post(
                 }
             };
 
+            if !user.can_edit_exercise(exercise.author_id().as_deref()) {
+                return Ok((
+                    http::StatusCode::FORBIDDEN,
+                    "You can only edit your own exercises",
+                )
+                    .into_response());
+            }
+
+            if !user.can(Permission::PublishExercises) {
+                form.set_published_at(*exercise.published_at());
+            }
+
             exercise.update(form);
 
             *exercise::update_exercise(&mut txn, exercise)
@@ -311,7 +518,11 @@ pub async fn exercise_post(
                 .wrap_err("Failed to update exercise")?
                 .id()
         } else {
-            *exercise::create_exercise(&mut txn, form)
+            if !user.can(Permission::PublishExercises) {
+                form.set_published_at(None);
+            }
+
+            *exercise::create_exercise(&mut txn, form, user.id())
                 .await
                 .wrap_err("Failed to create exercise")?
                 .id()
@@ -0,0 +1,132 @@
+use axum::{
+    debug_handler,
+    extract::{Json, State},
+    response::IntoResponse,
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+use sqlparser::keywords::ALL_KEYWORDS;
+
+use crate::{
+    db::exercise,
+    ddl::{self, SchemaInfo},
+    error::Result,
+    markdown,
+    models::{
+        exercise::{ExerciseSchema, ExerciseSchemaId},
+        user::{Permission, User},
+    },
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct QuestionPreviewRequest {
+    question: String,
+    schema_id: Option<ExerciseSchemaId>,
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_keyword(name: &str) -> bool {
+    ALL_KEYWORDS
+        .binary_search(&name.to_uppercase().as_str())
+        .is_ok()
+}
+
+/// Checks if `code` (contents of an inline code span) refers to a table or a column
+/// missing from the schema. Returns a warning if it does.
+///
+/// Only code spans which look like `name` or `table.column` are checked,
+/// anything else is assumed to be an SQL snippet.
+fn unknown_reference(schema: &SchemaInfo, code: &str) -> Option<String> {
+    let parts: Vec<&str> = code.trim().split('.').collect();
+
+    if !parts.iter().all(|part| is_identifier(part)) {
+        return None;
+    }
+
+    match parts[..] {
+        [name] => {
+            let known = schema.table(name).is_some() || schema.has_column(name) || is_keyword(name);
+
+            (!known).then(|| format!("`{name}` is neither a table nor a column in the schema"))
+        }
+        [table_name, column_name] => match schema.table(table_name) {
+            None => Some(format!("Table `{table_name}` doesn't exist in the schema")),
+            Some(table) if table.column(column_name).is_none() => Some(format!(
+                "Column `{column_name}` doesn't exist in table `{table_name}`"
+            )),
+            Some(_) => None,
+        },
+        _ => None,
+    }
+}
+
+fn schema_warnings(question: &str, schema: &ExerciseSchema) -> Vec<String> {
+    let schema = match ddl::parse(schema.schema(), *schema.dialect()) {
+        Ok(schema) => schema,
+        Err(e) => {
+            return vec![format!(
+                "Schema couldn't be parsed, names in the question weren't checked: {e}"
+            )];
+        }
+    };
+
+    markdown::inline_code(question)
+        .iter()
+        .filter_map(|code| unknown_reference(&schema, code))
+        .collect()
+}
+
+/// Renders a draft exercise question the same way students will see it,
+/// along with warnings about names missing from the selected schema.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_question_preview(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Json(request): Json<QuestionPreviewRequest>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
+    let warnings = match request.schema_id {
+        Some(schema_id) => {
+            let mut conn = state
+                .db()
+                .acquire()
+                .await
+                .wrap_err("Failed to acquire DB connection")?;
+
+            match exercise::get_exercise_schema(&mut conn, schema_id)
+                .await
+                .wrap_err("Failed to query exercise schema")?
+            {
+                Some(schema) => schema_warnings(&request.question, &schema),
+                None => vec!["Selected schema not found".to_string()],
+            }
+        }
+        None => vec![],
+    };
+
+    Ok(html! {
+        @if !warnings.is_empty() {
+            ul class="markdown-preview__warnings" {
+                @for warning in &warnings {
+                    li class="markdown-preview__warning" { (warning) }
+                }
+            }
+        }
+        div class="panel__text markdown" {
+            (markdown::render(&request.question))
+        }
+    })
+}
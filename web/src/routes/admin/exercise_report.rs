@@ -0,0 +1,178 @@
+use axum::{
+    debug_handler,
+    extract::{Form, Path, Query, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+
+use crate::{
+    db::report,
+    error::Result,
+    markdown::highlight_sql,
+    models::{
+        report::{ReportId, ReportStatus},
+        user::{Permission, User},
+    },
+    partials::{app_layout, format_datetime, page},
+    state::AppState,
+};
+
+const REPORTS_PER_PAGE: i64 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct ReportListQuery {
+    #[serde(default)]
+    status: ReportStatus,
+    page: Option<i64>,
+}
+
+/// Inbox of problems with exercises reported by students.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn report_list(
+    State(state): State<AppState>,
+    Query(query): Query<ReportListQuery>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TriageReports)?;
+
+    let current_page = query.page.unwrap_or(1).max(1);
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let total = report::count_reports(&mut conn, query.status)
+        .await
+        .wrap_err("Failed to count exercise reports")?;
+
+    let reports = report::get_reports(
+        &mut conn,
+        query.status,
+        REPORTS_PER_PAGE,
+        (current_page - 1) * REPORTS_PER_PAGE,
+    )
+    .await
+    .wrap_err("Failed to query exercise reports")?;
+
+    let pages = (total + REPORTS_PER_PAGE - 1) / REPORTS_PER_PAGE;
+    let page_href = |page: i64| format!("/admin/reports/?status={}&page={page}", query.status);
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "Reports" }
+            }
+
+            nav class="tabs" {
+                @for status in ReportStatus::ALL {
+                    a
+                        href={"/admin/reports/?status=" (status)}
+                        class={"tabs__link" @if status == query.status { " tabs__link--active" }}
+                    {
+                        (status.label())
+                    }
+                }
+            }
+
+            @if reports.is_empty() {
+                p class="attempts__empty" { "No reports here." }
+            } @else {
+                ol class="reports" {
+                    @for report in &reports {
+                        li class="report" {
+                            div class="report__meta" {
+                                a
+                                    href={"/admin/exercise/" (report.exercise_id()) "/"}
+                                    class="report__exercise"
+                                {
+                                    (report.exercise_name())
+                                }
+                                span class="report__author" { (report.user_name()) }
+                                time class="report__time" { (format_datetime(*report.created_at())) }
+
+                                form
+                                    class="role-form report__status"
+                                    method="post"
+                                    action={"/admin/reports/" (report.id()) "/status/"}
+                                {
+                                    select class="form__select role-form__select" name="status" {
+                                        @for status in ReportStatus::ALL {
+                                            option value=(status) selected[status == *report.status()] {
+                                                (status.label())
+                                            }
+                                        }
+                                    }
+                                    button type="submit" class="icon-button" title="Change status" {
+                                        i data-lucide="check" class="icon-button__icon" {}
+                                    }
+                                }
+                            }
+                            p class="report__message" { (report.message()) }
+                            @if !report.query().trim().is_empty() {
+                                pre class="attempts__query" {
+                                    code { (highlight_sql(report.query(), *report.dialect())) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            @if pages > 1 {
+                nav class="pagination" {
+                    @if current_page > 1 {
+                        a class="pagination__link" href=(page_href(current_page - 1)) { "Newer" }
+                    }
+                    span class="pagination__current" { "Page " (current_page) " of " (pages) }
+                    @if current_page < pages {
+                        a class="pagination__link" href=(page_href(current_page + 1)) { "Older" }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Reports",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportStatusForm {
+    status: ReportStatus,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn report_status_post(
+    State(state): State<AppState>,
+    Path(report_id): Path<ReportId>,
+    Cached(user): Cached<User>,
+    Form(form): Form<ReportStatusForm>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TriageReports)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if !report::set_report_status(&mut conn, report_id, form.status)
+        .await
+        .wrap_err("Failed to set exercise report status")?
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Report not found").into_response());
+    }
+
+    Ok(Redirect::to("/admin/reports/").into_response())
+}
@@ -7,41 +7,74 @@ use axum::{
 use axum_extra::extract::Cached;
 use eyre::WrapErr;
 use maud::html;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     db::exercise,
+    ddl, engine,
     error::{Result, SqlGrimoireError},
     models::{
-        exercise::{ExerciseSchema, ExerciseSchemaId},
-        user::User,
+        exercise::{ExerciseSchema, ExerciseSchemaId, SqlDialect},
+        user::{Permission, User},
     },
     partials::{app_layout, page},
     state::AppState,
     static_files,
 };
 
-fn exercise_schema_form(exercise_schema: Option<ExerciseSchema>) -> maud::Markup {
-    let name = exercise_schema
-        .as_ref()
-        .map(|schema| schema.name())
-        .cloned()
-        .unwrap_or_default();
-    let schema = exercise_schema
-        .as_ref()
-        .map(|schema| schema.schema())
-        .cloned()
-        .unwrap_or_default();
+/// What the schema form is being rendered for.
+enum ExerciseSchemaFormKind {
+    /// Blank form.
+    New,
+    /// Editing an existing schema in place.
+    Edit(ExerciseSchema),
+    /// New schema, prefilled with a copy of an existing one.
+    Clone {
+        source: ExerciseSchema,
+        /// Number of exercises which can be cloned along with the schema.
+        exercise_count: i64,
+    },
+}
+
+fn exercise_schema_form(kind: ExerciseSchemaFormKind) -> maud::Markup {
+    let (title, name, schema, dialect, action) = match &kind {
+        ExerciseSchemaFormKind::New => (
+            "New Schema".to_string(),
+            String::new(),
+            String::new(),
+            SqlDialect::default(),
+            None,
+        ),
+        ExerciseSchemaFormKind::Edit(schema) => (
+            "Edit Schema".to_string(),
+            schema.name().clone(),
+            schema.schema().clone(),
+            *schema.dialect(),
+            None,
+        ),
+        ExerciseSchemaFormKind::Clone { source, .. } => (
+            format!("Cloning Schema \"{}\"", source.name()),
+            format!("Copy of {}", source.name()),
+            source.schema().clone(),
+            *source.dialect(),
+            Some("/admin/exercise/schemas/new/"),
+        ),
+    };
+
+    let diagram = schema_diagram(&schema, dialect);
 
     html! {
         form
             #db
             data-controller="db mermaid-schema-vis"
             data-action="db:db-created->mermaid-schema-vis#drawSchema"
+            data-mermaid-schema-vis-diagram-value=[diagram]
+            data-db-dialect-value=(dialect)
             class="form"
             method="post"
+            action=[action]
         {
-            h1 class="form__title" { "Edit Schema" }
+            h1 class="form__title" { (title) }
             div class="form__group" {
                 label class="form__label" { "Name" }
                 input
@@ -52,6 +85,22 @@ fn exercise_schema_form(exercise_schema: Option<ExerciseSchema>) -> maud::Markup
                     required
                     placeholder="Enter schema name";
             }
+            div class="form__group" {
+                label class="form__label" { "Dialect" }
+                div class="select-wrapper" {
+                    select
+                        name="dialect"
+                        class="form__select"
+                        data-action="db#dialectChanged"
+                    {
+                        @for option in SqlDialect::ALL {
+                            option value=(option) selected[option == dialect] {
+                                (option.label())
+                            }
+                        }
+                    }
+                }
+            }
             div class="form__group" {
                 label class="form__label" { "SQL Schema" }
                 textarea
@@ -72,10 +121,41 @@ fn exercise_schema_form(exercise_schema: Option<ExerciseSchema>) -> maud::Markup
                     data-mermaid-schema-vis-target="schemaVis" {}
             }
 
+            @if let ExerciseSchemaFormKind::Clone { source, exercise_count } = &kind {
+                @if *exercise_count > 0 {
+                    div class="form__group" {
+                        label class="form__label" {
+                            input
+                                type="checkbox"
+                                name="clone_exercises_from"
+                                value=(source.id());
+                            " Also clone " (exercise_count) " exercise(s) onto the new schema"
+                        }
+                    }
+                }
+            }
+
             div class="form__actions" {
                 a href="/admin/exercise/schemas/" class="button button--secondary" {
                     "Cancel"
                 }
+                @if let ExerciseSchemaFormKind::Edit(schema) = &kind {
+                    a
+                        href={"/admin/exercise/schemas/" (schema.id()) "/clone/"}
+                        class="button button--secondary"
+                    {
+                        i data-lucide="copy" class="button__icon" {}
+                        "Clone"
+                    }
+                    a
+                        href={"/admin/exercise/schemas/" (schema.id()) "/diagram/"}
+                        download={(schema.name()) ".mmd"}
+                        class="button button--secondary"
+                    {
+                        i data-lucide="download" class="button__icon" {}
+                        "Diagram"
+                    }
+                }
                 button
                     data-action="db#resetDbRequest:prevent"
                     class="button button--secondary"
@@ -85,7 +165,7 @@ fn exercise_schema_form(exercise_schema: Option<ExerciseSchema>) -> maud::Markup
                 input
                     type="submit"
                     class="button button--primary"
-                    value=(if exercise_schema.is_some() {
+                    value=(if let ExerciseSchemaFormKind::Edit(_) = kind {
                         "Update Schema"
                     } else {
                         "Create Schema"
@@ -105,6 +185,8 @@ pub async fn exercise_schema_edit(
     Path(id): Path<ExerciseSchemaId>,
     Cached(user): Cached<User>,
 ) -> Result<impl IntoResponse> {
+    user.require(Permission::EditSchemas)?;
+
     let mut conn = state
         .db()
         .acquire()
@@ -122,7 +204,7 @@ pub async fn exercise_schema_edit(
         }
     };
 
-    let form = exercise_schema_form(Some(exercise_schema));
+    let form = exercise_schema_form(ExerciseSchemaFormKind::Edit(exercise_schema));
     let inner = app_layout(
         html! {
             div class="content__header" {
@@ -146,7 +228,9 @@ pub async fn exercise_schema_new(
     State(_state): State<AppState>,
     Cached(user): Cached<User>,
 ) -> Result<impl IntoResponse> {
-    let form = exercise_schema_form(None);
+    user.require(Permission::EditSchemas)?;
+
+    let form = exercise_schema_form(ExerciseSchemaFormKind::New);
     let inner = app_layout(
         html! {
             div class="content__header" {
@@ -164,10 +248,63 @@ pub async fn exercise_schema_new(
     Ok(page("SQL Grimoire - Exercise Schema New", inner).into_response())
 }
 
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_schema_clone(
+    State(state): State<AppState>,
+    Path(id): Path<ExerciseSchemaId>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::EditSchemas)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let source = match exercise::get_exercise_schema(&mut conn, id)
+        .await
+        .wrap_err("Failed to query exercise schema")?
+    {
+        Some(source) => source,
+        None => {
+            return Ok((http::StatusCode::NOT_FOUND, "Exercise schema not found").into_response());
+        }
+    };
+
+    let exercise_count = exercise::count_schema_exercises(&mut conn, id)
+        .await
+        .wrap_err("Failed to count schema exercises")?;
+
+    let form = exercise_schema_form(ExerciseSchemaFormKind::Clone {
+        source,
+        exercise_count,
+    });
+    let inner = app_layout(
+        html! {
+            div class="content__header" {
+                a href="/admin/exercise/schemas/" class="button button--text" {
+                    i data-lucide="chevron-left" class="button__icon" {}
+                    "Back to Schemas"
+                }
+            }
+            (form)
+        },
+        "SQL Grimoire - Exercise Schema Clone",
+        user.auth_state(),
+    );
+
+    Ok(page("SQL Grimoire - Exercise Schema Clone", inner).into_response())
+}
+
 #[derive(Deserialize)]
 pub struct ExerciseSchemaForm {
     name: String,
     schema: String,
+    dialect: SqlDialect,
+    /// When creating a schema, copy exercises of this schema onto the new one.
+    clone_exercises_from: Option<ExerciseSchemaId>,
 }
 
 #[debug_handler]
@@ -175,14 +312,40 @@ pub struct ExerciseSchemaForm {
 pub async fn exercise_schema_post(
     State(state): State<AppState>,
     id: Option<Path<ExerciseSchemaId>>,
+    Cached(user): Cached<User>,
     Form(form): Form<ExerciseSchemaForm>,
 ) -> Result<impl IntoResponse> {
+    user.require(Permission::EditSchemas)?;
+
+    if let Err(e) = engine::validate_schema(form.dialect, form.schema.clone()).await {
+        return Ok((http::StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response());
+    }
+
     let mut txn = state
         .db()
         .begin()
         .await
         .wrap_err("Failed to begin transaction")?;
 
+    if let Some(source_id) = form.clone_exercises_from {
+        let source = match exercise::get_exercise_schema(&mut txn, source_id)
+            .await
+            .wrap_err("Failed to query exercise schema")?
+        {
+            Some(source) => source,
+            None => return Err(SqlGrimoireError::not_found("Exercise schema not found")),
+        };
+
+        // engines format results differently, the expected results would never match
+        if *source.dialect() != form.dialect {
+            return Ok((
+                http::StatusCode::UNPROCESSABLE_ENTITY,
+                "Exercises can only be cloned onto a schema of the same dialect",
+            )
+                .into_response());
+        }
+    }
+
     let id = {
         if let Some(id) = id {
             let mut exercise_schema = match exercise::get_exercise_schema(&mut txn, id.0)
@@ -199,16 +362,51 @@ pub async fn exercise_schema_post(
 
             exercise_schema.set_name(form.name);
             exercise_schema.set_schema(form.schema);
+            exercise_schema.set_dialect(form.dialect);
 
             *exercise::update_exercise_schema(&mut txn, exercise_schema)
                 .await
                 .wrap_err("Failed to update exercise schema")?
                 .id()
         } else {
-            *exercise::create_exercise_schema(&mut txn, form.name, form.schema)
-                .await
-                .wrap_err("Failed to create exercise schema")?
-                .id()
+            let id = *exercise::create_exercise_schema(
+                &mut txn,
+                form.name,
+                form.schema.clone(),
+                form.dialect,
+            )
+            .await
+            .wrap_err("Failed to create exercise schema")?
+            .id();
+
+            if let Some(source_id) = form.clone_exercises_from {
+                let copies = exercise::clone_schema_exercises(&mut txn, source_id, id, user.id())
+                    .await
+                    .wrap_err("Failed to clone schema exercises")?;
+
+                // the schema may have been edited, exercises graded on the server expect
+                // what the server computes on the new one
+                for (exercise_id, expected_query) in copies {
+                    match engine::run_query(form.dialect, form.schema.clone(), expected_query).await
+                    {
+                        Some(Ok(expected_result)) => {
+                            exercise::set_expected_result(&mut txn, exercise_id, &expected_result)
+                                .await
+                                .wrap_err("Failed to update expected result")?;
+                        }
+                        Some(Err(e)) => {
+                            return Ok((
+                                http::StatusCode::UNPROCESSABLE_ENTITY,
+                                format!("Expected query of a cloned exercise can't be run: {e}"),
+                            )
+                                .into_response());
+                        }
+                        None => {}
+                    }
+                }
+            }
+
+            id
         }
     };
 
@@ -219,12 +417,31 @@ pub async fn exercise_schema_post(
     Ok(Redirect::to(&format!("/admin/exercise/schemas/{}/", id)).into_response())
 }
 
+/// Mermaid ER diagram of the schema, `None` when its DDL can't be parsed or has no tables.
+fn schema_diagram(schema: &str, dialect: SqlDialect) -> Option<String> {
+    ddl::parse(schema, dialect)
+        .ok()
+        .filter(|schema_info| !schema_info.tables().is_empty())
+        .map(|schema_info| schema_info.mermaid())
+}
+
+/// The schema as exported, along with its diagram.
+#[derive(Serialize)]
+struct ExerciseSchemaExport {
+    #[serde(flatten)]
+    schema: ExerciseSchema,
+    diagram: Option<String>,
+}
+
 #[debug_handler]
 #[tracing::instrument(skip_all)]
 pub async fn exercise_schema_json(
     State(state): State<AppState>,
     Path(id): Path<ExerciseSchemaId>,
+    Cached(user): Cached<User>,
 ) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
     let mut conn = state
         .db()
         .acquire()
@@ -240,5 +457,78 @@ pub async fn exercise_schema_json(
         None => return Err(SqlGrimoireError::not_found("Exercise schema not found")),
     };
 
-    Ok(Json(exercise_schema))
+    let diagram = schema_diagram(exercise_schema.schema(), *exercise_schema.dialect());
+
+    Ok(Json(ExerciseSchemaExport {
+        schema: exercise_schema,
+        diagram,
+    }))
+}
+
+/// Tables, columns and keys of the schema, introspected from its DDL.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_schema_info(
+    State(state): State<AppState>,
+    Path(id): Path<ExerciseSchemaId>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let exercise_schema = match exercise::get_exercise_schema(&mut conn, id)
+        .await
+        .wrap_err("Failed to query exercise schema")?
+    {
+        Some(exercise_schema) => exercise_schema,
+        None => return Err(SqlGrimoireError::not_found("Exercise schema not found")),
+    };
+
+    match ddl::parse(exercise_schema.schema(), *exercise_schema.dialect()) {
+        Ok(schema_info) => Ok(Json(schema_info).into_response()),
+        Err(e) => Ok((
+            http::StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Failed to parse schema: {e}"),
+        )
+            .into_response()),
+    }
+}
+
+/// Mermaid source of the ER diagram, for rendering the schema outside the app.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_schema_diagram(
+    State(state): State<AppState>,
+    Path(id): Path<ExerciseSchemaId>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::AuthorExercises)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let exercise_schema = match exercise::get_exercise_schema(&mut conn, id)
+        .await
+        .wrap_err("Failed to query exercise schema")?
+    {
+        Some(exercise_schema) => exercise_schema,
+        None => return Err(SqlGrimoireError::not_found("Exercise schema not found")),
+    };
+
+    match ddl::parse(exercise_schema.schema(), *exercise_schema.dialect()) {
+        Ok(schema_info) => Ok(schema_info.mermaid().into_response()),
+        Err(e) => Ok((
+            http::StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Failed to parse schema: {e}"),
+        )
+            .into_response()),
+    }
 }
@@ -6,7 +6,7 @@ use maud::html;
 use crate::{
     db::exercise,
     error::Result,
-    models::user::User,
+    models::user::{Permission, User},
     partials::{app_layout, page},
     state::AppState,
 };
@@ -17,6 +17,8 @@ pub async fn exercise_schema_list(
     State(state): State<AppState>,
     Cached(user): Cached<User>,
 ) -> Result<impl IntoResponse> {
+    user.require(Permission::EditSchemas)?;
+
     let mut txn = state
         .db()
         .begin()
@@ -41,6 +43,7 @@ pub async fn exercise_schema_list(
                     thead {
                         tr {
                             th class="table__header" { "Name" }
+                            th class="table__header" { "Dialect" }
                             th class="table__header table__header--actions" { "Actions" }
                         }
                     }
@@ -48,6 +51,7 @@ pub async fn exercise_schema_list(
                         @for schema in &schemas {
                             tr class="table__row" {
                                 td class="table__cell" { (schema.name()) }
+                                td class="table__cell" { (schema.dialect().label()) }
                                 td class="table__cell table__cell--actions" {
                                     button class="icon-button" {
                                         "View Exercises"
@@ -57,6 +61,11 @@ pub async fn exercise_schema_list(
                                             i data-lucide="edit" class="icon-button__icon" {}
                                         }
                                     }
+                                    a href=(format!("/admin/exercise/schemas/{}/clone/", schema.id())) {
+                                        button class="icon-button" title="Clone" {
+                                            i data-lucide="copy" class="icon-button__icon" {}
+                                        }
+                                    }
                                     button class="icon-button icon-button--danger" {
                                         i data-lucide="trash-2" class="icon-button__icon" {}
                                     }
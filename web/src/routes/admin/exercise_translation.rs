@@ -0,0 +1,264 @@
+use axum::{
+    debug_handler,
+    extract::{Form, Path, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::{Markup, html};
+
+use crate::{
+    db::exercise,
+    error::Result,
+    models::{
+        exercise::{ExerciseId, ExerciseTranslation, NewExerciseTranslation},
+        user::User,
+    },
+    partials::{app_layout, page},
+    state::AppState,
+    static_files,
+};
+
+/// Tabs switching between the exercise itself (in the default locale) and its translations.
+///
+/// Renders nothing if there are no locales to translate to.
+pub(super) fn locale_tabs(
+    exercise_id: ExerciseId,
+    locales: &[&str],
+    translations: &[ExerciseTranslation],
+    current: &str,
+) -> Markup {
+    html! {
+        @if locales.len() > 1 {
+            nav class="locale-tabs" {
+                @for (i, locale) in locales.iter().enumerate() {
+                    @let href = if i == 0 {
+                        format!("/admin/exercise/{exercise_id}/")
+                    } else {
+                        format!("/admin/exercise/{exercise_id}/translations/{locale}/")
+                    };
+                    @let translated = i == 0 || translations.iter().any(|t| t.locale() == locale);
+
+                    a
+                        href=(href)
+                        class={
+                            "locale-tabs__tab"
+                            @if *locale == current { " locale-tabs__tab--active" }
+                            @if !translated { " locale-tabs__tab--missing" }
+                        }
+                    {
+                        (locale.to_uppercase())
+                        @if i == 0 {
+                            span class="locale-tabs__note" { "default" }
+                        } @else if !translated {
+                            span class="locale-tabs__note" { "not translated" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks that `locale` is a supported one, other than the default.
+fn is_translatable(state: &AppState, locale: &str) -> bool {
+    state.config().supported_locales()[1..].contains(&locale)
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_translation_edit(
+    State(state): State<AppState>,
+    Path((exercise_id, locale)): Path<(ExerciseId, String)>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    if !is_translatable(&state, &locale) {
+        return Ok((http::StatusCode::NOT_FOUND, "Locale not found").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let exercise = match exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+    {
+        Some(exercise) => exercise,
+        None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
+    };
+
+    if !user.can_edit_exercise(exercise.author_id().as_deref()) {
+        return Ok((
+            http::StatusCode::FORBIDDEN,
+            "You can only edit your own exercises",
+        )
+            .into_response());
+    }
+
+    let translations = exercise::get_exercise_translations(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise translations")?;
+
+    let translation = translations.iter().find(|t| t.locale() == &locale);
+    let text = |field: fn(&ExerciseTranslation) -> &Option<String>| {
+        translation
+            .and_then(|t| field(t).as_deref())
+            .unwrap_or_default()
+    };
+
+    let config = state.config();
+    let locales = config.supported_locales();
+
+    let inner = app_layout(
+        html! {
+            div class="content__header" {
+                a class="button button--text" href="/" {
+                    i data-lucide="chevron-left" class="button__icon" {}
+                    "Back to Exercises"
+                }
+            }
+            (locale_tabs(exercise_id, &locales, &translations, &locale))
+
+            form
+                class="form"
+                method="post"
+                data-controller="markdown-preview"
+            {
+                h1 class="form__title" {
+                    "Translating Exercise \"" (exercise.name()) "\" to " (locale.to_uppercase())
+                }
+
+                p class="form__hint" {
+                    "Fields left empty fall back to the " (locales[0].to_uppercase()) " text, shown as placeholders."
+                }
+
+                div class="form__group" {
+                    label class="form__label" { "Exercise Name" }
+                    input
+                        type="text"
+                        class="form__input"
+                        name="name"
+                        placeholder=(exercise.name())
+                        value=(text(ExerciseTranslation::name))
+                    ;
+                }
+
+                div class="form__group" {
+                    label class="form__label" { "Question" }
+                    textarea
+                        class="form__textarea"
+                        name="question"
+                        placeholder=(exercise.question())
+                        data-markdown-preview-target="source"
+                        data-action="markdown-preview#update"
+                        {
+                            (text(ExerciseTranslation::question))
+                        }
+                    ;
+                }
+
+                div class="form__group" {
+                    label class="form__label" { "Question Preview" }
+                    div
+                        class="markdown-preview"
+                        data-markdown-preview-target="preview" {}
+                }
+
+                div class="form__group" {
+                    label class="form__label" { "Hint" }
+                    textarea
+                        class="form__textarea"
+                        name="hint"
+                        placeholder=(exercise.hint().as_deref().unwrap_or_default())
+                        {
+                            (text(ExerciseTranslation::hint))
+                        }
+                    ;
+                }
+
+                div class="form__group" {
+                    label class="form__label" { "Editorial" }
+                    textarea
+                        class="form__textarea"
+                        name="editorial"
+                        placeholder=(exercise.editorial().as_deref().unwrap_or_default())
+                        {
+                            (text(ExerciseTranslation::editorial))
+                        }
+                    ;
+                }
+
+                div class="form__actions" {
+                    a
+                        class="button button--secondary"
+                        href={"/admin/exercise/" (exercise_id) "/"}
+                    {
+                        "Cancel"
+                    }
+                    input
+                        type="submit"
+                        class="button button--primary"
+                        value="Save Translation";
+                }
+            }
+
+            script type="module" src={"/static/" (static_files::markdown_preview_controller.name)} {}
+        },
+        "SQL Grimoire - Exercise Translation",
+        user.auth_state(),
+    );
+
+    Ok(page("SQL Grimoire - Exercise Translation", inner).into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn exercise_translation_post(
+    State(state): State<AppState>,
+    Path((exercise_id, locale)): Path<(ExerciseId, String)>,
+    Cached(user): Cached<User>,
+    Form(form): Form<NewExerciseTranslation>,
+) -> Result<impl IntoResponse> {
+    if !is_translatable(&state, &locale) {
+        return Ok((http::StatusCode::NOT_FOUND, "Locale not found").into_response());
+    }
+
+    let mut txn = state
+        .db()
+        .begin()
+        .await
+        .wrap_err("Failed to begin transaction")?;
+
+    let exercise = match exercise::get_exercise(&mut txn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+    {
+        Some(exercise) => exercise,
+        None => return Ok(http::StatusCode::NOT_FOUND.into_response()),
+    };
+
+    if !user.can_edit_exercise(exercise.author_id().as_deref()) {
+        return Ok((
+            http::StatusCode::FORBIDDEN,
+            "You can only edit your own exercises",
+        )
+            .into_response());
+    }
+
+    exercise::save_exercise_translation(&mut txn, exercise_id, &locale, form)
+        .await
+        .wrap_err("Failed to save exercise translation")?;
+
+    txn.commit()
+        .await
+        .wrap_err("Failed to commit transaction")?;
+
+    Ok(Redirect::to(&format!(
+        "/admin/exercise/{exercise_id}/translations/{locale}/"
+    ))
+    .into_response())
+}
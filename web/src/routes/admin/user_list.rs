@@ -0,0 +1,471 @@
+use axum::{
+    debug_handler,
+    extract::{Form, Path, Query, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+
+use crate::{
+    db::{cohort, exercise_solution, user},
+    error::{Result, SqlGrimoireError},
+    markdown::highlight_sql,
+    models::user::{Permission, User, UserRole},
+    partials::{app_layout, format_datetime, page},
+    routes::account::{data_download, delete_confirmation, refresh_leaderboard_after_deletion},
+    state::AppState,
+};
+
+const USERS_PER_PAGE: i64 = 50;
+
+const SUBMISSIONS_PER_PAGE: i64 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct UserListQuery {
+    #[serde(default)]
+    q: String,
+    page: Option<i64>,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn user_list(
+    State(state): State<AppState>,
+    Query(query): Query<UserListQuery>,
+    Cached(current_user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    current_user.require(Permission::ManageUsers)?;
+
+    let search = query.q.trim();
+    let current_page = query.page.unwrap_or(1).max(1);
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let total = user::count_users(&mut conn, search)
+        .await
+        .wrap_err("Failed to count users")?;
+
+    let users = user::get_users(
+        &mut conn,
+        search,
+        USERS_PER_PAGE,
+        (current_page - 1) * USERS_PER_PAGE,
+    )
+    .await
+    .wrap_err("Failed to query users")?;
+
+    let pages = (total + USERS_PER_PAGE - 1) / USERS_PER_PAGE;
+    let page_href = |page: i64| {
+        format!(
+            "/admin/users/?q={}&page={page}",
+            url::form_urlencoded::byte_serialize(search.as_bytes()).collect::<String>()
+        )
+    };
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "Users" }
+                form class="search" method="get" action="/admin/users/" {
+                    input
+                        type="search"
+                        class="form__input search__input"
+                        name="q"
+                        placeholder="Search by name, email or ID"
+                        value=(search);
+                    button type="submit" class="button button--secondary" { "Search" }
+                }
+            }
+            div class="table-container" {
+                table class="table" {
+                    thead {
+                        tr {
+                            th class="table__header" { "User" }
+                            th class="table__header" { "Role" }
+                            th class="table__header" { "Submissions" }
+                            th class="table__header" { "Solved" }
+                            th class="table__header" { "Last Seen" }
+                            th class="table__header" { "Joined" }
+                            th class="table__header table__header--actions" { "Actions" }
+                        }
+                    }
+                    tbody {
+                        @for user in &users {
+                            tr class="table__row" {
+                                td class="table__cell" {
+                                    div class="user-cell" {
+                                        span class="user-cell__name" { (user.name()) }
+                                        @if let Some(email) = user.email() {
+                                            span class="user-cell__detail" { (email) }
+                                        }
+                                        @if user.display_name().is_some() {
+                                            code class="user-cell__detail" { (user.id()) }
+                                        }
+                                    }
+                                }
+                                td class="table__cell" {
+                                    // admins can't lock themselves out
+                                    @if user.id() == current_user.id() {
+                                        span class={"role role--" (user.role())} { (user.role().label()) }
+                                    } @else {
+                                        form
+                                            class="role-form"
+                                            method="post"
+                                            action={"/admin/users/" (user.id()) "/role/"}
+                                        {
+                                            select class="form__select role-form__select" name="role" {
+                                                @for role in UserRole::ALL {
+                                                    option value=(role) selected[role == user.role()] {
+                                                        (role.label())
+                                                    }
+                                                }
+                                                @if let UserRole::Unknown(role) = user.role() {
+                                                    option value=(role) selected disabled { (role) " (unknown)" }
+                                                }
+                                            }
+                                            button type="submit" class="icon-button" title="Change role" {
+                                                i data-lucide="check" class="icon-button__icon" {}
+                                            }
+                                        }
+                                    }
+                                }
+                                td class="table__cell" { (user.solutions()) }
+                                td class="table__cell" { (user.solved()) }
+                                td class="table__cell" {
+                                    @match user.last_seen_at() {
+                                        Some(last_seen_at) => (format_datetime(*last_seen_at)),
+                                        None => "Never",
+                                    }
+                                }
+                                td class="table__cell" { (format_datetime(*user.created_at())) }
+                                td class="table__cell table__cell--actions" {
+                                    a
+                                        href={"/admin/users/" (user.id()) "/submissions/"}
+                                        class="icon-button"
+                                        title="Submissions"
+                                    {
+                                        i data-lucide="list" class="icon-button__icon" {}
+                                    }
+                                    a
+                                        href={"/admin/users/" (user.id()) "/data/"}
+                                        class="icon-button"
+                                        title="Download data"
+                                        data-turbo="false"
+                                        download
+                                    {
+                                        i data-lucide="download" class="icon-button__icon" {}
+                                    }
+                                    @if user.id() != current_user.id() {
+                                        a
+                                            href={"/admin/users/" (user.id()) "/delete/"}
+                                            class="icon-button icon-button--danger"
+                                            title="Delete account"
+                                        {
+                                            i data-lucide="trash-2" class="icon-button__icon" {}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            @if pages > 1 {
+                nav class="pagination" {
+                    @if current_page > 1 {
+                        a class="pagination__link" href=(page_href(current_page - 1)) { "Previous" }
+                    }
+                    span class="pagination__current" { "Page " (current_page) " of " (pages) }
+                    @if current_page < pages {
+                        a class="pagination__link" href=(page_href(current_page + 1)) { "Next" }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Users",
+        app_layout(inner, "SQL Grimoire", current_user.auth_state()),
+    )
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserRoleForm {
+    role: String,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn user_role_post(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Cached(current_user): Cached<User>,
+    Form(form): Form<UserRoleForm>,
+) -> Result<impl IntoResponse> {
+    current_user.require(Permission::ManageUsers)?;
+
+    let role = UserRole::from(form.role);
+
+    if let UserRole::Unknown(role) = role {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            format!("Unknown role: {role}"),
+        )
+            .into_response());
+    }
+
+    if user_id == *current_user.id() {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            "You can't change your own role",
+        )
+            .into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if !user::set_user_role(&mut conn, &user_id, &role)
+        .await
+        .wrap_err("Failed to set user role")?
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "User not found").into_response());
+    }
+
+    Ok(Redirect::to("/admin/users/").into_response())
+}
+
+/// Everything stored about the user, for data-subject requests made to admins.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn user_data_export(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Cached(current_user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    current_user.require(Permission::ManageUsers)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    match user::get_user_data(&mut conn, &user_id)
+        .await
+        .wrap_err("Failed to get user data")?
+    {
+        Some(data) => Ok(data_download(&user_id, data)),
+        None => Ok((http::StatusCode::NOT_FOUND, "User not found").into_response()),
+    }
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn user_delete(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Cached(current_user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    current_user.require(Permission::ManageUsers)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let user = match user::get_user_by_id(&mut conn, &user_id)
+        .await
+        .wrap_err("Failed to query user")?
+    {
+        Some(user) => user,
+        None => return Ok((http::StatusCode::NOT_FOUND, "User not found").into_response()),
+    };
+
+    Ok(page(
+        "SQL Grimoire - Delete User",
+        app_layout(
+            delete_confirmation(
+                &format!("Delete the account of {}?", user.name()),
+                "/admin/users/",
+            ),
+            "SQL Grimoire",
+            current_user.auth_state(),
+        ),
+    )
+    .into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn user_delete_post(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Cached(current_user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    current_user.require(Permission::ManageUsers)?;
+
+    // admins delete their own account from the profile page, which also logs them out
+    if user_id == *current_user.id() {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            "You can't delete your own account here",
+        )
+            .into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if !user::delete_user(&mut conn, &user_id)
+        .await
+        .wrap_err("Failed to delete user")?
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "User not found").into_response());
+    }
+
+    refresh_leaderboard_after_deletion(&state).await;
+
+    Ok(Redirect::to("/admin/users/").into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserSubmissionsQuery {
+    page: Option<i64>,
+}
+
+/// Read-only list of all submissions of a user.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn user_submissions(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<UserSubmissionsQuery>,
+    Cached(current_user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    let current_page = query.page.unwrap_or(1).max(1);
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    // teachers can see submissions of their students
+    let allowed = current_user.can(Permission::ViewSubmissions)
+        || (current_user.can(Permission::TeachCohorts)
+            && cohort::is_teacher_of(&mut conn, current_user.id(), &user_id)
+                .await
+                .wrap_err("Failed to check cohort membership")?);
+
+    if !allowed {
+        return Err(SqlGrimoireError::forbidden("Not a student of yours"));
+    }
+
+    let user = match user::get_user_by_id(&mut conn, &user_id)
+        .await
+        .wrap_err("Failed to query user")?
+    {
+        Some(user) => user,
+        None => return Ok((http::StatusCode::NOT_FOUND, "User not found").into_response()),
+    };
+
+    let total = exercise_solution::count_user_submissions(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to count user submissions")?;
+
+    let submissions = exercise_solution::get_user_submissions(
+        &mut conn,
+        user.id(),
+        SUBMISSIONS_PER_PAGE,
+        (current_page - 1) * SUBMISSIONS_PER_PAGE,
+    )
+    .await
+    .wrap_err("Failed to query user submissions")?;
+
+    let pages = (total + SUBMISSIONS_PER_PAGE - 1) / SUBMISSIONS_PER_PAGE;
+    let page_href = |page: i64| format!("/admin/users/{user_id}/submissions/?page={page}");
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                @if current_user.can(Permission::ManageUsers) {
+                    a class="button button--text" href="/admin/users/" {
+                        i data-lucide="chevron-left" class="button__icon" {}
+                        "Back to Users"
+                    }
+                }
+            }
+            h1 class="content__title" { "Submissions of " (user.name()) }
+            p class="content__subtitle" {
+                @if let Some(email) = user.email() {
+                    (email) " · "
+                }
+                code { (user.id()) }
+            }
+
+            div class="panel panel--progress" {
+                @if submissions.is_empty() {
+                    p class="attempts__empty" { "No submissions yet" }
+                } @else {
+                    ol class="attempts" {
+                        @for submission in &submissions {
+                            li class="attempts__item" {
+                                div class="attempts__meta" {
+                                    a
+                                        href={"/exercise/" (submission.exercise_id()) "/"}
+                                        class="exercise-link"
+                                    {
+                                        (submission.exercise_name())
+                                    }
+                                    span class={"attempts__status attempts__status--" (submission.status())} {
+                                        @if submission.status() == "correct" { "Correct" } @else { "Incorrect" }
+                                    }
+                                    time class="attempts__time" { (format_datetime(*submission.created_at())) }
+                                }
+                                pre class="attempts__query" {
+                                    code { (highlight_sql(submission.query(), *submission.dialect())) }
+                                }
+                            }
+                        }
+                    }
+
+                    @if pages > 1 {
+                        nav class="pagination" {
+                            @if current_page > 1 {
+                                a class="pagination__link" href=(page_href(current_page - 1)) { "Newer" }
+                            }
+                            span class="pagination__current" { "Page " (current_page) " of " (pages) }
+                            @if current_page < pages {
+                                a class="pagination__link" href=(page_href(current_page + 1)) { "Older" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - User Submissions",
+        app_layout(inner, "SQL Grimoire", current_user.auth_state()),
+    )
+    .into_response())
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::{self, header},
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::{Cached, Form};
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+use sqlx::PgConnection;
+use time::{PrimitiveDateTime, macros::format_description};
+
+use crate::{
+    db::{assignment, cohort},
+    error::Result,
+    extractors::Locale,
+    models::{
+        assignment::{Assignment, AssignmentId, AssignmentStatus},
+        cohort::{Cohort, CohortId},
+        exercise::ExerciseId,
+        user::{Permission, User},
+    },
+    partials::{app_layout, format_datetime, page},
+    routes::cohort::can_view_cohort,
+    state::AppState,
+};
+
+/// Spreadsheets run cells starting with these as formulas.
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// Quotes a CSV cell, so it's shown as text rather than run as a formula when opened.
+fn spreadsheet_safe(value: &str) -> String {
+    if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{value}")
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Parses the value of a `datetime-local` input, treated as UTC.
+fn parse_datetime_local(value: &str) -> Option<PrimitiveDateTime> {
+    PrimitiveDateTime::parse(
+        value,
+        format_description!("[year]-[month]-[day]T[hour]:[minute]"),
+    )
+    .or_else(|_| {
+        PrimitiveDateTime::parse(
+            value,
+            format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+        )
+    })
+    .ok()
+}
+
+/// Loads the assignment, making sure it belongs to a cohort the user can see.
+async fn get_visible_assignment(
+    conn: &mut PgConnection,
+    user: &User,
+    cohort_id: CohortId,
+    assignment_id: AssignmentId,
+) -> Result<Option<(Cohort, Assignment)>> {
+    let cohort = match cohort::get_cohort(conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort")?
+    {
+        Some(cohort) if can_view_cohort(user, &cohort) => cohort,
+        _ => return Ok(None),
+    };
+
+    let assignment = assignment::get_assignment(conn, assignment_id)
+        .await
+        .wrap_err("Failed to query assignment")?
+        .filter(|assignment| *assignment.cohort_id() == cohort_id);
+
+    Ok(assignment.map(|assignment| (cohort, assignment)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewAssignmentForm {
+    name: String,
+    opens_at: String,
+    due_at: String,
+    #[serde(default)]
+    exercise_ids: Vec<ExerciseId>,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn assignment_create(
+    State(state): State<AppState>,
+    Path(cohort_id): Path<CohortId>,
+    Cached(user): Cached<User>,
+    Form(form): Form<NewAssignmentForm>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TeachCohorts)?;
+
+    let name = form.name.trim();
+
+    let (Some(opens_at), Some(due_at)) = (
+        parse_datetime_local(&form.opens_at),
+        parse_datetime_local(&form.due_at),
+    ) else {
+        return Ok((http::StatusCode::BAD_REQUEST, "Invalid date").into_response());
+    };
+
+    if name.is_empty() || form.exercise_ids.is_empty() {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            "Assignment needs a name and at least one exercise",
+        )
+            .into_response());
+    }
+
+    if opens_at >= due_at {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            "Due date must be after the opening date",
+        )
+            .into_response());
+    }
+
+    let mut txn = state
+        .db()
+        .begin()
+        .await
+        .wrap_err("Failed to start transaction")?;
+
+    match cohort::get_cohort(&mut txn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort")?
+    {
+        Some(cohort) if can_view_cohort(&user, &cohort) => {}
+        _ => return Ok((http::StatusCode::NOT_FOUND, "Cohort not found").into_response()),
+    }
+
+    let assignment = assignment::create_assignment(&mut txn, cohort_id, name, opens_at, due_at)
+        .await
+        .wrap_err("Failed to create assignment")?;
+
+    assignment::add_assignment_exercises(&mut txn, *assignment.id(), &form.exercise_ids)
+        .await
+        .wrap_err("Failed to add assignment exercises")?;
+
+    txn.commit()
+        .await
+        .wrap_err("Failed to commit transaction")?;
+
+    Ok(Redirect::to(&format!(
+        "/cohorts/{cohort_id}/assignments/{}/",
+        assignment.id()
+    ))
+    .into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn assignment_view(
+    State(state): State<AppState>,
+    Path((cohort_id, assignment_id)): Path<(CohortId, AssignmentId)>,
+    Cached(user): Cached<User>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TeachCohorts)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let Some((cohort, assignment)) =
+        get_visible_assignment(&mut conn, &user, cohort_id, assignment_id).await?
+    else {
+        return Ok((http::StatusCode::NOT_FOUND, "Assignment not found").into_response());
+    };
+
+    let exercises = assignment::get_assignment_exercises(&mut conn, assignment_id, &locale)
+        .await
+        .wrap_err("Failed to query assignment exercises")?;
+
+    let results = assignment::get_assignment_results(&mut conn, assignment_id)
+        .await
+        .wrap_err("Failed to query assignment results")?;
+
+    let cells: HashMap<(&str, ExerciseId), _> = results
+        .iter()
+        .map(|result| ((result.user_id().as_str(), *result.exercise_id()), result))
+        .collect();
+
+    let students = cohort::get_cohort_members(&mut conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort members")?;
+
+    let due_at = *assignment.due_at();
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                a class="button button--text" href={"/cohorts/" (cohort_id) "/"} {
+                    i data-lucide="chevron-left" class="button__icon" {}
+                    "Back to " (cohort.name())
+                }
+                a class="button button--secondary" href={"/cohorts/" (cohort_id) "/assignments/" (assignment_id) "/results.csv"} {
+                    i data-lucide="download" class="button__icon" {}
+                    "Export CSV"
+                }
+            }
+            h1 class="content__title" { (assignment.name()) }
+            p class="content__subtitle" {
+                "Open from " (format_datetime(*assignment.opens_at()))
+                " until " (format_datetime(due_at))
+            }
+
+            div class="panel panel--progress" {
+                @if students.is_empty() {
+                    p class="panel__text" { "No students have joined yet." }
+                } @else {
+                    div class="table-container" {
+                        table class="matrix" {
+                            thead {
+                                tr {
+                                    th class="matrix__header" { "Student" }
+                                    @for exercise in &exercises {
+                                        th class="matrix__header matrix__header--exercise" title=(exercise.name()) {
+                                            span { (exercise.name()) }
+                                        }
+                                    }
+                                }
+                            }
+                            tbody {
+                                @for student in &students {
+                                    @let submissions_href = format!("/admin/users/{}/submissions/", student.user_id());
+
+                                    tr {
+                                        th class="matrix__student" {
+                                            a href=(submissions_href) class="exercise-link" title=[student.email()] { (student.name()) }
+                                        }
+                                        @for exercise in &exercises {
+                                            @if let Some(result) = cells.get(&(student.user_id().as_str(), *exercise.exercise_id())) {
+                                                @match result.status(due_at) {
+                                                    AssignmentStatus::OnTime => {
+                                                        td class="matrix__cell matrix__cell--solved" {
+                                                            a href=(submissions_href) { "✓ " (result.attempts()) }
+                                                        }
+                                                    },
+                                                    AssignmentStatus::Late => {
+                                                        td
+                                                            class="matrix__cell matrix__cell--late"
+                                                            title={"Solved late: " (result.solved_at().map(format_datetime).unwrap_or_default())}
+                                                        {
+                                                            a href=(submissions_href) { "late " (result.attempts()) }
+                                                        }
+                                                    },
+                                                    AssignmentStatus::Unsolved => {
+                                                        td class="matrix__cell matrix__cell--attempted" {
+                                                            a href=(submissions_href) { "✗ " (result.attempts()) }
+                                                        }
+                                                    },
+                                                    AssignmentStatus::Missing => {
+                                                        td class="matrix__cell" { "–" }
+                                                    },
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        format!("SQL Grimoire - {}", assignment.name()),
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+/// CSV with a row per student and exercise, whether it was solved on time.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn assignment_export(
+    State(state): State<AppState>,
+    Path((cohort_id, assignment_id)): Path<(CohortId, AssignmentId)>,
+    Cached(user): Cached<User>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TeachCohorts)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let Some((_, assignment)) =
+        get_visible_assignment(&mut conn, &user, cohort_id, assignment_id).await?
+    else {
+        return Ok((http::StatusCode::NOT_FOUND, "Assignment not found").into_response());
+    };
+
+    let exercises: HashMap<_, _> =
+        assignment::get_assignment_exercises(&mut conn, assignment_id, &locale)
+            .await
+            .wrap_err("Failed to query assignment exercises")?
+            .into_iter()
+            .map(|exercise| (*exercise.exercise_id(), exercise))
+            .collect();
+
+    let students: HashMap<_, _> = cohort::get_cohort_members(&mut conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort members")?
+        .into_iter()
+        .map(|student| (student.user_id().clone(), student))
+        .collect();
+
+    let results = assignment::get_assignment_results(&mut conn, assignment_id)
+        .await
+        .wrap_err("Failed to query assignment results")?;
+
+    let mut csv = csv::Writer::from_writer(Vec::new());
+
+    csv.write_record([
+        "student",
+        "name",
+        "email",
+        "exercise",
+        "on_time",
+        "status",
+        "solved_at",
+        "attempts",
+    ])
+    .wrap_err("Failed to write CSV header")?;
+
+    for result in &results {
+        let status = result.status(*assignment.due_at());
+
+        let student = students.get(result.user_id());
+
+        csv.write_record(
+            [
+                result.user_id().as_str(),
+                student.map(|student| student.name()).unwrap_or_default(),
+                student
+                    .and_then(|student| student.email().as_deref())
+                    .unwrap_or_default(),
+                exercises
+                    .get(result.exercise_id())
+                    .map(|exercise| exercise.name().as_str())
+                    .unwrap_or_default(),
+                if status == AssignmentStatus::OnTime {
+                    "true"
+                } else {
+                    "false"
+                },
+                status.label(),
+                &result.solved_at().map(format_datetime).unwrap_or_default(),
+                &result.attempts().to_string(),
+            ]
+            .map(spreadsheet_safe),
+        )
+        .wrap_err("Failed to write CSV row")?;
+    }
+
+    let body = csv.into_inner().wrap_err("Failed to finish CSV")?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_owned()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"assignment-{assignment_id}.csv\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -0,0 +1,186 @@
+use axum::{
+    debug_handler,
+    extract::{Form, Path, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use eyre::WrapErr;
+use maud::{Markup, html};
+use serde::Deserialize;
+
+use crate::{
+    db::{bookmark, exercise},
+    error::Result,
+    markdown,
+    models::{empty_as_none, exercise::ExerciseId, user::UserClaims},
+    state::AppState,
+};
+
+pub const MAX_NOTE_LENGTH: usize = 10_000;
+
+/// Star toggling the bookmark in place, on the exercise list and the exercise page.
+pub fn bookmark_toggle(exercise_id: ExerciseId, bookmarked: bool) -> Markup {
+    html! {
+        turbo-frame id={"bookmark-" (exercise_id)} {
+            form class="bookmark" method="post" action={"/exercise/" (exercise_id) "/bookmark/"} {
+                input type="hidden" name="bookmarked" value=(!bookmarked);
+                button
+                    type="submit"
+                    class="icon-button"
+                    title=(if bookmarked { "Remove bookmark" } else { "Bookmark" })
+                {
+                    i
+                        data-lucide="star"
+                        class={"icon-button__icon" @if bookmarked { " bookmark__icon--active" }}
+                    {}
+                }
+            }
+        }
+    }
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn bookmark(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let bookmarked = bookmark::is_bookmarked(&mut conn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to query exercise bookmark")?;
+
+    Ok(bookmark_toggle(exercise_id, bookmarked))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookmarkForm {
+    bookmarked: bool,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn bookmark_post(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+    Form(form): Form<BookmarkForm>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .is_none()
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response());
+    }
+
+    bookmark::set_bookmark(&mut conn, user.sub(), exercise_id, form.bookmarked)
+        .await
+        .wrap_err("Failed to save exercise bookmark")?;
+
+    Ok(Redirect::to(&format!("/exercise/{exercise_id}/bookmark/")).into_response())
+}
+
+/// Private notes of the user, rendered into the `notes` frame of the exercise page.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn notes(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let note = bookmark::get_note(&mut conn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to query exercise note")?;
+
+    Ok(html! {
+        turbo-frame #notes {
+            h3 class="panel__title" { "My Notes" }
+
+            @if let Some(note) = &note {
+                div class="notes__text markdown" { (markdown::render(note)) }
+            }
+
+            details class="notes__editor" open[note.is_none()] {
+                summary class="notes__summary" {
+                    @if note.is_some() { "Edit notes" } @else { "Add notes" }
+                }
+                form class="comment-form" method="post" action={"/exercise/" (exercise_id) "/notes/"} {
+                    textarea
+                        class="form__textarea"
+                        name="body"
+                        rows="4"
+                        maxlength=(MAX_NOTE_LENGTH)
+                        placeholder="Only you can see these. Markdown is supported."
+                    {
+                        (note.as_deref().unwrap_or(""))
+                    }
+                    button type="submit" class="button button--secondary" { "Save Notes" }
+                }
+            }
+        }
+    }
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NoteForm {
+    /// `None` removes the notes.
+    #[serde(default, deserialize_with = "empty_as_none")]
+    body: Option<String>,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn notes_post(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+    Form(form): Form<NoteForm>,
+) -> Result<impl IntoResponse> {
+    if form
+        .body
+        .as_ref()
+        .is_some_and(|body| body.chars().count() > MAX_NOTE_LENGTH)
+    {
+        return Ok((http::StatusCode::BAD_REQUEST, "Notes are too long").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .is_none()
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response());
+    }
+
+    bookmark::save_note(&mut conn, user.sub(), exercise_id, form.body.as_deref())
+        .await
+        .wrap_err("Failed to save exercise note")?;
+
+    Ok(Redirect::to(&format!("/exercise/{exercise_id}/notes/")).into_response())
+}
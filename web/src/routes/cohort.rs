@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+
+use axum::{
+    debug_handler,
+    extract::{Form, Path, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+
+use crate::{
+    db::{assignment, cohort, exercise},
+    error::Result,
+    extractors::Locale,
+    models::{
+        cohort::{Cohort, CohortId},
+        exercise::ExerciseId,
+        user::{Permission, User},
+    },
+    partials::{app_layout, format_datetime, page},
+    state::AppState,
+};
+
+/// Teachers only see their own cohorts, admins see all of them.
+pub(crate) fn can_view_cohort(user: &User, cohort: &Cohort) -> bool {
+    user.can(Permission::ManageUsers)
+        || (user.can(Permission::TeachCohorts) && cohort.teacher_id() == user.id())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn cohort_list(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TeachCohorts)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let teacher_id = (!user.can(Permission::ManageUsers)).then(|| user.id().as_str());
+
+    let cohorts = cohort::get_cohorts(&mut conn, teacher_id)
+        .await
+        .wrap_err("Failed to query cohorts")?;
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "Cohorts" }
+                form class="search" method="post" action="/cohorts/" {
+                    input
+                        type="text"
+                        class="form__input search__input"
+                        name="name"
+                        placeholder="Cohort name"
+                        required;
+                    button type="submit" class="button button--primary" {
+                        i data-lucide="plus" class="button__icon" {}
+                        "New Cohort"
+                    }
+                }
+            }
+            div class="table-container" {
+                table class="table" {
+                    thead {
+                        tr {
+                            th class="table__header" { "Name" }
+                            th class="table__header" { "Students" }
+                            th class="table__header" { "Created" }
+                        }
+                    }
+                    tbody {
+                        @for cohort in &cohorts {
+                            tr class="table__row" {
+                                td class="table__cell" {
+                                    a href={"/cohorts/" (cohort.id()) "/"} class="exercise-link" {
+                                        (cohort.name())
+                                    }
+                                }
+                                td class="table__cell" { (cohort.members()) }
+                                td class="table__cell" { (format_datetime(*cohort.created_at())) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Cohorts",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewCohortForm {
+    name: String,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn cohort_create(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Form(form): Form<NewCohortForm>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TeachCohorts)?;
+
+    let name = form.name.trim();
+
+    if name.is_empty() {
+        return Ok((http::StatusCode::BAD_REQUEST, "Cohort name is required").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let cohort = cohort::create_cohort(&mut conn, name, user.id())
+        .await
+        .wrap_err("Failed to create cohort")?;
+
+    Ok(Redirect::to(&format!("/cohorts/{}/", cohort.id())).into_response())
+}
+
+/// Teacher dashboard: progress of every student on every published exercise.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn cohort_view(
+    State(state): State<AppState>,
+    Path(cohort_id): Path<CohortId>,
+    Cached(user): Cached<User>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::TeachCohorts)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let cohort = match cohort::get_cohort(&mut conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort")?
+    {
+        Some(cohort) if can_view_cohort(&user, &cohort) => cohort,
+        _ => return Ok((http::StatusCode::NOT_FOUND, "Cohort not found").into_response()),
+    };
+
+    let members = cohort::get_cohort_members(&mut conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort members")?;
+
+    let progress = cohort::get_cohort_progress(&mut conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort progress")?;
+
+    let exercises: Vec<_> = exercise::get_exercise_list(&mut conn, user.id(), &locale)
+        .await
+        .wrap_err("Failed to get exercise list")?
+        .into_iter()
+        .filter(|exercise| *exercise.published())
+        .collect();
+
+    let assignments = assignment::get_cohort_assignments(&mut conn, cohort_id)
+        .await
+        .wrap_err("Failed to query cohort assignments")?;
+
+    let cells: HashMap<(&str, ExerciseId), _> = progress
+        .iter()
+        .map(|cell| ((cell.user_id().as_str(), *cell.exercise_id()), cell))
+        .collect();
+
+    let invite_href = format!("/cohorts/join/{}/", cohort.invite_code());
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                a class="button button--text" href="/cohorts/" {
+                    i data-lucide="chevron-left" class="button__icon" {}
+                    "Back to Cohorts"
+                }
+            }
+            h1 class="content__title" { (cohort.name()) }
+
+            p class="content__subtitle" {
+                "Students join through the invite link: "
+                a href=(invite_href) class="exercise-link" { (invite_href) }
+            }
+
+            div class="panel panel--progress" {
+                @if members.is_empty() {
+                    p class="panel__text" { "No students have joined yet." }
+                } @else {
+                    div class="table-container" {
+                        table class="matrix" {
+                            thead {
+                                tr {
+                                    th class="matrix__header" { "Student" }
+                                    @for exercise in &exercises {
+                                        th class="matrix__header matrix__header--exercise" title=(exercise.name()) {
+                                            span { (exercise.name()) }
+                                        }
+                                    }
+                                }
+                            }
+                            tbody {
+                                @for member in &members {
+                                    @let submissions_href = format!("/admin/users/{}/submissions/", member.user_id());
+
+                                    tr {
+                                        th class="matrix__student" {
+                                            a href=(submissions_href) class="exercise-link" title=[member.email()] { (member.name()) }
+                                        }
+                                        @for exercise in &exercises {
+                                            @match cells.get(&(member.user_id().as_str(), *exercise.id())) {
+                                                Some(cell) => {
+                                                    td
+                                                        class={
+                                                            "matrix__cell "
+                                                            @if *cell.solved() { "matrix__cell--solved" } @else { "matrix__cell--attempted" }
+                                                        }
+                                                        title={"Last query:\n" (cell.last_query())}
+                                                    {
+                                                        a href=(submissions_href) {
+                                                            @if *cell.solved() { "✓ " } @else { "✗ " }
+                                                            (cell.attempts())
+                                                        }
+                                                    }
+                                                },
+                                                None => {
+                                                    td class="matrix__cell" { "–" }
+                                                },
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div class="panel panel--progress" {
+                h2 class="panel__title" { "Assignments" }
+                @if !assignments.is_empty() {
+                    div class="table-container" {
+                        table class="table" {
+                            thead {
+                                tr {
+                                    th class="table__header" { "Name" }
+                                    th class="table__header" { "Opens" }
+                                    th class="table__header" { "Due" }
+                                    th class="table__header" { "Exercises" }
+                                }
+                            }
+                            tbody {
+                                @for assignment in &assignments {
+                                    tr class="table__row" {
+                                        td class="table__cell" {
+                                            a href={"/cohorts/" (cohort_id) "/assignments/" (assignment.id()) "/"} class="exercise-link" {
+                                                (assignment.name())
+                                            }
+                                        }
+                                        td class="table__cell" { (format_datetime(*assignment.opens_at())) }
+                                        td class="table__cell" { (format_datetime(*assignment.due_at())) }
+                                        td class="table__cell" { (assignment.exercises()) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                details class="assignment-form" {
+                    summary class="button button--secondary" {
+                        i data-lucide="plus" class="button__icon" {}
+                        "New Assignment"
+                    }
+                    form method="post" action={"/cohorts/" (cohort_id) "/assignments/"} {
+                        div class="form__group" {
+                            label class="form__label" for="name" { "Name" }
+                            input type="text" class="form__input" id="name" name="name" required;
+                        }
+                        div class="form__group assignment-form__dates" {
+                            div {
+                                label class="form__label" for="opens_at" { "Opens at (UTC)" }
+                                input type="datetime-local" class="form__input" id="opens_at" name="opens_at" required;
+                            }
+                            div {
+                                label class="form__label" for="due_at" { "Due at (UTC)" }
+                                input type="datetime-local" class="form__input" id="due_at" name="due_at" required;
+                            }
+                        }
+                        div class="form__group" {
+                            span class="form__label" { "Exercises" }
+                            div class="assignment-form__exercises" {
+                                @for exercise in &exercises {
+                                    label class="assignment-form__exercise" {
+                                        input type="checkbox" name="exercise_ids" value=(exercise.id());
+                                        (exercise.name())
+                                    }
+                                }
+                            }
+                        }
+                        div class="form__actions" {
+                            input type="submit" class="button button--primary" value="Create Assignment";
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        format!("SQL Grimoire - {}", cohort.name()),
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn cohort_join(
+    State(state): State<AppState>,
+    Path(invite_code): Path<String>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let cohort = match cohort::get_cohort_by_invite_code(&mut conn, &invite_code)
+        .await
+        .wrap_err("Failed to query cohort")?
+    {
+        Some(cohort) => cohort,
+        None => {
+            return Ok((http::StatusCode::NOT_FOUND, "Invite link is not valid").into_response());
+        }
+    };
+
+    let inner = html! {
+        div class="content" {
+            form class="form" method="post" {
+                h1 class="form__title" { "Join \"" (cohort.name()) "\"" }
+                p class="form__hint" {
+                    "The teacher of the cohort will see your progress and submissions."
+                }
+                div class="form__actions" {
+                    a class="button button--secondary" href="/" { "Cancel" }
+                    input type="submit" class="button button--primary" value="Join Cohort";
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Join Cohort",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn cohort_join_post(
+    State(state): State<AppState>,
+    Path(invite_code): Path<String>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let cohort = match cohort::get_cohort_by_invite_code(&mut conn, &invite_code)
+        .await
+        .wrap_err("Failed to query cohort")?
+    {
+        Some(cohort) => cohort,
+        None => {
+            return Ok((http::StatusCode::NOT_FOUND, "Invite link is not valid").into_response());
+        }
+    };
+
+    cohort::join_cohort(&mut conn, *cohort.id(), user.id())
+        .await
+        .wrap_err("Failed to join cohort")?;
+
+    Ok(Redirect::to("/").into_response())
+}
@@ -0,0 +1,276 @@
+use axum::{
+    debug_handler,
+    extract::{Form, Path, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+
+use crate::{
+    db::{comment, exercise},
+    error::Result,
+    markdown,
+    models::{
+        comment::CommentId,
+        exercise::ExerciseId,
+        user::{Permission, User},
+    },
+    partials::format_datetime,
+    state::AppState,
+};
+
+pub const MAX_COMMENT_LENGTH: usize = 4000;
+
+fn comments_href(exercise_id: ExerciseId) -> String {
+    format!("/exercise/{exercise_id}/comments/")
+}
+
+/// Discussion thread of the exercise, rendered into the `comments` frame of the exercise page.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn comments(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .is_none()
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response());
+    }
+
+    let moderator = user.can(Permission::ModerateComments);
+
+    let comments = comment::get_exercise_comments(&mut conn, exercise_id, moderator)
+        .await
+        .wrap_err("Failed to query exercise comments")?;
+
+    let moderate_href =
+        |comment_id: CommentId| format!("/exercise/{exercise_id}/comments/{comment_id}/moderate/");
+
+    Ok(html! {
+        turbo-frame
+            #comments
+            data-controller="spoiler"
+            data-action="click->spoiler#reveal"
+        {
+            h3 class="panel__title" { "Discussion" }
+
+            @if comments.is_empty() {
+                p class="attempts__empty" { "No comments yet. Ask a question or share a tip!" }
+            } @else {
+                ol class="comments" {
+                    @for comment in &comments {
+                        @let body = markdown::render_comment(comment.body());
+
+                        li class={
+                            "comment"
+                            @if *comment.pinned() { " comment--pinned" }
+                            @if *comment.hidden() { " comment--hidden" }
+                        } {
+                            div class="comment__meta" {
+                                @match comment.author_name() {
+                                    Some(name) => span class="comment__author" { (name) },
+                                    None => span class="comment__author comment__author--anonymous" { "Anonymous" },
+                                }
+                                @if *comment.author_solved() {
+                                    span class="comment__badge comment__badge--solved" { "Solved" }
+                                }
+                                @if *comment.pinned() {
+                                    span class="comment__badge" {
+                                        i data-lucide="pin" class="comment__badge-icon" {}
+                                        "Pinned"
+                                    }
+                                }
+                                @if *comment.hidden() {
+                                    span class="comment__badge comment__badge--hidden" { "Hidden" }
+                                }
+                                time class="comment__time" { (format_datetime(*comment.created_at())) }
+
+                                @if moderator {
+                                    form
+                                        class="comment__actions"
+                                        method="post"
+                                        action=(moderate_href(*comment.id()))
+                                    {
+                                        @if *comment.pinned() {
+                                            button type="submit" name="action" value="unpin" class="button button--text" title="Unpin" {
+                                                i data-lucide="pin-off" class="button__icon" {}
+                                            }
+                                        } @else {
+                                            button type="submit" name="action" value="pin" class="button button--text" title="Pin" {
+                                                i data-lucide="pin" class="button__icon" {}
+                                            }
+                                        }
+                                        @if *comment.hidden() {
+                                            button type="submit" name="action" value="unhide" class="button button--text" title="Unhide" {
+                                                i data-lucide="eye" class="button__icon" {}
+                                            }
+                                        } @else {
+                                            button type="submit" name="action" value="hide" class="button button--text" title="Hide" {
+                                                i data-lucide="eye-off" class="button__icon" {}
+                                            }
+                                        }
+                                        button
+                                            type="submit"
+                                            name="action"
+                                            value="delete"
+                                            class="button button--text"
+                                            title="Delete"
+                                            data-turbo-confirm="Delete this comment?"
+                                        {
+                                            i data-lucide="trash-2" class="button__icon" {}
+                                        }
+                                    }
+                                }
+                            }
+
+                            // comments of those still working on the exercise often carry their attempts
+                            @if *comment.author_solved() || comment.user_id() == user.id() {
+                                div class="comment__body markdown" { (body) }
+                            } @else {
+                                details class="comment__collapsed" {
+                                    summary class="comment__summary" {
+                                        "The author hasn't solved this exercise yet, click to show"
+                                    }
+                                    div class="comment__body markdown" { (body) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            form class="comment-form" method="post" action=(comments_href(exercise_id)) {
+                textarea
+                    class="form__textarea comment-form__input"
+                    name="body"
+                    rows="3"
+                    required
+                    maxlength=(MAX_COMMENT_LENGTH)
+                    placeholder="Ask a question or share a tip. Markdown is supported, wrap ||spoilers|| in double bars."
+                {}
+                button type="submit" class="button button--primary" {
+                    i data-lucide="send" class="button__icon" {}
+                    "Post Comment"
+                }
+            }
+        }
+    }
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentForm {
+    body: String,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn comment_create(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    Cached(user): Cached<User>,
+    Form(form): Form<CommentForm>,
+) -> Result<impl IntoResponse> {
+    let body = form.body.trim();
+
+    if body.is_empty() {
+        return Ok((http::StatusCode::BAD_REQUEST, "Comment is empty").into_response());
+    }
+
+    if body.chars().count() > MAX_COMMENT_LENGTH {
+        return Ok((http::StatusCode::BAD_REQUEST, "Comment is too long").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .is_none()
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response());
+    }
+
+    comment::create_comment(&mut conn, exercise_id, user.id(), body)
+        .await
+        .wrap_err("Failed to create comment")?;
+
+    Ok(Redirect::to(&comments_href(exercise_id)).into_response())
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    Pin,
+    Unpin,
+    Hide,
+    Unhide,
+    Delete,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationForm {
+    action: ModerationAction,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn comment_moderate(
+    State(state): State<AppState>,
+    Path((exercise_id, comment_id)): Path<(ExerciseId, CommentId)>,
+    Cached(user): Cached<User>,
+    Form(form): Form<ModerationForm>,
+) -> Result<impl IntoResponse> {
+    user.require(Permission::ModerateComments)?;
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let found = match form.action {
+        ModerationAction::Pin | ModerationAction::Unpin => comment::set_comment_pinned(
+            &mut conn,
+            exercise_id,
+            comment_id,
+            matches!(form.action, ModerationAction::Pin),
+        )
+        .await
+        .wrap_err("Failed to pin comment")?,
+        ModerationAction::Hide | ModerationAction::Unhide => comment::set_comment_hidden(
+            &mut conn,
+            exercise_id,
+            comment_id,
+            matches!(form.action, ModerationAction::Hide),
+        )
+        .await
+        .wrap_err("Failed to hide comment")?,
+        ModerationAction::Delete => comment::delete_comment(&mut conn, exercise_id, comment_id)
+            .await
+            .wrap_err("Failed to delete comment")?,
+    };
+
+    if !found {
+        return Ok((http::StatusCode::NOT_FOUND, "Comment not found").into_response());
+    }
+
+    Ok(Redirect::to(&comments_href(exercise_id)).into_response())
+}
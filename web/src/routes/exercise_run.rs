@@ -1,33 +1,69 @@
 use axum::{
     debug_handler,
-    extract::{Json, Path, State},
+    extract::{Form, Json, Path, Query, State},
     http,
-    response::IntoResponse,
+    response::{IntoResponse, Redirect},
 };
 use axum_extra::extract::Cached;
 use eyre::{OptionExt, WrapErr};
-use maud::{PreEscaped, html};
+use maud::{Markup, html};
 use serde::Deserialize;
+use sqlx::postgres::PgConnection;
+use time::{OffsetDateTime, PrimitiveDateTime};
 
 use crate::{
-    db::{exercise, exercise_solution},
+    db::{achievement, bookmark, exercise, exercise_solution, report, review},
+    ddl, engine,
     error::Result,
+    extractors::Locale,
+    markdown::highlight_sql,
     models::{
         exercise::ExerciseId,
+        exercise_solution::UserSolutionId,
+        review::ReviewCard,
         user::{User, UserClaims},
     },
-    partials::{app_layout, page},
+    partials::{app_layout, format_datetime, page},
+    review::ReviewRating,
+    routes::bookmark::bookmark_toggle,
     state::AppState,
     static_files,
 };
 
+/// The user's review card of the exercise, if it's due. Earlier answers stay hidden until
+/// it's reviewed, it's meant to be solved from memory.
+async fn get_due_review_card(
+    conn: &mut PgConnection,
+    user_id: &str,
+    exercise_id: ExerciseId,
+) -> eyre::Result<Option<ReviewCard>> {
+    let now = OffsetDateTime::now_utc();
+    let now = PrimitiveDateTime::new(now.date(), now.time());
+
+    let card = review::get_review_card(conn, user_id, exercise_id)
+        .await
+        .wrap_err("Failed to query review card")?
+        .filter(|card| *card.due_at() <= now);
+
+    Ok(card)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunQuery {
+    /// Solving the exercise again from memory, see `routes::review`.
+    #[serde(default)]
+    review: bool,
+}
+
 #[debug_handler]
 #[tracing::instrument(skip_all)]
 pub async fn run(
     State(state): State<AppState>,
     Path(exercise_id): Path<ExerciseId>,
+    Query(run_query): Query<RunQuery>,
     Cached(user): Cached<User>,
     user_claims: UserClaims,
+    Locale(locale): Locale,
 ) -> Result<impl IntoResponse> {
     let mut conn = state
         .db()
@@ -35,7 +71,7 @@ pub async fn run(
         .await
         .wrap_err("Failed to acquire DB connection")?;
 
-    let exercise = match exercise::get_exercise(&mut conn, exercise_id)
+    let mut exercise = match exercise::get_exercise(&mut conn, exercise_id)
         .await
         .wrap_err("Failed to query exercise")?
     {
@@ -43,6 +79,13 @@ pub async fn run(
         None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
     };
 
+    if let Some(translation) = exercise::get_exercise_translation(&mut conn, exercise_id, &locale)
+        .await
+        .wrap_err("Failed to query exercise translation")?
+    {
+        exercise.translate(translation);
+    }
+
     let schema = exercise::get_exercise_schema(&mut conn, *exercise.schema_id())
         .await
         .wrap_err("Failed to query exercise schema")?
@@ -52,6 +95,35 @@ pub async fn run(
         .await
         .wrap_err("Failed to query user solution")?;
 
+    let draft = exercise_solution::get_user_draft(&mut conn, user.id(), exercise_id)
+        .await
+        .wrap_err("Failed to query user draft")?;
+
+    let review_card = if run_query.review {
+        get_due_review_card(&mut conn, user.id(), exercise_id).await?
+    } else {
+        None
+    };
+
+    let last_submission = solution.as_ref().map(|solution| *solution.id());
+
+    // reviews start from an empty editor, only what's been submitted since counts
+    let (solution, draft) = match &review_card {
+        Some(card) => (
+            solution.filter(|solution| solution.created_at() >= card.due_at()),
+            None,
+        ),
+        None => (solution, draft),
+    };
+
+    let bookmarked = bookmark::is_bookmarked(&mut conn, user.id(), exercise_id)
+        .await
+        .wrap_err("Failed to query exercise bookmark")?;
+
+    let new_achievements = achievement::take_new_achievements(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to query new achievements")?;
+
     let solution_correct = solution
         .as_ref()
         .map(|s| s.status() == "correct")
@@ -59,26 +131,113 @@ pub async fn run(
 
     let title = format!("SQL Grimoire - {}", exercise.name());
 
-    let question_text = {
-        let question_parsed = pulldown_cmark::Parser::new(exercise.question());
-        let mut question_html = String::new();
-        pulldown_cmark::html::push_html(&mut question_html, question_parsed);
+    // unparseable schemas are still drawn once the DB boots in the browser
+    let dialect = *schema.dialect();
+    let code_language = format!("language-{}", dialect.code_language());
 
-        PreEscaped(question_html)
+    let diagram = ddl::parse(schema.schema(), dialect)
+        .ok()
+        .map(|schema_info| schema_info.mermaid());
+
+    let render_text = |field: &str, source: &str| {
+        state
+            .markdown()
+            .render(&format!("{}:{locale}:{field}", exercise.id()), source)
     };
 
+    let question_text = render_text("question", exercise.question());
+    let hint_text = exercise
+        .hint()
+        .as_deref()
+        .map(|hint| render_text("hint", hint));
+    let editorial_text = exercise
+        .editorial()
+        .as_deref()
+        .filter(|_| solution_correct)
+        .map(|editorial| render_text("editorial", editorial));
+
     let inner = app_layout(
         html! {
+            @if !new_achievements.is_empty() {
+                div class="toasts" {
+                    @for achievement in new_achievements.iter().filter_map(|earned| earned.achievement()) {
+                        div class="toast" {
+                            i data-lucide=(achievement.icon()) class="toast__icon" {}
+                            div class="toast__body" {
+                                strong class="toast__title" { "Achievement unlocked: " (achievement.title()) }
+                                span class="toast__text" { (achievement.description()) }
+                            }
+                        }
+                    }
+                }
+            }
             div class="content__header" {
                 a href="/" class="button button--text" {
                     i data-lucide="chevron-left" class="button__icon" {}
                     "Back to Exercises"
                 }
+
+                details
+                    class="report-problem"
+                    data-controller="report"
+                    data-report-url-value={"/exercise/" (exercise_id) "/report/"}
+                    data-report-editor-outlet="#editor"
+                {
+                    summary class="button button--text report-problem__summary" {
+                        i data-lucide="flag" class="button__icon" {}
+                        "Report a Problem"
+                    }
+                    form class="report-problem__form" data-action="submit->report#submit" {
+                        p class="form__hint" {
+                            "Wrong expected result or an unclear question? Your current query is sent along."
+                        }
+                        textarea
+                            class="form__textarea"
+                            name="message"
+                            rows="4"
+                            required
+                            maxlength=(MAX_REPORT_LENGTH)
+                            placeholder="What's wrong with this exercise?"
+                            data-report-target="message"
+                        {}
+                        div class="report-problem__footer" {
+                            span class="report-problem__status" data-report-target="status" {}
+                            button type="submit" class="button button--primary" { "Send Report" }
+                        }
+                    }
+                }
+            }
+            @if review_card.is_some() {
+                div class="review" {
+                    i data-lucide="repeat" class="review__icon" {}
+                    span class="review__text" {
+                        @if solution_correct {
+                            "Solved again! How hard was it?"
+                        } @else {
+                            "Review: solve the exercise from memory, then rate how it went."
+                        }
+                    }
+                    form class="review__ratings" method="post" action={"/review/" (exercise_id) "/"} {
+                        @if solution_correct {
+                            @for rating in ReviewRating::ALL {
+                                button type="submit" name="rating" value=(rating) class="button button--secondary" {
+                                    (rating.label())
+                                }
+                            }
+                        } @else {
+                            button type="submit" name="rating" value=(ReviewRating::Again) class="button button--text" {
+                                "I forgot, show it again soon"
+                            }
+                        }
+                    }
+                }
             }
             turbo-frame
                 #db
                 data-controller="sql-run solution-submit sql-highlight mermaid-schema-vis db"
                 data-action="db:db-created->mermaid-schema-vis#drawSchema"
+                data-mermaid-schema-vis-diagram-value=[diagram]
+                data-db-dialect-value=(dialect)
                 data-sql-run-editor-outlet="#editor"
                 data-sql-run-db-outlet="#db"
                 data-solution-submit-editor-outlet="#editor"
@@ -88,9 +247,26 @@ pub async fn run(
 
                 div class="grid" {
                     div class="panel panel--exercise" {
-                        h2 class="panel__title" { (exercise.name()) }
+                        div class="panel__header" {
+                            h2 class="panel__title" { (exercise.name()) }
+                            (bookmark_toggle(exercise_id, bookmarked))
+                        }
                         div class="panel__content" {
-                            div class="panel__text" { (question_text) }
+                            div class="panel__text markdown" { (question_text) }
+                            @if let Some(hint_text) = hint_text {
+                                details
+                                    class="hint"
+                                    data-controller="hint"
+                                    data-hint-url-value={"/exercise/" (exercise_id) "/hint/"}
+                                    data-action="toggle->hint#toggle"
+                                {
+                                    summary class="hint__summary" {
+                                        i data-lucide="lightbulb" class="hint__icon" {}
+                                        "Show hint"
+                                    }
+                                    div class="hint__text markdown" { (hint_text) }
+                                }
+                            }
                             div
                                 class="table-info"
                             {
@@ -100,7 +276,7 @@ pub async fn run(
                                     }
                                     pre class="table-info__schema" {
                                         code
-                                            class="language-pgsql"
+                                            class=(code_language)
                                             data-sql-highlight-target="code"
                                         {
                                             (schema.schema())
@@ -123,9 +299,14 @@ pub async fn run(
                         data-action="db:db-created@window->editor#updateSchemaSuggestions"
                         data-controller="editor"
                         data-editor-mode-value="monaco"
+                        data-editor-draft-url-value=[review_card.is_none().then(|| format!("/exercise/{exercise_id}/draft/"))]
+                        data-editor-last-submission-value=[last_submission]
                     {
                         div class="editor__header" {
                             h3 class="editor__title" { "Query Editor" }
+                            span class="editor__status" data-editor-target="status" {
+                                @if draft.is_some() { "Draft restored" }
+                            }
 
                             div
                                 class="editor__actions"
@@ -186,10 +367,13 @@ pub async fn run(
                         div
                             class="editor__textarea"
                             placeholder="Write your SQL query here..."
-                            data-language="pgsql"
+                            data-language=(dialect.code_language())
                             data-editor-target="editor"
                         {
-                            (solution.as_ref().map(|s| s.query().as_str()).unwrap_or(""))
+                            (draft
+                                .as_deref()
+                                .or(solution.as_ref().map(|s| s.query().as_str()))
+                                .unwrap_or(""))
                         }
 
                         @if let Some(solution) = &solution {
@@ -214,12 +398,17 @@ pub async fn run(
                                 }
                                 pre class="expected-query__code" {
                                     code
-                                        class="language-sql"
+                                        class=(code_language)
                                         data-sql-highlight-target="code"
                                     {
                                         (exercise.expected_query())
                                     }
                                 }
+                                @if let Some(editorial_text) = editorial_text {
+                                    div class="expected-query__editorial markdown" {
+                                        (editorial_text)
+                                    }
+                                }
                             }
                         }
                     }
@@ -235,11 +424,50 @@ pub async fn run(
                         "Run the query to see the results"
                     }
                 }
+
+                // earlier attempts and community solutions would give the answer away
+                @if review_card.is_none() {
+                    div class="panel panel--attempts" {
+                        turbo-frame
+                            #attempts
+                            src={"/exercise/" (exercise_id) "/attempts/"}
+                            loading="lazy"
+                        {
+                            h3 class="panel__title" { "Attempt History" }
+                            "Loading attempts..."
+                        }
+                    }
+                }
+
+                div class="panel panel--notes" {
+                    turbo-frame
+                        #notes
+                        src={"/exercise/" (exercise_id) "/notes/"}
+                        loading="lazy"
+                    {
+                        h3 class="panel__title" { "My Notes" }
+                        "Loading notes..."
+                    }
+                }
+
+                div class="panel panel--discussion" {
+                    turbo-frame
+                        #comments
+                        src={"/exercise/" (exercise_id) "/comments/"}
+                        loading="lazy"
+                    {
+                        h3 class="panel__title" { "Discussion" }
+                        "Loading comments..."
+                    }
+                }
             }
 
             script type="module" src={"/static/" (static_files::db_controller.name)} {}
             script type="module" src={"/static/" (static_files::mermaid_schema_vis_controller.name)} {}
             script type="module" src={"/static/" (static_files::drag_resize_controller.name)} {}
+            script type="module" src={"/static/" (static_files::hint_controller.name)} {}
+            script type="module" src={"/static/" (static_files::spoiler_controller.name)} {}
+            script type="module" src={"/static/" (static_files::report_controller.name)} {}
 
             script defer type="module" src={"/static/" (static_files::monaco_init.name)} {}
             script defer type="module" src={"/static/" (static_files::editor_controller.name)} {}
@@ -253,6 +481,150 @@ pub async fn run(
     Ok(page(&title, inner).into_response())
 }
 
+const ATTEMPTS_PER_PAGE: i64 = 10;
+
+/// Title of the `attempts` frame, community solutions get a tab once the exercise is solved.
+fn history_header(exercise_id: ExerciseId, solved: bool, community: bool) -> Markup {
+    html! {
+        @if solved {
+            nav class="tabs tabs--panel" {
+                a
+                    href={"/exercise/" (exercise_id) "/attempts/"}
+                    class={"tabs__link" @if !community { " tabs__link--active" }}
+                {
+                    "Attempt History"
+                }
+                a
+                    href={"/exercise/" (exercise_id) "/solutions/"}
+                    class={"tabs__link" @if community { " tabs__link--active" }}
+                {
+                    i data-lucide="users" class="tabs__icon" {}
+                    "Community Solutions"
+                }
+            }
+        } @else {
+            h3 class="panel__title" { "Attempt History" }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttemptsQuery {
+    page: Option<i64>,
+}
+
+/// Page of the user's attempt history, rendered into the `attempts` frame of the exercise page.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn attempts(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    Query(query): Query<AttemptsQuery>,
+    user: UserClaims,
+) -> Result<impl IntoResponse> {
+    let page = query.page.unwrap_or(1).max(1);
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let exercise = match exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+    {
+        Some(exercise) => exercise,
+        None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
+    };
+
+    if get_due_review_card(&mut conn, user.sub(), exercise_id)
+        .await?
+        .is_some()
+    {
+        return Ok(html! {
+            turbo-frame #attempts {
+                h3 class="panel__title" { "Attempt History" }
+                p class="attempts__empty" { "Hidden until the review is done" }
+            }
+        }
+        .into_response());
+    }
+
+    let schema = exercise::get_exercise_schema(&mut conn, *exercise.schema_id())
+        .await
+        .wrap_err("Failed to query exercise schema")?
+        .ok_or_eyre("Exercise schema not found")?;
+
+    let solved = exercise_solution::has_solved_exercise(&mut conn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to check if exercise is solved")?;
+
+    let total = exercise_solution::count_user_solutions(&mut conn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to count user solutions")?;
+
+    let attempts = exercise_solution::get_user_solutions(
+        &mut conn,
+        user.sub(),
+        exercise_id,
+        ATTEMPTS_PER_PAGE,
+        (page - 1) * ATTEMPTS_PER_PAGE,
+    )
+    .await
+    .wrap_err("Failed to query user solutions")?;
+
+    let pages = (total + ATTEMPTS_PER_PAGE - 1) / ATTEMPTS_PER_PAGE;
+    let page_href = |page: i64| format!("/exercise/{exercise_id}/attempts/?page={page}");
+
+    Ok(html! {
+        turbo-frame #attempts {
+            (history_header(exercise_id, solved, false))
+
+            @if attempts.is_empty() {
+                p class="attempts__empty" { "No attempts yet" }
+            } @else {
+                ol class="attempts" {
+                    @for attempt in &attempts {
+                        li class="attempts__item" {
+                            div class="attempts__meta" {
+                                span class={"attempts__status attempts__status--" (attempt.status())} {
+                                    @if attempt.status() == "correct" { "Correct" } @else { "Incorrect" }
+                                }
+                                time class="attempts__time" { (format_datetime(*attempt.created_at())) }
+                                button
+                                    type="button"
+                                    class="button button--secondary attempts__load"
+                                    data-action="solution-submit#loadAttempt"
+                                    data-solution-submit-query-param=(attempt.query())
+                                {
+                                    "Load into editor"
+                                }
+                            }
+                            pre class="attempts__query" {
+                                code { (highlight_sql(attempt.query(), *schema.dialect())) }
+                            }
+                        }
+                    }
+                }
+
+                @if pages > 1 {
+                    nav class="pagination" {
+                        @if page > 1 {
+                            a class="pagination__link" href=(page_href(page - 1)) { "Newer" }
+                        }
+                        span class="pagination__current" { "Page " (page) " of " (pages) }
+                        @if page < pages {
+                            a class="pagination__link" href=(page_href(page + 1)) { "Older" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    .into_response())
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ExerciseCheckResultRequest {
     query: String,
@@ -278,8 +650,26 @@ pub async fn submit_solution(
         .wrap_err("Failed to query exercise")?
         .ok_or_eyre("Exercise not found")?;
 
+    let schema = exercise::get_exercise_schema(&mut txn, *exercise.schema_id())
+        .await
+        .wrap_err("Failed to query exercise schema")?
+        .ok_or_eyre("Exercise schema not found")?;
+
+    // results reported by the browser are only trusted if there's no engine on the server
+    let result = match engine::run_query(
+        *schema.dialect(),
+        schema.schema().clone(),
+        results.query.clone(),
+    )
+    .await
+    {
+        Some(Ok(result)) => result,
+        Some(Err(e)) => serde_json::json!({ "error": e.to_string() }),
+        None => results.result,
+    };
+
     let status = {
-        if exercise.expected_result() == &results.result {
+        if exercise.expected_result() == &result {
             "correct"
         } else {
             "incorrect"
@@ -290,14 +680,34 @@ pub async fn submit_solution(
         &mut txn,
         user.sub(),
         exercise_id,
+        *schema.dialect(),
         &results.query,
-        results.result,
+        result,
         status,
     )
     .await
     .wrap_err("Failed to create user solution")?
     .id();
 
+    // the submitted query is in the attempt history now
+    exercise_solution::delete_user_draft(&mut txn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to delete user draft")?;
+
+    if status == "correct" {
+        review::create_review_card(&mut txn, user.sub(), exercise_id)
+            .await
+            .wrap_err("Failed to create review card")?;
+    }
+
+    let facts = achievement::get_achievement_facts(&mut txn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to collect achievement facts")?;
+
+    achievement::award_achievements(&mut txn, user.sub(), &facts.earned())
+        .await
+        .wrap_err("Failed to award achievements")?;
+
     txn.commit()
         .await
         .wrap_err("Failed to commit transaction")?;
@@ -307,3 +717,271 @@ pub async fn submit_solution(
         Json(serde_json::json!({ "solution_id": solution_id })),
     ))
 }
+
+/// Called when the user opens the hint of the exercise.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn hint_viewed(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    achievement::record_hint_view(&mut conn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to record hint view")?;
+
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+const MAX_DRAFT_LENGTH: usize = 64 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct DraftRequest {
+    query: String,
+    /// Latest submission when the editor loaded, drafts older than a newer one are dropped.
+    last_submission: Option<UserSolutionId>,
+}
+
+/// Autosaved by the editor while the user types, kept apart from graded submissions.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn save_draft(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+    Json(draft): Json<DraftRequest>,
+) -> Result<impl IntoResponse> {
+    if draft.query.len() > MAX_DRAFT_LENGTH {
+        return Ok((http::StatusCode::PAYLOAD_TOO_LARGE, "Draft is too long").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .is_none()
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response());
+    }
+
+    let saved = exercise_solution::save_user_draft(
+        &mut conn,
+        user.sub(),
+        exercise_id,
+        &draft.query,
+        draft.last_submission,
+    )
+    .await
+    .wrap_err("Failed to save user draft")?;
+
+    if !saved {
+        return Ok((http::StatusCode::CONFLICT, "Draft is out of date").into_response());
+    }
+
+    Ok(http::StatusCode::NO_CONTENT.into_response())
+}
+
+const COMMUNITY_SOLUTIONS_LIMIT: i64 = 20;
+
+/// Distinct correct queries of other users, only revealed to those who solved the exercise.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn community_solutions(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let exercise = match exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+    {
+        Some(exercise) => exercise,
+        None => return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response()),
+    };
+
+    if !exercise_solution::has_solved_exercise(&mut conn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to check if exercise is solved")?
+    {
+        return Ok((http::StatusCode::FORBIDDEN, "Solve the exercise first").into_response());
+    }
+
+    if get_due_review_card(&mut conn, user.sub(), exercise_id)
+        .await?
+        .is_some()
+    {
+        return Ok((http::StatusCode::FORBIDDEN, "Finish the review first").into_response());
+    }
+
+    let schema = exercise::get_exercise_schema(&mut conn, *exercise.schema_id())
+        .await
+        .wrap_err("Failed to query exercise schema")?
+        .ok_or_eyre("Exercise schema not found")?;
+
+    let solutions = exercise_solution::get_community_solutions(
+        &mut conn,
+        user.sub(),
+        exercise_id,
+        COMMUNITY_SOLUTIONS_LIMIT,
+    )
+    .await
+    .wrap_err("Failed to query community solutions")?;
+
+    Ok(html! {
+        turbo-frame #attempts {
+            (history_header(exercise_id, true, true))
+
+            ol class="attempts" {
+                @for solution in &solutions {
+                    li class="attempts__item" {
+                        div class="attempts__meta" {
+                            form method="post" action={"/exercise/" (exercise_id) "/solutions/vote/"} {
+                                input type="hidden" name="fingerprint" value=(solution.fingerprint());
+                                button
+                                    type="submit"
+                                    class={"vote" @if *solution.voted() { " vote--active" }}
+                                    title=(if *solution.voted() { "Remove upvote" } else { "Upvote" })
+                                {
+                                    i data-lucide="thumbs-up" class="vote__icon" {}
+                                    (solution.votes())
+                                }
+                            }
+                            @match solution.author_name() {
+                                Some(name) => span class="attempts__author" { (name) },
+                                None => span class="attempts__author attempts__author--anonymous" { "Anonymous" },
+                            }
+                            span class="attempts__users" {
+                                (solution.users())
+                                @if *solution.users() == 1 { " user" } @else { " users" }
+                            }
+                            button
+                                type="button"
+                                class="button button--secondary attempts__load"
+                                data-action="solution-submit#loadAttempt"
+                                data-solution-submit-query-param=(solution.example())
+                            {
+                                "Load into editor"
+                            }
+                        }
+                        pre class="attempts__query" {
+                            code { (highlight_sql(solution.example(), *schema.dialect())) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VoteForm {
+    fingerprint: String,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn vote_solution(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+    Form(form): Form<VoteForm>,
+) -> Result<impl IntoResponse> {
+    let mut txn = state
+        .db()
+        .begin()
+        .await
+        .wrap_err("Failed to start transaction")?;
+
+    if !exercise_solution::has_solved_exercise(&mut txn, user.sub(), exercise_id)
+        .await
+        .wrap_err("Failed to check if exercise is solved")?
+    {
+        return Ok((http::StatusCode::FORBIDDEN, "Solve the exercise first").into_response());
+    }
+
+    if !exercise_solution::toggle_solution_vote(
+        &mut txn,
+        user.sub(),
+        exercise_id,
+        &form.fingerprint,
+    )
+    .await
+    .wrap_err("Failed to toggle solution vote")?
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Solution not found").into_response());
+    }
+
+    txn.commit()
+        .await
+        .wrap_err("Failed to commit transaction")?;
+
+    Ok(Redirect::to(&format!("/exercise/{exercise_id}/solutions/")).into_response())
+}
+
+pub const MAX_REPORT_LENGTH: usize = 4000;
+
+#[derive(Debug, Deserialize)]
+pub struct ReportRequest {
+    message: String,
+    /// What was in the editor when reporting.
+    #[serde(default)]
+    query: String,
+}
+
+/// Lets students tell about a wrong expected result or an ambiguous question.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn report_problem(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    user: UserClaims,
+    Json(request): Json<ReportRequest>,
+) -> Result<impl IntoResponse> {
+    let message = request.message.trim();
+
+    if message.is_empty() {
+        return Ok((http::StatusCode::BAD_REQUEST, "Report message is empty").into_response());
+    }
+
+    if message.chars().count() > MAX_REPORT_LENGTH || request.query.len() > MAX_DRAFT_LENGTH {
+        return Ok((http::StatusCode::PAYLOAD_TOO_LARGE, "Report is too long").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if exercise::get_exercise(&mut conn, exercise_id)
+        .await
+        .wrap_err("Failed to query exercise")?
+        .is_none()
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Exercise not found").into_response());
+    }
+
+    report::create_report(&mut conn, exercise_id, user.sub(), &request.query, message)
+        .await
+        .wrap_err("Failed to create exercise report")?;
+
+    Ok(http::StatusCode::CREATED.into_response())
+}
@@ -0,0 +1,196 @@
+use axum::{
+    debug_handler,
+    extract::{Query, State},
+    http,
+    response::IntoResponse,
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+
+use crate::{
+    db::{cohort, exercise, leaderboard},
+    error::Result,
+    models::{
+        cohort::CohortId,
+        exercise::ExerciseSchemaId,
+        leaderboard::LeaderboardMetric,
+        user::{Permission, User},
+    },
+    partials::{app_layout, page},
+    state::AppState,
+};
+
+const LEADERBOARD_SIZE: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    #[serde(default)]
+    metric: LeaderboardMetric,
+    track: Option<ExerciseSchemaId>,
+    cohort: Option<CohortId>,
+}
+
+fn leaderboard_href(
+    metric: LeaderboardMetric,
+    track: Option<ExerciseSchemaId>,
+    cohort: Option<CohortId>,
+) -> String {
+    let mut href = format!("/leaderboard/?metric={}", metric.as_str());
+
+    if let Some(track) = track {
+        href.push_str(&format!("&track={track}"));
+    }
+
+    if let Some(cohort) = cohort {
+        href.push_str(&format!("&cohort={cohort}"));
+    }
+
+    href
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn leaderboard_page(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let tracks = exercise::get_exercise_schemas(&mut conn)
+        .await
+        .wrap_err("Failed to get tracks")?;
+
+    let cohorts = cohort::get_user_cohorts(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to get user cohorts")?;
+
+    // cohort leaderboards are only shown to the cohort itself
+    if let Some(cohort_id) = query.cohort
+        && !user.can(Permission::ManageUsers)
+        && !cohorts.iter().any(|cohort| *cohort.id() == cohort_id)
+    {
+        return Ok((http::StatusCode::NOT_FOUND, "Cohort not found").into_response());
+    }
+
+    let entries = leaderboard::get_leaderboard(
+        &mut conn,
+        query.metric,
+        query.track,
+        query.cohort,
+        LEADERBOARD_SIZE,
+    )
+    .await
+    .wrap_err("Failed to get leaderboard")?;
+
+    let global = query.track.is_none() && query.cohort.is_none();
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "Leaderboard" }
+                form class="search" method="post" action="/settings/leaderboard/" {
+                    label class="form__checkbox" {
+                        input type="checkbox" name="opt_out" value="true" checked[*user.leaderboard_opt_out()];
+                        "Hide my name"
+                    }
+                    button type="submit" class="button button--secondary" { "Save" }
+                }
+            }
+
+            nav class="tabs" {
+                a
+                    href=(leaderboard_href(query.metric, None, None))
+                    class={"tabs__link" @if global { " tabs__link--active" }}
+                {
+                    "All Time"
+                }
+                @for track in &tracks {
+                    a
+                        href=(leaderboard_href(query.metric, Some(*track.id()), None))
+                        class={"tabs__link" @if query.track == Some(*track.id()) { " tabs__link--active" }}
+                    {
+                        (track.name())
+                    }
+                }
+                @for cohort in &cohorts {
+                    a
+                        href=(leaderboard_href(query.metric, None, Some(*cohort.id())))
+                        class={"tabs__link" @if query.cohort == Some(*cohort.id()) { " tabs__link--active" }}
+                    {
+                        i data-lucide="graduation-cap" class="tabs__icon" {}
+                        (cohort.name())
+                    }
+                }
+            }
+
+            nav class="tabs" {
+                @for metric in LeaderboardMetric::ALL {
+                    a
+                        href=(leaderboard_href(*metric, query.track, query.cohort))
+                        class={"tabs__link" @if *metric == query.metric { " tabs__link--active" }}
+                    {
+                        (metric.label())
+                    }
+                }
+            }
+
+            div class="panel panel--progress" {
+                @if entries.is_empty() {
+                    p class="panel__text" { "Nobody has solved anything here yet." }
+                } @else {
+                    table class="table" {
+                        thead {
+                            tr {
+                                th class="table__header" { "#" }
+                                th class="table__header" { "Name" }
+                                @for metric in LeaderboardMetric::ALL {
+                                    th class={"table__header" @if *metric == query.metric { " leaderboard__metric" }} {
+                                        (metric.label())
+                                    }
+                                }
+                            }
+                        }
+                        tbody {
+                            @for entry in &entries {
+                                @let current = entry.user_id() == user.id();
+
+                                tr class={"table__row" @if current { " table__row--current" }} {
+                                    td class="table__cell" { (entry.rank()) }
+                                    td class="table__cell" {
+                                        @match entry.display_name() {
+                                            Some(name) => (name),
+                                            None => span class="leaderboard__anonymous" { "Anonymous" },
+                                        }
+                                        @if current {
+                                            " (you)"
+                                        }
+                                    }
+                                    td class="table__cell" { (entry.solved()) }
+                                    td class="table__cell" { (entry.score()) }
+                                    td class="table__cell" { (entry.first_try()) }
+                                }
+                            }
+                        }
+                    }
+                }
+                p class="content__subtitle" {
+                    "A solved exercise scores 10 points, minus one for every incorrect attempt before it. "
+                    "Leaderboards are updated every few minutes."
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Leaderboard",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
@@ -1,22 +1,41 @@
-use axum::{debug_handler, extract::State, response::IntoResponse};
+use std::collections::HashMap;
+
+use axum::{
+    debug_handler,
+    extract::{Query, State},
+    response::IntoResponse,
+};
 use axum_extra::extract::Cached;
 use eyre::WrapErr;
 use maud::html;
+use serde::Deserialize;
+use time::{OffsetDateTime, PrimitiveDateTime};
 
 use crate::{
-    db::exercise,
+    db::{assignment, exercise, review},
     error::Result,
-    models::user::User,
-    partials::{app_layout, page},
+    extractors::Locale,
+    models::user::{Permission, User},
+    partials::{app_layout, format_due, locale_switcher, page},
+    routes::bookmark::bookmark_toggle,
     state::AppState,
 };
 
+#[derive(Debug, Deserialize)]
+pub struct MainPageQuery {
+    /// Only lists exercises the user has bookmarked.
+    #[serde(default)]
+    bookmarked: bool,
+}
+
 #[debug_handler]
 #[tracing::instrument(skip_all)]
 /// Main page of the web interface
 pub async fn main_page(
     State(state): State<AppState>,
+    Query(query): Query<MainPageQuery>,
     Cached(user): Cached<User>,
+    Locale(locale): Locale,
 ) -> Result<impl IntoResponse> {
     let mut txn = state
         .db()
@@ -24,42 +43,108 @@ pub async fn main_page(
         .await
         .wrap_err("Failed to start transaction")?;
 
-    let exercises = exercise::get_exercise_list(&mut txn, user.id())
+    let exercises = exercise::get_exercise_list(&mut txn, user.id(), &locale)
         .await
         .wrap_err("Failed to get exercise list")?
         .into_iter()
-        .filter(|ex| user.is_admin() || *ex.published());
+        // authors see drafts, to be able to work on them
+        .filter(|ex| user.can(Permission::AuthorExercises) || *ex.published())
+        .filter(|ex| !query.bookmarked || *ex.bookmarked())
+        .collect::<Vec<_>>();
+
+    let assignments = assignment::get_open_assignments(&mut txn, user.id())
+        .await
+        .wrap_err("Failed to get open assignments")?;
+
+    let due_reviews = review::count_due_reviews(&mut txn, user.id())
+        .await
+        .wrap_err("Failed to count due reviews")?;
+
+    // assignments are sorted by due date, so the closest one wins
+    let mut due_dates = HashMap::new();
+    for assignment in &assignments {
+        for exercise_id in assignment.exercise_ids() {
+            due_dates
+                .entry(*exercise_id)
+                .or_insert(*assignment.due_at());
+        }
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let now = PrimitiveDateTime::new(now.date(), now.time());
 
     let inner = html! {
         div class="exercises" {
             div class="exercises__header" {
                 h1 class="exercises__title" { "SQL Exercises" }
-                @if user.is_admin() {
-                    a href="/admin/exercise/new/" class="button button--primary" {
-                        i data-lucide="plus" class="button__icon" {}
-                        "New Exercise"
+                div class="exercises__actions" {
+                    (locale_switcher(&state.config().supported_locales(), user.locale().as_deref()))
+                    @if due_reviews > 0 {
+                        a href="/review/" class="button button--secondary" {
+                            i data-lucide="repeat" class="button__icon" {}
+                            "Review (" (due_reviews) " due)"
+                        }
+                    }
+                    @if user.can(Permission::AuthorExercises) {
+                        a href="/admin/exercise/new/" class="button button--primary" {
+                            i data-lucide="plus" class="button__icon" {}
+                            "New Exercise"
+                        }
                     }
                 }
             }
+            @if !assignments.is_empty() {
+                div class="assignments" {
+                    @for assignment in &assignments {
+                        div class="assignments__item" {
+                            i data-lucide="graduation-cap" class="assignments__icon" {}
+                            span class="assignments__name" { (assignment.name()) }
+                            span class="assignments__cohort" { (assignment.cohort_name()) }
+                            span class="assignments__progress" {
+                                (assignment.solved()) "/" (assignment.exercise_ids().len()) " solved"
+                            }
+                            span class="due" { (format_due(*assignment.due_at(), now)) }
+                        }
+                    }
+                }
+            }
+            nav class="tabs tabs--list" {
+                a href="/" class={"tabs__link" @if !query.bookmarked { " tabs__link--active" }} {
+                    "All Exercises"
+                }
+                a href="/?bookmarked=true" class={"tabs__link" @if query.bookmarked { " tabs__link--active" }} {
+                    i data-lucide="star" class="tabs__icon" {}
+                    "Bookmarked"
+                }
+            }
             div class="exercises__container" {
                 table class="exercises-table" {
                     thead {
                         tr {
+                            th class="exercises-table__header exercises-table__header--center" { "Bookmark" }
                             th class="exercises-table__header" { "Exercise" }
                             th class="exercises-table__header exercises-table__header--center" { "Status" }
-                            @if user.is_admin() {
+                            @if user.can(Permission::AuthorExercises) {
                                 th class="exercises-table__header exercises-table__header--center" { "Published" }
                                 th class="exercises-table__header exercises-table__header--actions" { "Actions" }
                             }
                         }
                     }
                     tbody {
-                        @for exercise in exercises {
+                        @for exercise in &exercises {
                             tr class="exercises-table__row" {
+                                td class="exercises-table__cell exercises-table__cell--center" {
+                                    (bookmark_toggle(*exercise.id(), *exercise.bookmarked()))
+                                }
                                 td class="exercises-table__cell" {
                                     a href=(format!("/exercise/{}/", exercise.id())) class="exercise-link" {
                                         (exercise.name())
                                     }
+                                    @if !*exercise.solved() {
+                                        @if let Some(due_at) = due_dates.get(exercise.id()) {
+                                            span class="due" { (format_due(*due_at, now)) }
+                                        }
+                                    }
                                 }
                                 td class="exercises-table__cell exercises-table__cell--center" {
                                     @if *exercise.solved() {
@@ -68,7 +153,7 @@ pub async fn main_page(
                                         span class="status-icon status-icon--pending" { "○" }
                                     }
                                 }
-                                @if user.is_admin() {
+                                @if user.can(Permission::AuthorExercises) {
                                     td class="exercises-table__cell exercises-table__cell--center" {
                                         @if *exercise.published() {
                                             i data-lucide="check-circle-2" class="status-icon status-icon--completed" {}
@@ -77,8 +162,18 @@ pub async fn main_page(
                                         }
                                     }
                                     td class="exercises-table__cell exercises-table__cell--actions" {
-                                        a href=(format!("/admin/exercise/{}/", exercise.id())) class="icon-button" {
-                                            i data-lucide="edit" class="icon-button__icon" {}
+                                        @if user.can_edit_exercise(exercise.author_id().as_deref()) {
+                                            a href=(format!("/admin/exercise/{}/", exercise.id())) class="icon-button" {
+                                                i data-lucide="edit" class="icon-button__icon" {}
+                                            }
+                                        }
+                                        a href=(format!("/admin/exercise/{}/clone/", exercise.id())) class="icon-button" title="Clone" {
+                                            i data-lucide="copy" class="icon-button__icon" {}
+                                        }
+                                        @if user.can(Permission::ViewAnalytics) {
+                                            a href=(format!("/admin/exercise/{}/analytics/", exercise.id())) class="icon-button" title="Analytics" {
+                                                i data-lucide="activity" class="icon-button__icon" {}
+                                            }
                                         }
                                     }
                                 }
@@ -86,6 +181,9 @@ pub async fn main_page(
                         }
                     }
                 }
+                @if query.bookmarked && exercises.is_empty() {
+                    p class="exercises__empty" { "No bookmarks yet. Star exercises to find them here." }
+                }
             }
         }
     };
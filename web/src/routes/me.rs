@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use axum::{debug_handler, extract::State, response::IntoResponse};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::{Markup, html};
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::{
+    achievements::Achievement,
+    db::{achievement, progress},
+    error::Result,
+    extractors::Locale,
+    models::{progress::ActivityDay, user::User},
+    partials::{app_layout, format_datetime, page},
+    routes::settings::MAX_DISPLAY_NAME_LENGTH,
+    state::AppState,
+};
+
+/// Number of weeks shown in the activity heatmap.
+const ACTIVITY_WEEKS: i64 = 26;
+
+/// Number of unsolved exercises suggested to get back to.
+const UNSOLVED_LIMIT: i64 = 5;
+
+/// First day shown in the activity heatmap: Monday, `ACTIVITY_WEEKS` weeks ago.
+fn activity_start(today: Date) -> Date {
+    today
+        - Duration::weeks(ACTIVITY_WEEKS - 1)
+        - Duration::days(today.weekday().number_days_from_monday().into())
+}
+
+/// GitHub-style heatmap: a column per week, a cell per day, shaded by the number of attempts.
+fn activity_heatmap(activity: &[ActivityDay], today: Date) -> Markup {
+    let start = activity_start(today);
+    let counts: HashMap<Date, i64> = activity
+        .iter()
+        .map(|day| (*day.day(), *day.attempts()))
+        .collect();
+    let max = counts.values().copied().max().unwrap_or(0);
+
+    html! {
+        div class="heatmap" {
+            @for week in 0..ACTIVITY_WEEKS {
+                div class="heatmap__week" {
+                    @for weekday in 0..7 {
+                        @let day = start + Duration::days(week * 7 + weekday);
+                        @if day <= today {
+                            @let count = counts.get(&day).copied().unwrap_or(0);
+                            // 1..=4 for days with attempts, relative to the busiest day
+                            @let level = if count == 0 { 0 } else { (count * 4 + max - 1) / max };
+
+                            div
+                                class={"heatmap__day heatmap__day--" (level)}
+                                title={(day) ": " (count) " attempt(s)"} {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+/// User's progress dashboard.
+pub async fn progress_page(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Locale(locale): Locale,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    let tracks = progress::get_track_progress(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to get track progress")?;
+
+    let first_try = progress::get_first_try_stats(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to get first try stats")?;
+
+    let today = OffsetDateTime::now_utc().date();
+    let activity = progress::get_activity(
+        &mut conn,
+        user.id(),
+        PrimitiveDateTime::new(activity_start(today), Time::MIDNIGHT),
+    )
+    .await
+    .wrap_err("Failed to get activity")?;
+
+    let unsolved = progress::get_recent_unsolved(&mut conn, user.id(), &locale, UNSOLVED_LIMIT)
+        .await
+        .wrap_err("Failed to get unsolved exercises")?;
+
+    let earned: HashMap<_, _> = achievement::get_user_achievements(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to get achievements")?
+        .into_iter()
+        .filter_map(|earned| Some((earned.achievement()?.key(), *earned.earned_at())))
+        .collect();
+
+    let total: i64 = tracks.iter().map(|track| track.total()).sum();
+    let solved: i64 = tracks.iter().map(|track| track.solved()).sum();
+    let attempted: i64 = tracks.iter().map(|track| track.attempted()).sum();
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "My Progress" }
+                form class="search" method="post" action="/settings/display-name/" {
+                    input
+                        type="text"
+                        class="form__input search__input"
+                        name="display_name"
+                        placeholder="Display name"
+                        title="Shown to teachers and on leaderboards"
+                        maxlength=(MAX_DISPLAY_NAME_LENGTH)
+                        value=[user.display_name()];
+                    button type="submit" class="button button--secondary" { "Save Name" }
+                }
+            }
+
+            div class="stats" {
+                div class="stats__card" {
+                    span class="stats__value" { (solved) " / " (total) }
+                    span class="stats__label" { "Exercises solved" }
+                }
+                div class="stats__card" {
+                    span class="stats__value" { (attempted) }
+                    span class="stats__label" { "Exercises attempted" }
+                }
+                div class="stats__card" {
+                    span class="stats__value" {
+                        @match first_try.rate() {
+                            Some(rate) => { (rate) "%" },
+                            None => "–",
+                        }
+                    }
+                    span class="stats__label" { "Solved on the first try" }
+                }
+            }
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Achievements" }
+                div class="achievements" {
+                    @for achievement in Achievement::ALL {
+                        @let earned_at = earned.get(achievement.key());
+
+                        div
+                            class={"achievements__item" @if earned_at.is_none() { " achievements__item--locked" }}
+                            title=[earned_at.map(|earned_at| format!("Earned {}", format_datetime(*earned_at)))]
+                        {
+                            i data-lucide=(achievement.icon()) class="achievements__icon" {}
+                            span class="achievements__title" { (achievement.title()) }
+                            span class="achievements__text" { (achievement.description()) }
+                        }
+                    }
+                }
+            }
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Tracks" }
+                table class="table" {
+                    thead {
+                        tr {
+                            th class="table__header" { "Track" }
+                            th class="table__header" { "Attempted" }
+                            th class="table__header" { "Solved" }
+                            th class="table__header" { "Progress" }
+                        }
+                    }
+                    tbody {
+                        @for track in &tracks {
+                            tr class="table__row" {
+                                td class="table__cell" { (track.name()) }
+                                td class="table__cell" { (track.attempted()) " / " (track.total()) }
+                                td class="table__cell" { (track.solved()) " / " (track.total()) }
+                                td class="table__cell" {
+                                    progress
+                                        class="progress-bar"
+                                        value=(track.solved())
+                                        max=(track.total()) {}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Activity" }
+                (activity_heatmap(&activity, today))
+            }
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Pick Up Where You Left Off" }
+                @if unsolved.is_empty() {
+                    p class="panel__text" { "No unsolved exercises, well done!" }
+                } @else {
+                    ul class="unsolved" {
+                        @for exercise in &unsolved {
+                            li class="unsolved__item" {
+                                a href={"/exercise/" (exercise.id()) "/"} class="exercise-link" {
+                                    (exercise.name())
+                                }
+                                span class="unsolved__time" {
+                                    "Last attempt: " (format_datetime(*exercise.last_attempt_at()))
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div class="panel panel--progress" {
+                h3 class="panel__title" { "Your Data" }
+                p class="panel__text" {
+                    "Download everything stored about you, or delete your account with all of its data."
+                }
+                form class="search" method="post" action="/settings/solutions/" {
+                    label class="form__checkbox" {
+                        input type="checkbox" name="share" value="true" checked[*user.share_solution_name()];
+                        "Show my name on community solutions"
+                    }
+                    button type="submit" class="button button--secondary" { "Save" }
+                }
+                div class="data-actions" {
+                    a href="/me/data/" class="button button--secondary" data-turbo="false" download {
+                        i data-lucide="download" class="button__icon" {}
+                        "Download My Data"
+                    }
+                    a href="/me/delete/" class="button button--danger" {
+                        i data-lucide="trash-2" class="button__icon" {}
+                        "Delete Account"
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - My Progress",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
@@ -0,0 +1,141 @@
+use axum::{
+    debug_handler,
+    extract::{Form, Path, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use maud::html;
+use serde::Deserialize;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+use crate::{
+    db::{exercise_solution, review},
+    error::Result,
+    models::{exercise::ExerciseId, user::User},
+    partials::{app_layout, format_datetime, page},
+    review::ReviewRating,
+    state::AppState,
+};
+
+/// Serves the next exercise due for review, with an empty editor.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn review_page(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    if let Some(exercise_id) = review::get_next_due_review(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to query next due review")?
+    {
+        return Ok(Redirect::to(&format!("/exercise/{exercise_id}/?review=true")).into_response());
+    }
+
+    let next_review_at = review::get_next_review_at(&mut conn, user.id())
+        .await
+        .wrap_err("Failed to query next review time")?;
+
+    let inner = html! {
+        div class="content" {
+            div class="content__header" {
+                h1 class="content__title" { "Review" }
+            }
+
+            div class="panel panel--progress" {
+                @match next_review_at {
+                    Some(next_review_at) => {
+                        p class="panel__text" {
+                            "You're all caught up! The next exercise comes back for review on "
+                            (format_datetime(next_review_at)) "."
+                        }
+                    },
+                    None => {
+                        p class="panel__text" {
+                            "Nothing to review yet. Solved exercises come back here "
+                            "after a while, so you can practice them again from memory."
+                        }
+                    },
+                }
+                a href="/" class="button button--secondary" { "Back to Exercises" }
+            }
+        }
+    };
+
+    Ok(page(
+        "SQL Grimoire - Review",
+        app_layout(inner, "SQL Grimoire", user.auth_state()),
+    )
+    .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewForm {
+    rating: ReviewRating,
+}
+
+/// Reschedules the exercise with the user's rating, then moves on to the next one.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn review_post(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<ExerciseId>,
+    Cached(user): Cached<User>,
+    Form(form): Form<ReviewForm>,
+) -> Result<impl IntoResponse> {
+    let mut txn = state
+        .db()
+        .begin()
+        .await
+        .wrap_err("Failed to start transaction")?;
+
+    let card = match review::get_review_card(&mut txn, user.id(), exercise_id)
+        .await
+        .wrap_err("Failed to query review card")?
+    {
+        Some(card) => card,
+        None => return Ok((http::StatusCode::NOT_FOUND, "Review card not found").into_response()),
+    };
+
+    let now = OffsetDateTime::now_utc();
+    if *card.due_at() > PrimitiveDateTime::new(now.date(), now.time()) {
+        return Ok((
+            http::StatusCode::BAD_REQUEST,
+            "Exercise isn't due for review",
+        )
+            .into_response());
+    }
+
+    // a review only counts if the exercise was actually solved again
+    let solved =
+        exercise_solution::has_solved_since(&mut txn, user.id(), exercise_id, *card.due_at())
+            .await
+            .wrap_err("Failed to check if exercise was solved")?;
+    let rating = if solved {
+        form.rating
+    } else {
+        ReviewRating::Again
+    };
+
+    review::save_review(
+        &mut txn,
+        user.id(),
+        exercise_id,
+        card.schedule().next(rating),
+    )
+    .await
+    .wrap_err("Failed to save review")?;
+
+    txn.commit()
+        .await
+        .wrap_err("Failed to commit transaction")?;
+
+    Ok(Redirect::to("/review/").into_response())
+}
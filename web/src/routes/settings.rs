@@ -0,0 +1,141 @@
+use axum::{
+    debug_handler,
+    extract::{Form, State},
+    http,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Cached;
+use eyre::WrapErr;
+use serde::Deserialize;
+
+use crate::{
+    db::user,
+    error::Result,
+    models::{empty_as_none, user::User},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct LocaleForm {
+    /// `None` resets the preference to the browser's languages.
+    #[serde(default, deserialize_with = "empty_as_none")]
+    locale: Option<String>,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn set_locale(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Form(form): Form<LocaleForm>,
+) -> Result<impl IntoResponse> {
+    if let Some(locale) = &form.locale
+        && !state
+            .config()
+            .supported_locales()
+            .contains(&locale.as_str())
+    {
+        return Ok((http::StatusCode::BAD_REQUEST, "Unsupported locale").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    user::set_user_locale(&mut conn, user.id(), form.locale.as_deref())
+        .await
+        .wrap_err("Failed to save user locale")?;
+
+    Ok(Redirect::to("/").into_response())
+}
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+
+#[derive(Debug, Deserialize)]
+pub struct DisplayNameForm {
+    /// `None` goes back to the name from the identity provider.
+    #[serde(default, deserialize_with = "empty_as_none")]
+    display_name: Option<String>,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn set_display_name(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Form(form): Form<DisplayNameForm>,
+) -> Result<impl IntoResponse> {
+    let display_name = form.display_name.as_deref().map(str::trim);
+
+    if display_name.is_some_and(|name| name.chars().count() > MAX_DISPLAY_NAME_LENGTH) {
+        return Ok((http::StatusCode::BAD_REQUEST, "Display name is too long").into_response());
+    }
+
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    user::set_user_display_name(&mut conn, user.id(), display_name)
+        .await
+        .wrap_err("Failed to save user display name")?;
+
+    Ok(Redirect::to("/me/").into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardForm {
+    /// Unchecked checkboxes aren't sent at all.
+    #[serde(default)]
+    opt_out: bool,
+}
+
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn set_leaderboard_opt_out(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Form(form): Form<LeaderboardForm>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    user::set_user_leaderboard_opt_out(&mut conn, user.id(), form.opt_out)
+        .await
+        .wrap_err("Failed to save leaderboard opt-out")?;
+
+    Ok(Redirect::to("/leaderboard/").into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SolutionNameForm {
+    #[serde(default)]
+    share: bool,
+}
+
+/// Whether the user's name is shown on their community solutions.
+#[debug_handler]
+#[tracing::instrument(skip_all)]
+pub async fn set_share_solution_name(
+    State(state): State<AppState>,
+    Cached(user): Cached<User>,
+    Form(form): Form<SolutionNameForm>,
+) -> Result<impl IntoResponse> {
+    let mut conn = state
+        .db()
+        .acquire()
+        .await
+        .wrap_err("Failed to acquire DB connection")?;
+
+    user::set_user_share_solution_name(&mut conn, user.id(), form.share)
+        .await
+        .wrap_err("Failed to save solution name sharing")?;
+
+    Ok(Redirect::to("/me/").into_response())
+}
@@ -1,10 +1,12 @@
+use std::time::Duration;
+
 use apply::Apply;
 use axum::Router;
 use eyre::{Result, WrapErr};
 use sqlx::migrate;
 use tracing::info;
 
-use crate::{config::Config, state::AppState};
+use crate::{config::Config, db, state::AppState};
 
 mod logging;
 mod routes;
@@ -21,14 +23,19 @@ pub(crate) async fn setup_app(config: Config) -> Result<Router> {
         .wrap_err("Migrations failed")?;
     info!("Successfully ran migrations");
 
-    info!("Starting JWKS decoder");
-    let app_state_clone = app_state.clone();
-    tokio::spawn(async move {
-        app_state_clone
-            .jwks_decoder()
-            .refresh_keys_periodically()
-            .await
-    });
+    info!("Starting auth provider");
+    app_state.auth().spawn_background_tasks();
+
+    info!("Backfilling query fingerprints");
+    tokio::spawn(db::exercise_solution::backfill_query_fingerprints(
+        app_state.db().clone(),
+    ));
+
+    info!("Starting leaderboard refresher");
+    tokio::spawn(db::leaderboard::refresh_leaderboard_periodically(
+        app_state.db().clone(),
+        Duration::from_secs(*config.leaderboard_refresh_secs()),
+    ));
 
     info!("Setting up routes");
     let app = Router::new()
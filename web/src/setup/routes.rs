@@ -2,7 +2,8 @@ use axum::{Router, middleware, routing};
 use tower_http::compression::CompressionLayer;
 
 use crate::{
-    middlewares::{add_corbado_project_id, no_cache, require_admin, require_auth},
+    auth,
+    middlewares::{no_cache, require_auth},
     routes,
     state::AppState,
 };
@@ -16,11 +17,16 @@ pub(super) fn setup(app: Router<AppState>, state: AppState) -> Router<AppState>
         .route("/{*path}", routing::get(routes::serve_static::static_path))
         .layer(CompressionLayer::new());
 
-    let auth_router = Router::new()
-        .route("/login/", routing::get(routes::login::login))
-        .route("/callback/", routing::get(routes::login::after_login));
+    let auth_router = state
+        .auth()
+        .routes()
+        .route("/logout/", routing::post(auth::logout));
 
     let admin_router = Router::new()
+        .route(
+            "/analytics/",
+            routing::get(routes::admin::analytics_overview),
+        )
         .route(
             "/exercise/schemas/",
             routing::get(routes::admin::exercise_schema_list),
@@ -35,40 +41,195 @@ pub(super) fn setup(app: Router<AppState>, state: AppState) -> Router<AppState>
             routing::get(routes::admin::exercise_schema_edit)
                 .post(routes::admin::exercise_schema_post),
         )
+        .route(
+            "/exercise/schemas/{id}/clone/",
+            routing::get(routes::admin::exercise_schema_clone),
+        )
         .route(
             "/exercise/schemas/{id}/json/",
             routing::get(routes::admin::exercise_schema_json),
         )
+        .route(
+            "/exercise/schemas/{id}/info/",
+            routing::get(routes::admin::exercise_schema_info),
+        )
+        .route(
+            "/exercise/schemas/{id}/diagram/",
+            routing::get(routes::admin::exercise_schema_diagram),
+        )
         .route(
             "/exercise/{id}/",
             routing::get(routes::admin::exercise_edit).post(routes::admin::exercise_post),
         )
+        .route(
+            "/exercise/{id}/analytics/",
+            routing::get(routes::admin::exercise_analytics),
+        )
+        .route(
+            "/exercise/{id}/clone/",
+            routing::get(routes::admin::exercise_clone),
+        )
+        .route(
+            "/exercise/{id}/translations/{locale}/",
+            routing::get(routes::admin::exercise_translation_edit)
+                .post(routes::admin::exercise_translation_post),
+        )
+        .route(
+            "/exercise/preview/",
+            routing::post(routes::admin::exercise_question_preview),
+        )
         .route(
             "/exercise/new/",
             routing::get(routes::admin::exercise_new).post(routes::admin::exercise_post),
         )
-        .layer(middleware::from_fn_with_state(state.clone(), require_admin));
+        .route("/users/", routing::get(routes::admin::user_list))
+        .route(
+            "/users/{id}/role/",
+            routing::post(routes::admin::user_role_post),
+        )
+        .route(
+            "/users/{id}/submissions/",
+            routing::get(routes::admin::user_submissions),
+        )
+        .route("/reports/", routing::get(routes::admin::report_list))
+        .route(
+            "/reports/{id}/status/",
+            routing::post(routes::admin::report_status_post),
+        )
+        .route(
+            "/users/{id}/data/",
+            routing::get(routes::admin::user_data_export),
+        )
+        .route(
+            "/users/{id}/delete/",
+            routing::get(routes::admin::user_delete).post(routes::admin::user_delete_post),
+        )
+        // permissions are checked by every action
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth));
 
     let exercise_router = Router::new()
         .route("/{id}/", routing::get(routes::exercise_run::run))
+        .route(
+            "/{id}/attempts/",
+            routing::get(routes::exercise_run::attempts),
+        )
         .route(
             "/{id}/submit/",
             routing::post(routes::exercise_run::submit_solution),
         )
+        .route(
+            "/{id}/hint/",
+            routing::post(routes::exercise_run::hint_viewed),
+        )
+        .route(
+            "/{id}/solutions/",
+            routing::get(routes::exercise_run::community_solutions),
+        )
+        .route(
+            "/{id}/solutions/vote/",
+            routing::post(routes::exercise_run::vote_solution),
+        )
+        .route(
+            "/{id}/comments/",
+            routing::get(routes::discussion::comments).post(routes::discussion::comment_create),
+        )
+        .route(
+            "/{id}/comments/{comment_id}/moderate/",
+            routing::post(routes::discussion::comment_moderate),
+        )
+        .route(
+            "/{id}/bookmark/",
+            routing::get(routes::bookmark::bookmark).post(routes::bookmark::bookmark_post),
+        )
+        .route(
+            "/{id}/notes/",
+            routing::get(routes::bookmark::notes).post(routes::bookmark::notes_post),
+        )
+        .route(
+            "/{id}/report/",
+            routing::post(routes::exercise_run::report_problem),
+        )
+        .route(
+            "/{id}/draft/",
+            routing::put(routes::exercise_run::save_draft),
+        )
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let cohort_router = Router::new()
+        .route(
+            "/",
+            routing::get(routes::cohort::cohort_list).post(routes::cohort::cohort_create),
+        )
+        .route("/{id}/", routing::get(routes::cohort::cohort_view))
+        .route(
+            "/{id}/assignments/",
+            routing::post(routes::assignment::assignment_create),
+        )
+        .route(
+            "/{id}/assignments/{assignment_id}/",
+            routing::get(routes::assignment::assignment_view),
+        )
+        .route(
+            "/{id}/assignments/{assignment_id}/results.csv",
+            routing::get(routes::assignment::assignment_export),
+        )
+        .route(
+            "/join/{code}/",
+            routing::get(routes::cohort::cohort_join).post(routes::cohort::cohort_join_post),
+        )
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let me_router = Router::new()
+        .route("/", routing::get(routes::me::progress_page))
+        .route("/data/", routing::get(routes::account::data_export))
+        .route(
+            "/delete/",
+            routing::get(routes::account::delete_account)
+                .post(routes::account::delete_account_post),
+        )
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let leaderboard_router = Router::new()
+        .route("/", routing::get(routes::leaderboard::leaderboard_page))
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let review_router = Router::new()
+        .route("/", routing::get(routes::review::review_page))
+        .route("/{id}/", routing::post(routes::review::review_post))
+        .layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let settings_router = Router::new()
+        .route("/locale/", routing::post(routes::settings::set_locale))
+        .route(
+            "/display-name/",
+            routing::post(routes::settings::set_display_name),
+        )
+        .route(
+            "/leaderboard/",
+            routing::post(routes::settings::set_leaderboard_opt_out),
+        )
+        .route(
+            "/solutions/",
+            routing::post(routes::settings::set_share_solution_name),
+        )
         .layer(middleware::from_fn_with_state(state.clone(), require_auth));
 
     let main_page_router = Router::new()
         .route("/", routing::get(routes::main::main_page))
         .layer(middleware::from_fn_with_state(state.clone(), require_auth));
 
-    app.merge(main_page_router)
+    let app = app
+        .merge(main_page_router)
         .nest("/static/", static_router)
         .nest("/exercise/", exercise_router)
+        .nest("/review/", review_router)
+        .nest("/cohorts/", cohort_router)
+        .nest("/me/", me_router)
+        .nest("/leaderboard/", leaderboard_router)
+        .nest("/settings/", settings_router)
         .nest("/auth/", auth_router)
         .nest("/admin/", admin_router)
-        .nest("/health/", health_router)
-        .layer(middleware::from_fn_with_state(
-            state.clone(),
-            add_corbado_project_id,
-        ))
+        .nest("/health/", health_router);
+
+    state.auth().layer(app)
 }
@@ -1,13 +1,15 @@
 use std::{ops::Deref, sync::Arc};
 
-use axum_jwt_auth::{RemoteJwksDecoder, RemoteJwksDecoderBuilder};
 use derive_getters::Getters;
 use eyre::{Result, WrapErr};
-use jsonwebtoken::{Algorithm, Validation};
 use secrecy::ExposeSecret;
 use sqlx::postgres::PgPool;
 
-use crate::config::Config;
+use crate::{
+    auth::{self, AuthProvider},
+    config::Config,
+    markdown::RenderCache,
+};
 
 #[derive(Clone)]
 pub struct AppState(Arc<AppStateInner>);
@@ -15,10 +17,10 @@ pub struct AppState(Arc<AppStateInner>);
 #[derive(Getters)]
 pub struct AppStateInner {
     db: PgPool,
-    jwks_decoder: RemoteJwksDecoder,
+    auth: Arc<dyn AuthProvider>,
     config: Config,
+    markdown: RenderCache,
 }
-
 impl Deref for AppState {
     type Target = AppStateInner;
 
@@ -32,27 +34,15 @@ impl AppStateInner {
         let db = PgPool::connect(config.database_url().expose_secret())
             .await
             .wrap_err("Failed to connect to database")?;
-        let jwks_url = url::Url::parse(config.corbado_host())
-            .wrap_err("Failed to parse corbado host")?
-            .join("/.well-known/jwks")
-            .wrap_err("Failed to join corbado host and jwks path")?;
-
-        let mut validation = Validation::new(Algorithm::RS256);
-        validation.algorithms = vec![Algorithm::RS256, Algorithm::RS384, Algorithm::RS512];
-
-        let jwks_decoder = RemoteJwksDecoderBuilder::default()
-            .jwks_url(jwks_url.to_string())
-            .validation(validation)
-            .config(Default::default())
-            .keys_cache(Default::default())
-            .client(Default::default())
-            .build()
-            .unwrap();
+        let auth = auth::from_config(&config)
+            .await
+            .wrap_err("Failed to set up auth provider")?;
 
         Ok(Self {
             db,
-            jwks_decoder,
+            auth,
             config,
+            markdown: RenderCache::default(),
         })
     }
 }
@@ -45,11 +45,26 @@ static_files!(
         "../static/js/controllers/editor-controller.js",
         mime::TEXT_JAVASCRIPT
     ),
+    (
+        hint_controller,
+        "../static/js/controllers/hint-controller.js",
+        mime::TEXT_JAVASCRIPT
+    ),
+    (
+        markdown_preview_controller,
+        "../static/js/controllers/markdown-preview-controller.js",
+        mime::TEXT_JAVASCRIPT
+    ),
     (
         mermaid_schema_vis_controller,
         "../static/js/controllers/mermaid-schema-vis-controller.js",
         mime::TEXT_JAVASCRIPT
     ),
+    (
+        report_controller,
+        "../static/js/controllers/report-controller.js",
+        mime::TEXT_JAVASCRIPT
+    ),
     (
         schema_hidden_controller,
         "../static/js/controllers/schema-hidden-controller.js",
@@ -60,6 +75,11 @@ static_files!(
         "../static/js/controllers/solution-submit-controller.js",
         mime::TEXT_JAVASCRIPT
     ),
+    (
+        spoiler_controller,
+        "../static/js/controllers/spoiler-controller.js",
+        mime::TEXT_JAVASCRIPT
+    ),
     (
         sql_highlight_controller,
         "../static/js/controllers/sql-highlight-controller.js",
@@ -1,8 +1,52 @@
 import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.2/+esm';
 import { PGlite } from 'https://cdn.jsdelivr.net/npm/@electric-sql/pglite@0.2.15/dist/index.js';
 
+const SQL_JS_VERSION = '1.12.0';
+
+// Wraps sql.js, so it can be used the same way as PGlite
+class SqliteDb {
+    static async create() {
+        const { default: initSqlJs } = await import(`https://esm.sh/sql.js@${SQL_JS_VERSION}`);
+        const SQL = await initSqlJs({
+            locateFile: (file) => `https://cdn.jsdelivr.net/npm/sql.js@${SQL_JS_VERSION}/dist/${file}`,
+        });
+
+        return new SqliteDb(new SQL.Database());
+    }
+
+    constructor(db) {
+        this.db = db;
+    }
+
+    async exec(sql) {
+        this.db.exec(sql);
+    }
+
+    // returns the result of the last statement, with rows as arrays
+    async query(sql) {
+        const results = this.db.exec(sql);
+        const last = results[results.length - 1];
+
+        if (!last) {
+            return { fields: [], rows: [] };
+        }
+
+        return {
+            fields: last.columns.map((name) => ({ name })),
+            rows: last.values,
+        };
+    }
+
+    async close() {
+        this.db.close();
+    }
+}
+
 class DbController extends Controller {
     static targets = ['schema'];
+    static values = {
+        dialect: { type: String, default: 'postgres' },
+    };
 
     async connect() {
         const schemaCreationQueries = this.getSchema();
@@ -22,12 +66,20 @@ class DbController extends Controller {
 
     async schemaUpdated({
         detail: {
-            schema: { schema },
+            schema: { schema, dialect },
         },
     }) {
+        this.dialectValue = dialect;
+
         await this.resetDb(schema);
     }
 
+    async dialectChanged({ target: { value } }) {
+        this.dialectValue = value;
+
+        await this.resetDbRequest();
+    }
+
     async resetDbRequest() {
         const schema = this.getSchema();
 
@@ -43,7 +95,9 @@ class DbController extends Controller {
             await this.db.close();
         }
 
-        this.db = await PGlite.create();
+        this.db = this.dialectValue === 'sqlite'
+            ? await SqliteDb.create()
+            : await PGlite.create();
 
         await this.db.exec(schema);
         this.dbInfo = await this.getDbInfo();
@@ -56,6 +110,10 @@ class DbController extends Controller {
             throw new Error('Database not initialized');
         }
 
+        if (this.dialectValue === 'sqlite') {
+            return await this.db.query(query);
+        }
+
         return await this.db.query(query, [], { rowMode: 'array' });
     }
 
@@ -68,6 +126,10 @@ class DbController extends Controller {
     async getDbInfo(schema = 'public') {
         const db = this.db;
 
+        if (this.dialectValue === 'sqlite') {
+            return await this.getSqliteDbInfo(db);
+        }
+
         const entities = await this.getEntities(db, schema);
         const relationships = await this.getRelationships(db, schema);
         const indexes = await this.getIndexes(db, schema);
@@ -205,6 +267,80 @@ class DbController extends Controller {
         return indexes.rows;
     }
     // #endregion
+
+    // #region SQLite DB Info
+    async getSqliteDbInfo(db) {
+        const entities = await db.query(`
+    select
+      tables.name as name,
+      json_group_array(
+        json_object(
+          'comment',
+          case when columns."notnull" or columns.pk then 'not null' else 'null' end,
+          'key',
+          case
+            when columns.pk then 'PK'
+            when foreign_keys."from" is not null then 'FK'
+          end,
+          'name',
+          columns.name,
+          'type',
+          lower(replace(coalesce(nullif(columns.type, ''), 'any'), ' ', '_'))
+        )
+      ) as attributes
+    from
+      sqlite_master as tables
+      join pragma_table_info(tables.name) as columns
+      left join (
+        select distinct tables.name as table_name, foreign_keys."from"
+        from sqlite_master as tables
+        join pragma_foreign_key_list(tables.name) as foreign_keys
+      ) as foreign_keys
+        on foreign_keys.table_name = tables.name
+        and foreign_keys."from" = columns.name
+    where
+      tables.type = 'table'
+      and tables.name not like 'sqlite_%'
+    group by
+      tables.name
+    order by
+      tables.name;
+        `);
+
+        const relationships = await db.query(`
+    select
+      tables.name as child_entity,
+      json_group_array(foreign_keys."from") as child_attributes,
+      foreign_keys."table" as parent_entity,
+      json_group_array(coalesce(foreign_keys."to", foreign_keys."from")) as parent_attributes
+    from
+      sqlite_master as tables
+      join pragma_foreign_key_list(tables.name) as foreign_keys
+    where
+      tables.type = 'table'
+    group by
+      tables.name,
+      foreign_keys.id
+    order by
+      parent_entity,
+      child_entity;
+        `);
+
+        return {
+            entities: entities.rows.map(([name, attributes]) => ({
+                name,
+                attributes: JSON.parse(attributes),
+            })),
+            relationships: relationships.rows.map(
+                ([childEntity, childAttributes, parentEntity, parentAttributes]) => ({
+                    child: { entity: childEntity, attributes: JSON.parse(childAttributes) },
+                    parent: { entity: parentEntity, attributes: JSON.parse(parentAttributes) },
+                }),
+            ),
+            indexes: [],
+        };
+    }
+    // #endregion
 }
 
 window.application.register('db', DbController);
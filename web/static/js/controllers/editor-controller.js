@@ -1,15 +1,23 @@
 import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.2/+esm';
 
+// how long typing has to pause before the draft is autosaved
+const DRAFT_SAVE_DELAY = 2000;
+
 class EditorController extends Controller {
-    static targets = ['editor'];
+    static targets = ['editor', 'status'];
     static values = {
-        mode: { type: String, default: 'simple' } // 'simple' or 'monaco'
+        mode: { type: String, default: 'simple' }, // 'simple' or 'monaco'
+        draftUrl: String,
+        lastSubmission: String,
     }
 
     connect() {
         this.tables = [];
         this.columns = [];
 
+        this.saveDraftNow = this.saveDraftNow.bind(this);
+        window.addEventListener('pagehide', this.saveDraftNow);
+
         if (this.modeValue === 'monaco') {
             this.initMonaco();
         } else if (this.modeValue === 'simple') {
@@ -106,8 +114,8 @@ class EditorController extends Controller {
     }
 
     initSimple() {
-        // For simple mode, we don't need to do anything special
-        // The textarea is ready to use as-is
+        // The textarea is ready to use as-is, only edits need to be tracked for drafts
+        this.editorTarget.addEventListener('input', () => this.scheduleDraftSave());
     }
 
     initMonaco() {
@@ -130,20 +138,92 @@ class EditorController extends Controller {
                 const value = this.editorTarget.textContent;
                 this.editorTarget.textContent = '';
 
-                monaco.languages.registerCompletionItemProvider("pgsql", {
+                // pgsql or sql, following the dialect of the exercise
+                const language = this.editorTarget.dataset.language;
+
+                this.completionProvider = monaco.languages.registerCompletionItemProvider(language, {
                     provideCompletionItems: this.provideCompletionItems.bind(this),
                 });
 
                 this.editor = monaco.editor.create(this.editorTarget, {
-                    language: this.editorTarget.dataset.language,
+                    language,
                     theme: 'vs-dark',
                     fontSize: 14,
                     value,
                     minimap: { enabled: false },
                 });
+
+                this.editor.onDidChangeModelContent(() => this.scheduleDraftSave());
             });
     }
 
+    scheduleDraftSave() {
+        if (!this.hasDraftUrlValue) {
+            return;
+        }
+
+        this.draftDirty = true;
+        clearTimeout(this.draftTimeout);
+        this.draftTimeout = setTimeout(this.saveDraftNow, DRAFT_SAVE_DELAY);
+    }
+
+    // Drops unsaved edits, the submission stores the query instead.
+    cancelDraftSave() {
+        clearTimeout(this.draftTimeout);
+        this.draftDirty = false;
+        // a save landing after the submission would bring the draft back
+        this.draftAbortController?.abort();
+    }
+
+    saveDraftNow() {
+        clearTimeout(this.draftTimeout);
+        if (!this.draftDirty) {
+            return;
+        }
+        this.draftDirty = false;
+
+        this.setStatus('Saving draft...');
+
+        // only the latest save matters, an older one finishing last would overwrite it
+        this.draftAbortController?.abort();
+        this.draftAbortController = new AbortController();
+
+        // keepalive lets the last save finish when the tab is being closed
+        fetch(this.draftUrlValue, {
+            method: 'PUT',
+            headers: {
+                'Content-Type': 'application/json',
+            },
+            body: JSON.stringify({
+                query: this.getValue(),
+                // lets the server drop this save if it lands after a submission
+                last_submission: this.lastSubmissionValue || null,
+            }),
+            keepalive: true,
+            signal: this.draftAbortController.signal,
+        })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error(`Unexpected status ${response.status}`);
+            }
+            this.setStatus('Draft saved');
+        })
+        .catch((error) => {
+            if (error.name === 'AbortError') {
+                return;
+            }
+
+            console.error('Error saving draft:', error);
+            this.setStatus('Draft not saved');
+        });
+    }
+
+    setStatus(text) {
+        if (this.hasStatusTarget) {
+            this.statusTarget.textContent = text;
+        }
+    }
+
     getValue() {
         if (this.modeValue === 'monaco') {
             return this.editor.getValue();
@@ -151,10 +231,25 @@ class EditorController extends Controller {
         return this.editorTarget.value;
     }
 
+    setValue(value) {
+        if (this.modeValue === 'monaco') {
+            this.editor.setValue(value);
+            this.editor.focus();
+            return;
+        }
+        this.editorTarget.value = value;
+    }
+
     disconnect() {
+        // leaving through a Turbo visit doesn't fire pagehide
+        this.saveDraftNow();
+        window.removeEventListener('pagehide', this.saveDraftNow);
+
         if (this.modeValue === 'monaco' && this.editor) {
             this.editor.dispose();
         }
+        // providers are global, the next editor would get its suggestions twice otherwise
+        this.completionProvider?.dispose();
     }
 }
 
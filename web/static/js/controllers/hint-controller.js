@@ -0,0 +1,20 @@
+import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.2/+esm';
+
+// Reports the first time the hint is opened, solving without it earns an achievement.
+class HintController extends Controller {
+    static values = { url: String };
+
+    toggle() {
+        if (!this.element.open || this.reported) {
+            return;
+        }
+
+        this.reported = true;
+
+        fetch(this.urlValue, { method: 'POST' }).catch((error) => {
+            console.error('Error reporting hint view:', error);
+        });
+    }
+}
+
+window.application.register('hint', HintController);
@@ -0,0 +1,61 @@
+import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.2/+esm';
+
+// Renders a live preview of the markdown in the source textarea,
+// using the same server-side renderer students see.
+class MarkdownPreviewController extends Controller {
+    static targets = ['source', 'schemaSelector', 'preview'];
+    static values = {
+        url: { type: String, default: '/admin/exercise/preview/' },
+        delay: { type: Number, default: 300 },
+    };
+
+    connect() {
+        this.render();
+    }
+
+    update() {
+        clearTimeout(this.timeout);
+        this.timeout = setTimeout(() => this.render(), this.delayValue);
+    }
+
+    async render() {
+        const body = {
+            question: this.sourceTarget.value,
+            schema_id: this.hasSchemaSelectorTarget && this.schemaSelectorTarget.value || null,
+        };
+
+        // a slower, older preview must not overwrite a newer one
+        this.abortController?.abort();
+        this.abortController = new AbortController();
+
+        try {
+            const response = await fetch(this.urlValue, {
+                method: 'POST',
+                headers: {
+                    'Content-Type': 'application/json',
+                },
+                body: JSON.stringify(body),
+                signal: this.abortController.signal,
+            });
+
+            if (!response.ok) {
+                throw new Error(`Preview request failed with status ${response.status}`);
+            }
+
+            this.previewTarget.innerHTML = await response.text();
+        } catch (error) {
+            if (error.name === 'AbortError') {
+                return;
+            }
+
+            console.error('Error rendering preview:', error);
+        }
+    }
+
+    disconnect() {
+        clearTimeout(this.timeout);
+        this.abortController?.abort();
+    }
+}
+
+window.application.register('markdown-preview', MarkdownPreviewController);
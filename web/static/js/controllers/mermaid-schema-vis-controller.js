@@ -9,6 +9,15 @@ mermaid.initialize({
 
 class MermaidSchemaVisController extends Controller {
     static targets = ['schemaVis'];
+    static values = { diagram: String };
+
+    connect() {
+        // diagram pre-rendered on the server from the schema DDL,
+        // shown right away instead of waiting for the DB to boot
+        if (this.diagramValue) {
+            this.renderDiagram(this.diagramValue);
+        }
+    }
 
     generateEntity(entity) {
         const mermaid = [`    ${entity.name} {`];
@@ -71,6 +80,10 @@ class MermaidSchemaVisController extends Controller {
         } = dbInfo;
         const diagram = this.generateDiagram({ entities, relationships });
 
+        this.renderDiagram(diagram);
+    }
+
+    renderDiagram(diagram) {
         // insert a single child element into the schemaVisTarget
         // child should be a pre with the class mermaid and content the diagram
         const pre = document.createElement('pre');
@@ -83,6 +96,10 @@ class MermaidSchemaVisController extends Controller {
             nodes: Array.from(this.schemaVisTarget.children),
         });
 
+        if (this.panzoom) {
+            return;
+        }
+
         this.panzoom = Panzoom(this.schemaVisTarget, {
             minScale: 1,
             maxScale: 3,
@@ -96,6 +113,7 @@ class MermaidSchemaVisController extends Controller {
 
     disconnect() {
         this.panzoom && this.panzoom.destroy();
+        this.panzoom = null;
     }
 }
 
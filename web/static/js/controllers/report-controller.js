@@ -0,0 +1,38 @@
+import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.2/+esm';
+
+// Sends a problem report about the exercise along with the query in the editor.
+class ReportController extends Controller {
+    static outlets = ['editor'];
+    static targets = ['message', 'status'];
+    static values = { url: String };
+
+    submit(event) {
+        event.preventDefault();
+
+        this.statusTarget.textContent = 'Sending...';
+
+        fetch(this.urlValue, {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json',
+            },
+            body: JSON.stringify({
+                message: this.messageTarget.value,
+                query: this.hasEditorOutlet ? this.editorOutlet.getValue() : '',
+            }),
+        })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error(`Unexpected status ${response.status}`);
+            }
+            this.messageTarget.value = '';
+            this.statusTarget.textContent = 'Thanks, we\'ll look into it!';
+        })
+        .catch((error) => {
+            console.error('Error sending report:', error);
+            this.statusTarget.textContent = 'Failed to send the report';
+        });
+    }
+}
+
+window.application.register('report', ReportController);
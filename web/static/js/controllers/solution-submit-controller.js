@@ -3,8 +3,13 @@ import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.
 class SolutionSubmitController extends Controller {
     static outlets = ['db', 'editor'];
 
+    loadAttempt({ params: { query } }) {
+        this.editorOutlet.setValue(query);
+    }
+
     async submit() {
         const query = this.editorOutlet.getValue();
+        this.editorOutlet.cancelDraftSave();
         const result = await this.dbOutlet.runQuery(query);
 
         // reformat result to be a array of objects
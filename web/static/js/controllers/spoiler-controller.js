@@ -0,0 +1,17 @@
+import { Controller } from 'https://cdn.jsdelivr.net/npm/@hotwired/stimulus@3.2.2/+esm';
+
+// Reveals ||spoilers|| in comments once they're clicked.
+class SpoilerController extends Controller {
+    reveal(event) {
+        const spoiler = event.target.closest('.spoiler');
+        if (!spoiler || spoiler.classList.contains('spoiler--revealed')) {
+            return;
+        }
+
+        // the first click only reveals, i.e. doesn't follow a link inside
+        event.preventDefault();
+        spoiler.classList.add('spoiler--revealed');
+    }
+}
+
+window.application.register('spoiler', SpoilerController);
@@ -9,8 +9,30 @@ function getCookieByName(name) {
    return null;
 }
 
-if (!window.CORBADO_LOADED) {
-    const projectId = getCookieByName('corbado_project_id');
+function loadBundle() {
+    const stylesheet = document.createElement('link');
+    stylesheet.rel = 'stylesheet';
+    stylesheet.href = 'https://unpkg.com/@corbado/web-js@2/dist/bundle/index.css';
+    stylesheet.crossOrigin = 'anonymous';
+    stylesheet.referrerPolicy = 'no-referrer';
+    document.head.appendChild(stylesheet);
+
+    return new Promise((resolve, reject) => {
+        const script = document.createElement('script');
+        script.src = 'https://unpkg.com/@corbado/web-js@2/dist/bundle/index.js';
+        script.onload = resolve;
+        script.onerror = reject;
+        document.head.appendChild(script);
+    });
+}
+
+// the project cookie is only set when Corbado is the auth provider
+const projectId = getCookieByName('corbado_project_id');
+
+if (projectId && !window.CORBADO_LOADED) {
+    if (!window.Corbado) {
+        await loadBundle();
+    }
 
     await Corbado.load({
         darkMode: 'on',
@@ -19,3 +41,16 @@ if (!window.CORBADO_LOADED) {
 
     window.CORBADO_LOADED = true;
 }
+
+// Corbado keeps its own session too, it has to end before the app's one
+document.addEventListener('submit', async (event) => {
+    const form = event.target;
+    if (!form.matches('.nav__logout') || !window.CORBADO_LOADED) {
+        return;
+    }
+
+    event.preventDefault();
+    event.stopImmediatePropagation();
+    await Corbado.logout();
+    form.submit();
+}, true);
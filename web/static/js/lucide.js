@@ -1,36 +1,87 @@
 import {
     createIcons,
+    Activity,
     AlertCircle,
+    AppWindow,
     BookOpen,
+    Brain,
     Check,
     CheckCircle2,
     ChevronLeft,
+    Copy,
     Database,
+    Download,
     Edit,
+    Eye,
+    EyeOff,
+    Flag,
+    Flame,
+    GraduationCap,
     Heart,
+    Lightbulb,
+    List,
+    LogOut,
+    Medal,
+    Pin,
+    PinOff,
     Plus,
+    Repeat,
     Send,
+    Star,
     TableProperties,
+    Target,
+    ThumbsUp,
     Trash2,
+    TrendingUp,
+    Trophy,
+    Users,
 } from 'https://esm.sh/lucide@0.469.0';
 
 const icons = {
+    Activity,
     AlertCircle,
+    AppWindow,
     BookOpen,
+    Brain,
     Check,
     CheckCircle2,
     ChevronLeft,
+    Copy,
     Database,
+    Download,
     Edit,
+    Eye,
+    EyeOff,
+    Flag,
+    Flame,
+    GraduationCap,
     Heart,
+    Lightbulb,
+    List,
+    LogOut,
+    Medal,
+    Pin,
+    PinOff,
     Plus,
+    Repeat,
     Send,
+    Star,
     TableProperties,
+    Target,
+    ThumbsUp,
     Trash2,
+    TrendingUp,
+    Trophy,
+    Users,
 };
 
 document.documentElement.addEventListener('turbo:render', () => {
     createIcons({icons});
 });
 
+// lazy frames are loaded after the page has been rendered
+document.documentElement.addEventListener('turbo:frame-render', () => {
+    createIcons({icons});
+});
+
 createIcons({icons});